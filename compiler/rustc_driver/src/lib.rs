@@ -18,6 +18,8 @@ use rustc_ast as ast;
 use rustc_codegen_ssa::{traits::CodegenBackend, CodegenResults};
 use rustc_data_structures::profiling::print_time_passes_entry;
 use rustc_data_structures::sync::SeqCst;
+use rustc_errors::emitter::ColorConfig;
+use rustc_errors::explain::{render_explanation, Explanation};
 use rustc_errors::registry::{InvalidErrorCode, Registry};
 use rustc_errors::{ErrorReported, PResult};
 use rustc_feature::{find_gated_cfg, UnstableFeatures};
@@ -31,6 +33,7 @@ use rustc_middle::ty::TyCtxt;
 use rustc_save_analysis as save;
 use rustc_save_analysis::DumpHandler;
 use rustc_serialize::json::{self, ToJson};
+use rustc_serialize::Encodable;
 use rustc_session::config::nightly_options;
 use rustc_session::config::{ErrorOutputType, Input, OutputType, PrintRequest, TrimmedDefPaths};
 use rustc_session::getopts;
@@ -446,7 +449,16 @@ fn run_compiler(
 
             let linker = queries.linker()?;
             Ok(Some(linker))
-        })?;
+        });
+
+        // Also print the profile if the compilation failed, as the evaluation
+        // that failed, e.g. by exceeding the step limit, is often the one of
+        // interest.
+        if sess.opts.debugging_opts.const_eval_profile {
+            sess.code_stats.print_const_eval_profile();
+        }
+
+        let linker = linker?;
 
         if let Some(linker) = linker {
             let _timer = sess.timer("link");
@@ -562,50 +574,97 @@ fn stdout_isatty() -> bool {
 }
 
 fn handle_explain(registry: Registry, code: &str, output: ErrorOutputType) {
+    let json = match output {
+        ErrorOutputType::Json { pretty, .. } => Some(pretty),
+        _ => None,
+    };
+    // `--explain json` prints the explanations of all error codes.
+    if code == "json" {
+        let explanations = registry
+            .codes()
+            .into_iter()
+            .map(|code| Explanation::new(&registry, code).unwrap())
+            .collect::<Vec<_>>();
+        print_json(&explanations, json.unwrap_or(false));
+        return;
+    }
+
     let normalised =
         if code.starts_with('E') { code.to_string() } else { format!("E{0:0>4}", code) };
-    match registry.try_find_description(&normalised) {
-        Ok(Some(description)) => {
-            let mut is_in_code_block = false;
-            let mut text = String::new();
-            // Slice off the leading newline and print.
-            for line in description.lines() {
-                let indent_level =
-                    line.find(|c: char| !c.is_whitespace()).unwrap_or_else(|| line.len());
-                let dedented_line = &line[indent_level..];
-                if dedented_line.starts_with("```") {
-                    is_in_code_block = !is_in_code_block;
-                    text.push_str(&line[..(indent_level + 3)]);
-                } else if is_in_code_block && dedented_line.starts_with("# ") {
-                    continue;
-                } else {
-                    text.push_str(line);
-                }
-                text.push('\n');
-            }
-            if stdout_isatty() {
-                show_content_with_pager(&text);
-            } else {
-                print!("{}", text);
-            }
-        }
-        Ok(None) => {
-            early_error(output, &format!("no extended information for {}", code));
-        }
+    let description = match registry.try_find_description(&normalised) {
+        Ok(description) => description,
         Err(InvalidErrorCode) => {
             early_error(output, &format!("{} is not a valid error code", code));
         }
+    };
+    if let Some(pretty) = json {
+        print_json(&Explanation::new(&registry, &normalised).unwrap(), pretty);
+        return;
+    }
+    let description = match description {
+        Some(description) => description,
+        None => early_error(output, &format!("no extended information for {}", code)),
+    };
+
+    if stdout_isatty() {
+        let pager_name = pager_name();
+        // Only `less` is known to show colors, given `-R`.
+        let color = pager_name == "less"
+            && match output {
+                ErrorOutputType::HumanReadable(kind) => kind.unzip().1 != ColorConfig::Never,
+                _ => false,
+            };
+        let text =
+            rustc_span::with_default_session_globals(|| render_explanation(description, color));
+        show_content_with_pager(&text, pager_name, color);
+    } else {
+        let mut is_in_code_block = false;
+        let mut text = String::new();
+        // Slice off the leading newline and print.
+        for line in description.lines() {
+            let indent_level =
+                line.find(|c: char| !c.is_whitespace()).unwrap_or_else(|| line.len());
+            let dedented_line = &line[indent_level..];
+            if dedented_line.starts_with("```") {
+                is_in_code_block = !is_in_code_block;
+                text.push_str(&line[..(indent_level + 3)]);
+            } else if is_in_code_block && dedented_line.starts_with("# ") {
+                continue;
+            } else {
+                text.push_str(line);
+            }
+            text.push('\n');
+        }
+        print!("{}", text);
     }
 }
 
-fn show_content_with_pager(content: &String) {
-    let pager_name = env::var_os("PAGER").unwrap_or_else(|| {
+fn print_json<T>(value: &T, pretty: bool)
+where
+    T: for<'a> Encodable<json::Encoder<'a>> + for<'a> Encodable<json::PrettyEncoder<'a>>,
+{
+    if pretty {
+        println!("{}", json::as_pretty_json(value));
+    } else {
+        println!("{}", json::as_json(value));
+    }
+}
+
+fn pager_name() -> OsString {
+    env::var_os("PAGER").unwrap_or_else(|| {
         if cfg!(windows) { OsString::from("more.com") } else { OsString::from("less") }
-    });
+    })
+}
 
+fn show_content_with_pager(content: &str, pager_name: OsString, color: bool) {
     let mut fallback_to_println = false;
 
-    match Command::new(pager_name).stdin(Stdio::piped()).spawn() {
+    let mut command = Command::new(pager_name);
+    if color {
+        // Without `-R`, `less` shows the escape sequences of colors.
+        command.arg("-R");
+    }
+    match command.stdin(Stdio::piped()).spawn() {
         Ok(mut pager) => {
             if let Some(pipe) = pager.stdin.as_mut() {
                 if pipe.write_all(content.as_bytes()).is_err() {
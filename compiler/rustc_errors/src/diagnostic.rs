@@ -5,8 +5,10 @@ use crate::Substitution;
 use crate::SubstitutionPart;
 use crate::SuggestionStyle;
 use rustc_lint_defs::Applicability;
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
 use rustc_span::{MultiSpan, Span, DUMMY_SP};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 #[must_use]
 #[derive(Clone, Debug, PartialEq, Hash, Encodable, Decodable)]
@@ -22,6 +24,9 @@ pub struct Diagnostic {
     /// as a sort key to sort a buffer of diagnostics.  By default, it is the primary span of
     /// `span` if there is one.  Otherwise, it is `DUMMY_SP`.
     pub sort_span: Span,
+
+    /// The place in the compiler that gave the diagnostic its error code.
+    pub emitted_at: EmittedAt,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
@@ -30,6 +35,38 @@ pub enum DiagnosticId {
     Lint { name: String, has_future_breakage: bool },
 }
 
+/// The place in the compiler that gave a diagnostic its error code, as
+/// `file:line:column`, if it has one.
+///
+/// This depends on the compiler rather than on the code being compiled, so it
+/// is not part of the identity of a diagnostic: it is ignored when diagnostics
+/// are compared or hashed, e.g. to deduplicate them, and it is not encoded,
+/// e.g. in the incremental cache.
+#[derive(Clone, Debug, Default)]
+pub struct EmittedAt(pub Option<String>);
+
+impl PartialEq for EmittedAt {
+    fn eq(&self, _: &EmittedAt) -> bool {
+        true
+    }
+}
+
+impl Hash for EmittedAt {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl<S: Encoder> Encodable<S> for EmittedAt {
+    fn encode(&self, _: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Decoder> Decodable<D> for EmittedAt {
+    fn decode(_: &mut D) -> Result<EmittedAt, D::Error> {
+        Ok(EmittedAt(None))
+    }
+}
+
 /// For example a note attached to an error.
 #[derive(Clone, Debug, PartialEq, Hash, Encodable, Decodable)]
 pub struct SubDiagnostic {
@@ -91,23 +128,35 @@ impl Diagnostic {
         Diagnostic::new_with_code(level, None, message)
     }
 
+    #[track_caller]
     pub fn new_with_code(level: Level, code: Option<DiagnosticId>, message: &str) -> Self {
-        Diagnostic {
+        let mut diagnostic = Diagnostic {
             level,
             message: vec![(message.to_owned(), Style::NoStyle)],
-            code,
+            code: None,
             span: MultiSpan::new(),
             children: vec![],
             suggestions: vec![],
             sort_span: DUMMY_SP,
+            emitted_at: EmittedAt(None),
+        };
+        if let Some(code) = code {
+            diagnostic.code(code);
         }
+        diagnostic
     }
 
     pub fn is_error(&self) -> bool {
         match self.level {
             Level::Bug | Level::Fatal | Level::Error | Level::FailureNote => true,
 
-            Level::Warning | Level::Note | Level::Help | Level::Cancelled | Level::Allow => false,
+            Level::Warning
+            | Level::ForceWarning
+            | Level::Note
+            | Level::Help
+            | Level::Cancelled
+            | Level::Allow
+            | Level::Expect(_) => false,
         }
     }
 
@@ -510,13 +559,22 @@ impl Diagnostic {
         self
     }
 
+    /// Sets the code of the diagnostic. For error codes, the caller is
+    /// recorded as the place that emits the code, so the functions that
+    /// forward their caller's code are `#[track_caller]` too.
+    #[track_caller]
     pub fn code(&mut self, s: DiagnosticId) -> &mut Self {
+        self.emitted_at = EmittedAt(match s {
+            DiagnosticId::Error(_) => Some(std::panic::Location::caller().to_string()),
+            DiagnosticId::Lint { .. } => None,
+        });
         self.code = Some(s);
         self
     }
 
     pub fn clear_code(&mut self) -> &mut Self {
         self.code = None;
+        self.emitted_at = EmittedAt(None);
         self
     }
 
@@ -376,7 +376,7 @@ impl<'a> DiagnosticBuilder<'a> {
     }
 
     forward!(pub fn set_span<S: Into<MultiSpan>>(&mut self, sp: S) -> &mut Self);
-    forward!(pub fn code(&mut self, s: DiagnosticId) -> &mut Self);
+    forward!(#[track_caller] pub fn code(&mut self, s: DiagnosticId) -> &mut Self);
 
     pub fn allow_suggestions(&mut self, allow: bool) -> &mut Self {
         self.0.allow_suggestions = allow;
@@ -391,6 +391,7 @@ impl<'a> DiagnosticBuilder<'a> {
 
     /// Convenience function for internal use, clients should use one of the
     /// struct_* methods on Handler.
+    #[track_caller]
     crate fn new_with_code(
         handler: &'a Handler,
         level: Level,
@@ -0,0 +1,130 @@
+//! The changes that suggestions make to the source, as line-based diffs.
+//!
+//! This is used to render suggestions as unified diffs in the terminal
+//! (`--error-format=human-diff`) and as inline diffs in HTML
+//! (`--error-format=html`).
+
+use crate::CodeSuggestion;
+
+use rustc_span::source_map::SourceMap;
+use rustc_span::FileName;
+
+use std::ops::Range;
+
+#[cfg(test)]
+mod tests;
+
+/// The lines that one substitution of a suggestion replaces, and the lines
+/// it replaces them with. Lines that the substitution leaves unchanged at the
+/// start and the end of the affected range are not included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hunk {
+    pub file: FileName,
+    /// The 1-based number of the first removed line, or of the line that the
+    /// added lines are inserted before if no line is removed.
+    pub line: usize,
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+impl Hunk {
+    /// Returns the `@@ -l,n +l,m @@` line that starts the hunk in a unified
+    /// diff. `line_number` formats the line numbers.
+    pub fn header(&self, line_number: impl Fn(usize) -> String) -> String {
+        let range = |len: usize| match len {
+            // An empty range is written as the line before it.
+            0 => format!("{},0", line_number(self.line - 1)),
+            1 => line_number(self.line),
+            len => format!("{},{}", line_number(self.line), len),
+        };
+        format!("@@ -{} +{} @@", range(self.removed.len()), range(self.added.len()))
+    }
+
+    /// Returns the pairs of a removed and an added line that the hunk can be
+    /// shown as changing in place, i.e. all of them if it removes as many
+    /// lines as it adds, and none otherwise.
+    pub fn changed_lines(&self) -> impl Iterator<Item = (&str, &str)> {
+        let paired = if self.removed.len() == self.added.len() { self.removed.len() } else { 0 };
+        self.removed.iter().zip(&self.added).take(paired).map(|(old, new)| (&old[..], &new[..]))
+    }
+}
+
+/// Returns a hunk for each substitution of `suggestion`, or `None` in its
+/// place if the substitution cannot be shown as a diff, e.g. because it
+/// changes more than one file or it does not change anything.
+pub fn suggestion_hunks(suggestion: &CodeSuggestion, sm: &SourceMap) -> Vec<Option<Hunk>> {
+    suggestion
+        .substitutions
+        .iter()
+        .map(|substitution| {
+            let mut parts = substitution.parts.clone();
+            parts.sort_by_key(|part| part.span.lo());
+            let lo = parts.first()?.span.lo();
+            let hi = parts.iter().map(|part| part.span.hi()).max()?;
+            if parts.iter().any(|part| sm.is_valid_span(part.span).is_err()) {
+                return None;
+            }
+
+            let file = sm.lookup_source_file(lo);
+            if !file.contains(hi) || !sm.ensure_source_file_source_present(file.clone()) {
+                return None;
+            }
+            let (first, last) = (file.lookup_line(lo)?, file.lookup_line(hi)?);
+            let old = (first..=last)
+                .map(|line| file.get_line(line))
+                .collect::<Option<Vec<_>>>()?
+                .join("\n");
+
+            // Splice the parts into the text of the affected lines.
+            let start = file.line_bounds(first).start;
+            let mut new = String::with_capacity(old.len());
+            let mut pos = 0;
+            for part in &parts {
+                let (part_lo, part_hi) =
+                    ((part.span.lo() - start).0 as usize, (part.span.hi() - start).0 as usize);
+                if part_lo < pos || part_hi > old.len() {
+                    return None;
+                }
+                new.push_str(old.get(pos..part_lo)?);
+                new.push_str(&part.snippet);
+                pos = part_hi;
+            }
+            new.push_str(old.get(pos..)?);
+
+            let mut removed: Vec<String> = old.split('\n').map(String::from).collect();
+            let mut added: Vec<String> = new.split('\n').map(String::from).collect();
+            let mut line = first + 1;
+            while !removed.is_empty() && !added.is_empty() && removed[0] == added[0] {
+                removed.remove(0);
+                added.remove(0);
+                line += 1;
+            }
+            while !removed.is_empty() && removed.last() == added.last() {
+                removed.pop();
+                added.pop();
+            }
+            if removed.is_empty() && added.is_empty() {
+                return None;
+            }
+            Some(Hunk { file: file.name.clone(), line, removed, added })
+        })
+        .collect()
+}
+
+/// Returns the byte ranges of `old` and `new` that differ, leaving out their
+/// common prefix and suffix.
+pub fn changed_ranges(old: &str, new: &str) -> (Range<usize>, Range<usize>) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|&((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((i, _), _)| i);
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    (prefix..old.len() - suffix, prefix..new.len() - suffix)
+}
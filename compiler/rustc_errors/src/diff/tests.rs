@@ -0,0 +1,105 @@
+use super::*;
+
+use crate::{Applicability, Substitution, SubstitutionPart, SuggestionStyle};
+
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Returns a source map with `src` as the file `test.rs`, and a function
+/// making spans from byte offsets in `src`.
+fn source_map(src: &str) -> (SourceMap, impl Fn(u32, u32) -> Span) {
+    let sm = SourceMap::new(FilePathMapping::empty());
+    let file = sm.new_source_file(Path::new("test.rs").to_owned().into(), src.to_owned());
+    let start = file.start_pos;
+    (sm, move |lo, hi| Span::with_root_ctxt(start + BytePos(lo), start + BytePos(hi)))
+}
+
+fn suggestion(substitutions: Vec<Vec<(Span, &str)>>) -> CodeSuggestion {
+    CodeSuggestion {
+        substitutions: substitutions
+            .into_iter()
+            .map(|parts| Substitution {
+                parts: parts
+                    .into_iter()
+                    .map(|(span, snippet)| SubstitutionPart { span, snippet: snippet.to_string() })
+                    .collect(),
+            })
+            .collect(),
+        msg: "msg".to_string(),
+        style: SuggestionStyle::ShowCode,
+        applicability: Applicability::MachineApplicable,
+    }
+}
+
+fn hunk(line: usize, removed: &[&str], added: &[&str]) -> Option<Hunk> {
+    Some(Hunk {
+        file: Path::new("test.rs").to_owned().into(),
+        line,
+        removed: removed.iter().map(|line| line.to_string()).collect(),
+        added: added.iter().map(|line| line.to_string()).collect(),
+    })
+}
+
+#[test]
+fn hunks_of_substitutions() {
+    with_default_session_globals(|| {
+        let src = "fn main() {\n    let x: &str = s;\n    f(x);\n}\n";
+        let (sm, span) = source_map(src);
+
+        let borrow =
+            suggestion(vec![vec![(span(30, 30), "&")], vec![(span(30, 31), "s.as_str()")]]);
+        assert_eq!(
+            suggestion_hunks(&borrow, &sm),
+            vec![
+                hunk(2, &["    let x: &str = s;"], &["    let x: &str = &s;"]),
+                hunk(2, &["    let x: &str = s;"], &["    let x: &str = s.as_str();"]),
+            ]
+        );
+
+        // The parts of a substitution are in the same hunk.
+        let multipart = suggestion(vec![vec![(span(20, 21), "y"), (span(39, 40), "y")]]);
+        assert_eq!(
+            suggestion_hunks(&multipart, &sm),
+            vec![hunk(
+                2,
+                &["    let x: &str = s;", "    f(x);"],
+                &["    let y: &str = s;", "    f(y);"]
+            )]
+        );
+
+        let insert_line = suggestion(vec![vec![(span(0, 0), "use std::fmt;\n\n")]]);
+        assert_eq!(suggestion_hunks(&insert_line, &sm), vec![hunk(1, &[], &["use std::fmt;", ""])]);
+        assert_eq!(
+            hunk(1, &[], &["use std::fmt;", ""]).unwrap().header(|l| l.to_string()),
+            "@@ -0,0 +1,2 @@"
+        );
+
+        let no_change = suggestion(vec![vec![(span(16, 19), "let")]]);
+        assert_eq!(suggestion_hunks(&no_change, &sm), vec![None]);
+    });
+}
+
+#[test]
+fn headers() {
+    let one_line = hunk(2, &["a"], &["b"]).unwrap();
+    assert_eq!(one_line.header(|l| l.to_string()), "@@ -2 +2 @@");
+    assert_eq!(one_line.header(|_| "LL".to_string()), "@@ -LL +LL @@");
+
+    let removal = hunk(4, &["a", "b"], &[]).unwrap();
+    assert_eq!(removal.header(|l| l.to_string()), "@@ -4,2 +3,0 @@");
+}
+
+#[test]
+fn changed_ranges_of_lines() {
+    assert_eq!(changed_ranges("let x = s;", "let x = &s;"), (8..8, 8..9));
+    assert_eq!(changed_ranges("let mut x = 0;", "let x = 0;"), (4..8, 4..4));
+    assert_eq!(changed_ranges("abc", "abc"), (3..3, 3..3));
+    assert_eq!(changed_ranges("é = 1", "è = 1"), (0..2, 0..2));
+}
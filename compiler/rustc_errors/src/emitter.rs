@@ -13,6 +13,8 @@ use rustc_lint_defs::FutureBreakage;
 use rustc_span::source_map::SourceMap;
 use rustc_span::{MultiSpan, SourceFile, Span};
 
+use crate::diff;
+use crate::grouping::DiagnosticGroup;
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, Style, StyledString};
 use crate::styled_buffer::StyledBuffer;
 use crate::{CodeSuggestion, Diagnostic, DiagnosticId, Level, SubDiagnostic, SuggestionStyle};
@@ -41,6 +43,11 @@ pub enum HumanReadableErrorType {
     Default(ColorConfig),
     AnnotateSnippet(ColorConfig),
     Short(ColorConfig),
+    /// Like `Default`, but suggestions are rendered as unified diffs.
+    Diff(ColorConfig),
+    /// HTML, see `HtmlEmitter`. Text rendered with this type, like the
+    /// `rendered` field of the json output, is rendered like `Default`.
+    Html,
 }
 
 impl HumanReadableErrorType {
@@ -50,6 +57,8 @@ impl HumanReadableErrorType {
             HumanReadableErrorType::Default(cc) => (false, cc),
             HumanReadableErrorType::Short(cc) => (true, cc),
             HumanReadableErrorType::AnnotateSnippet(cc) => (false, cc),
+            HumanReadableErrorType::Diff(cc) => (false, cc),
+            HumanReadableErrorType::Html => (false, ColorConfig::Never),
         }
     }
     pub fn new_emitter(
@@ -63,6 +72,7 @@ impl HumanReadableErrorType {
         let (short, color_config) = self.unzip();
         let color = color_config.suggests_using_colors();
         EmitterWriter::new(dst, source_map, short, teach, color, terminal_width, macro_backtrace)
+            .diff_suggestions(matches!(self, HumanReadableErrorType::Diff(_)))
     }
 }
 
@@ -195,6 +205,21 @@ pub trait Emitter {
 
     fn emit_future_breakage_report(&mut self, _diags: Vec<(FutureBreakage, Diagnostic)>) {}
 
+    /// Emit a group of similar diagnostics (see `-Z group-diagnostics`).
+    /// By default, the first diagnostic of the group is emitted, with a note
+    /// about the other ones.
+    fn emit_diagnostic_group(&mut self, group: &DiagnosticGroup) {
+        let diag = group.to_diagnostic(self.source_map().map(|sm| &**sm));
+        self.emit_diagnostic(&diag);
+    }
+
+    /// Emit the summary that ends the diagnostics of a compilation, like
+    /// "aborting due to previous error". By default, it is emitted like the
+    /// other diagnostics.
+    fn emit_summary(&mut self, diag: &Diagnostic) {
+        self.emit_diagnostic(diag);
+    }
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
@@ -480,7 +505,12 @@ impl Emitter for EmitterWriter {
 
     fn emit_diagnostic(&mut self, diag: &Diagnostic) {
         let mut children = diag.children.clone();
-        let (mut primary_span, suggestions) = self.primary_span_formatted(&diag);
+        let (mut primary_span, suggestions) = if self.diff_suggestions {
+            // Diffs are never shown inline.
+            (diag.span.clone(), &diag.suggestions[..])
+        } else {
+            self.primary_span_formatted(&diag)
+        };
         debug!("emit_diagnostic: suggestions={:?}", suggestions);
 
         self.fix_multispans_in_extern_macros_and_render_macro_backtrace(
@@ -556,7 +586,8 @@ impl ColorConfig {
     }
 }
 
-/// Handles the writing of `HumanReadableErrorType::Default` and `HumanReadableErrorType::Short`
+/// Handles the writing of `HumanReadableErrorType::Default`, `HumanReadableErrorType::Short` and
+/// `HumanReadableErrorType::Diff`
 pub struct EmitterWriter {
     dst: Destination,
     sm: Option<Lrc<SourceMap>>,
@@ -566,6 +597,8 @@ pub struct EmitterWriter {
     terminal_width: Option<usize>,
 
     macro_backtrace: bool,
+    /// Render suggestions as unified diffs.
+    diff_suggestions: bool,
 }
 
 #[derive(Debug)]
@@ -593,6 +626,7 @@ impl EmitterWriter {
             ui_testing: false,
             terminal_width,
             macro_backtrace,
+            diff_suggestions: false,
         }
     }
 
@@ -613,6 +647,7 @@ impl EmitterWriter {
             ui_testing: false,
             terminal_width,
             macro_backtrace,
+            diff_suggestions: false,
         }
     }
 
@@ -621,6 +656,11 @@ impl EmitterWriter {
         self
     }
 
+    pub fn diff_suggestions(mut self, diff_suggestions: bool) -> Self {
+        self.diff_suggestions = diff_suggestions;
+        self
+    }
+
     fn maybe_anonymized(&self, line_num: usize) -> String {
         if self.ui_testing { ANONYMIZED_LINE_NUM.to_string() } else { line_num.to_string() }
     }
@@ -1681,6 +1721,74 @@ impl EmitterWriter {
         Ok(())
     }
 
+    /// Renders each substitution of the suggestion as a unified diff:
+    ///
+    /// ```text
+    /// help: consider borrowing here
+    /// --- src/main.rs
+    /// +++ src/main.rs
+    /// @@ -3 +3 @@
+    /// -    let x: &str = s;
+    /// +    let x: &str = &s;
+    /// ```
+    fn emit_suggestion_diff(
+        &mut self,
+        suggestion: &CodeSuggestion,
+        level: &Level,
+        max_line_num_len: usize,
+    ) -> io::Result<()> {
+        let sm = match self.sm {
+            Some(ref sm) => sm,
+            None => return Ok(()),
+        };
+
+        let hunks =
+            diff::suggestion_hunks(suggestion, &**sm).into_iter().flatten().collect::<Vec<_>>();
+        if hunks.is_empty() {
+            // The suggestion is either invalid or has no visible effect, like the ones that
+            // `emit_suggestion_default` ignores.
+            return Ok(());
+        }
+
+        let mut buffer = StyledBuffer::new();
+
+        // Render the suggestion message
+        buffer.append(0, level.to_str(), Style::Level(*level));
+        buffer.append(0, ": ", Style::HeaderMsg);
+
+        self.msg_to_buffer(
+            &mut buffer,
+            &[(suggestion.msg.to_owned(), Style::NoStyle)],
+            max_line_num_len,
+            "suggestion",
+            Some(Style::HeaderMsg),
+        );
+
+        let mut row_num = buffer.num_lines();
+        for hunk in hunks.iter().take(MAX_SUGGESTIONS) {
+            buffer.puts(row_num, 0, &format!("--- {}", hunk.file), Style::Removal);
+            buffer.puts(row_num + 1, 0, &format!("+++ {}", hunk.file), Style::Addition);
+            let header = hunk.header(|line| self.maybe_anonymized(line));
+            buffer.puts(row_num + 2, 0, &header, Style::LineNumber);
+            row_num += 3;
+            for line in &hunk.removed {
+                buffer.puts(row_num, 0, &format!("-{}", line), Style::Removal);
+                row_num += 1;
+            }
+            for line in &hunk.added {
+                buffer.puts(row_num, 0, &format!("+{}", line), Style::Addition);
+                row_num += 1;
+            }
+        }
+        if hunks.len() > MAX_SUGGESTIONS {
+            let others = hunks.len() - MAX_SUGGESTIONS;
+            let msg = format!("and {} other candidate{}", others, pluralize!(others));
+            buffer.puts(row_num, 0, &msg, Style::NoStyle);
+        }
+        emit_to_destination(&buffer.render(), level, &mut self.dst, self.short_message)?;
+        Ok(())
+    }
+
     fn emit_messages_default(
         &mut self,
         level: &Level,
@@ -1743,11 +1851,16 @@ impl EmitterWriter {
                             ) {
                                 panic!("failed to emit error: {}", e);
                             }
-                        } else if let Err(e) =
-                            self.emit_suggestion_default(sugg, &Level::Help, max_line_num_len)
-                        {
-                            panic!("failed to emit error: {}", e);
-                        };
+                        } else {
+                            let result = if self.diff_suggestions {
+                                self.emit_suggestion_diff(sugg, &Level::Help, max_line_num_len)
+                            } else {
+                                self.emit_suggestion_default(sugg, &Level::Help, max_line_num_len)
+                            };
+                            if let Err(e) = result {
+                                panic!("failed to emit error: {}", e);
+                            }
+                        }
                     }
                 }
             }
@@ -2100,6 +2213,12 @@ impl<'a> WritableDst<'a> {
             Style::Highlight => {
                 spec.set_bold(true);
             }
+            Style::Addition => {
+                spec.set_fg(Some(Color::Green)).set_intense(true);
+            }
+            Style::Removal => {
+                spec.set_fg(Some(Color::Red)).set_intense(true);
+            }
         }
         self.set_color(&spec)
     }
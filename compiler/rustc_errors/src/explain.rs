@@ -0,0 +1,228 @@
+//! The explanations of error codes, for `rustc --explain`.
+//!
+//! On a terminal, the Markdown of an explanation is rendered with colors:
+//! headings in bold, inline code and code blocks in color, with the Rust
+//! code blocks syntax highlighted and without the lines that rustdoc hides.
+//! For tools, `Explanation` is the JSON form of an explanation, with the raw
+//! Markdown.
+
+use crate::html::token_class;
+use crate::registry::{InvalidErrorCode, Registry};
+
+use std::io;
+use termcolor::{Buffer, Color, ColorSpec, WriteColor};
+
+#[cfg(test)]
+mod tests;
+
+/// The explanation of an error code, as printed by `rustc --explain` with
+/// `--error-format=json`.
+#[derive(Encodable)]
+pub struct Explanation {
+    /// The error code, e.g. `E0308`.
+    code: String,
+    /// The long-form explanation in Markdown, if the code has one.
+    explanation: Option<&'static str>,
+}
+
+impl Explanation {
+    pub fn new(registry: &Registry, code: &str) -> Result<Explanation, InvalidErrorCode> {
+        Ok(Explanation {
+            code: code.to_string(),
+            explanation: registry.try_find_description(code)?,
+        })
+    }
+}
+
+/// Renders the explanation `description` of an error code for a terminal.
+/// Without `color`, only the layout of the Markdown is rendered.
+pub fn render_explanation(description: &str, color: bool) -> String {
+    let mut buffer = if color { Buffer::ansi() } else { Buffer::no_color() };
+    render(&mut buffer, description).unwrap();
+    String::from_utf8(buffer.into_inner()).unwrap()
+}
+
+fn render(dst: &mut dyn WriteColor, description: &str) -> io::Result<()> {
+    // Whether the current line is in a code block, and if so, whether the
+    // code block is Rust code.
+    let mut code_block = None;
+    for line in description.lines() {
+        let dedented = line.trim_start();
+        if let Some(info) = dedented.strip_prefix("```") {
+            code_block = match code_block {
+                Some(_) => None,
+                None => Some(is_rust(info)),
+            };
+        } else if let Some(rust) = code_block {
+            if rust && (dedented == "#" || dedented.starts_with("# ")) {
+                continue;
+            }
+            if line.is_empty() {
+                writeln!(dst)?;
+                continue;
+            }
+            write!(dst, "    ")?;
+            if rust {
+                render_rust(dst, line)?;
+            } else {
+                dst.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+                write!(dst, "{}", line)?;
+                dst.reset()?;
+            }
+            writeln!(dst)?;
+        } else if let Some(heading) = heading(line) {
+            render_heading(dst, heading, line.starts_with("# "))?;
+        } else {
+            // The bullets of list items are not emphasis.
+            let item = dedented.strip_prefix("* ").or_else(|| dedented.strip_prefix("- "));
+            if let Some(item) = item {
+                write!(dst, "{}- ", &line[..line.len() - dedented.len()])?;
+                render_inline(dst, item)?;
+            } else {
+                render_inline(dst, line)?;
+            }
+            writeln!(dst)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether a code block with the info string `info` is Rust code,
+/// i.e. whether its attributes are all rustdoc's, e.g. `compile_fail,E0308`
+/// or `ignore (reason)`.
+fn is_rust(info: &str) -> bool {
+    info.split(',').all(|attr| {
+        let attr = attr.trim().split(|c: char| c.is_whitespace() || c == '(').next().unwrap();
+        matches!(
+            attr,
+            "" | "rust"
+                | "compile_fail"
+                | "ignore"
+                | "no_run"
+                | "should_panic"
+                | "edition2015"
+                | "edition2018"
+                | "edition2021"
+        ) || (attr.starts_with('E') && attr[1..].bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// Returns the text of `line` if it is an ATX heading, e.g. `## Examples`.
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    if (1..=6).contains(&level) && (text.is_empty() || text.starts_with(' ')) {
+        Some(text.trim())
+    } else {
+        None
+    }
+}
+
+/// Renders a heading in bold, and a top-level heading also underlined.
+fn render_heading(dst: &mut dyn WriteColor, text: &str, top_level: bool) -> io::Result<()> {
+    dst.set_color(ColorSpec::new().set_bold(true).set_underline(top_level))?;
+    write!(dst, "{}", text)?;
+    dst.reset()?;
+    writeln!(dst)
+}
+
+/// Renders a line of a paragraph: inline code in color, strong emphasis in
+/// bold, emphasis and the text of links underlined, and inline links
+/// followed by their URL.
+fn render_inline(dst: &mut dyn WriteColor, line: &str) -> io::Result<()> {
+    let mut strong = false;
+    let mut emphasis = false;
+    let mut rest = line;
+    while !rest.is_empty() {
+        let end = rest.find(|c| matches!(c, '`' | '*' | '[')).unwrap_or(rest.len());
+        write_styled(dst, &rest[..end], strong, emphasis)?;
+        rest = &rest[end..];
+
+        if rest.starts_with('`') {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let after = &rest[ticks..];
+            if let Some(code_end) = after.find(&rest[..ticks]) {
+                dst.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+                write!(dst, "{}", after[..code_end].trim())?;
+                dst.reset()?;
+                rest = &after[code_end + ticks..];
+            } else {
+                write_styled(dst, &rest[..ticks], strong, emphasis)?;
+                rest = after;
+            }
+        } else if let Some(after) = rest.strip_prefix("**") {
+            strong = !strong;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            emphasis = !emphasis;
+            rest = after;
+        } else if let Some((text, url, after)) = link(rest) {
+            write_styled(dst, text, strong, true)?;
+            if let Some(url) = url {
+                write_styled(dst, &format!(" ({})", url), strong, emphasis)?;
+            }
+            rest = after;
+        } else if !rest.is_empty() {
+            write_styled(dst, &rest[..1], strong, emphasis)?;
+            rest = &rest[1..];
+        }
+    }
+    Ok(())
+}
+
+/// Parses an inline link `[text](url)` or a reference link `[text][label]`
+/// at the start of `s`, returning its text, its URL if it is inline, and the
+/// rest of `s`.
+fn link(s: &str) -> Option<(&str, Option<&str>, &str)> {
+    let text_end = s.find(']')?;
+    let text = &s[1..text_end];
+    let after = &s[text_end + 1..];
+    if let Some(after) = after.strip_prefix('(') {
+        let url_end = after.find(')')?;
+        Some((text, Some(&after[..url_end]), &after[url_end + 1..]))
+    } else if let Some(after) = after.strip_prefix('[') {
+        let label_end = after.find(']')?;
+        Some((text, None, &after[label_end + 1..]))
+    } else {
+        None
+    }
+}
+
+fn write_styled(
+    dst: &mut dyn WriteColor,
+    text: &str,
+    strong: bool,
+    emphasis: bool,
+) -> io::Result<()> {
+    if strong || emphasis {
+        dst.set_color(ColorSpec::new().set_bold(strong).set_underline(emphasis))?;
+        write!(dst, "{}", text)?;
+        dst.reset()
+    } else {
+        write!(dst, "{}", text)
+    }
+}
+
+/// Renders a line of Rust code, highlighted like the HTML emitter does.
+fn render_rust(dst: &mut dyn WriteColor, line: &str) -> io::Result<()> {
+    let mut pos = 0;
+    for token in rustc_lexer::tokenize(line) {
+        let text = &line[pos..pos + token.len];
+        pos += token.len;
+        let color = match token_class(token.kind, text) {
+            Some("kw") => Color::Magenta,
+            Some("string") => Color::Green,
+            Some("number") => Color::Yellow,
+            Some("lifetime") => Color::Cyan,
+            Some("comment") => Color::Blue,
+            _ => {
+                write!(dst, "{}", text)?;
+                continue;
+            }
+        };
+        dst.set_color(ColorSpec::new().set_fg(Some(color)))?;
+        write!(dst, "{}", text)?;
+        dst.reset()?;
+    }
+    Ok(())
+}
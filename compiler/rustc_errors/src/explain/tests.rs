@@ -0,0 +1,59 @@
+use super::*;
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+const DESCRIPTION: &str = "\
+A *trait* was `implemented` twice.
+
+Erroneous code example:
+
+```compile_fail,E0119
+# trait Foo {}
+struct Bar;
+impl Foo for Bar {} // first
+```
+
+* See the [reference](https://doc.rust-lang.org/reference) and [the book][book].
+
+```text
+# not hidden
+```
+
+# Notes
+";
+
+#[test]
+fn layout() {
+    with_default_session_globals(|| {
+        assert_eq!(
+            render_explanation(DESCRIPTION, false),
+            "A trait was implemented twice.\n\
+             \n\
+             Erroneous code example:\n\
+             \n\
+             \x20   struct Bar;\n\
+             \x20   impl Foo for Bar {} // first\n\
+             \n\
+             - See the reference (https://doc.rust-lang.org/reference) and the book.\n\
+             \n\
+             \x20   # not hidden\n\
+             \n\
+             Notes\n"
+        );
+    });
+}
+
+#[test]
+fn colors() {
+    with_default_session_globals(|| {
+        assert_eq!(
+            render_explanation("# E0001\n`a` **b**\n```\nfn\n```", true),
+            "\x1b[0m\x1b[1m\x1b[4mE0001\x1b[0m\n\
+             \x1b[0m\x1b[36ma\x1b[0m \x1b[0m\x1b[1mb\x1b[0m\n\
+             \x20   \x1b[0m\x1b[35mfn\x1b[0m\n"
+        );
+    });
+}
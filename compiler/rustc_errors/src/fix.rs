@@ -0,0 +1,228 @@
+//! Applying suggestions to the source files, for `-Z apply-suggestions`.
+//!
+//! With the flag, the handler keeps every suggestion of the emitted
+//! diagnostics whose `Applicability` is at least as certain as requested.
+//! When the session ends, the kept suggestions are resolved against the
+//! source map, and the ones that can be applied are spliced into the
+//! source files, which are then written back.
+//!
+//! Resolution is deterministic: suggestions are considered in source order,
+//! and a suggestion is skipped if it overlaps one that was accepted before
+//! it. A suggestion is also skipped if it offers several alternatives, if it
+//! points outside of the local source files or into more than one file, or
+//! if its file was changed on disk while compiling.
+
+use crate::{Applicability, CodeSuggestion, SubstitutionPart};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, SourceFile, Span};
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+/// Returns whether a suggestion is applied with
+/// `-Z apply-suggestions=<threshold>`, where `threshold` is the least
+/// certain applicability that is accepted.
+pub fn is_accepted(applicability: Applicability, threshold: Applicability) -> bool {
+    match applicability {
+        Applicability::MachineApplicable => true,
+        Applicability::MaybeIncorrect => threshold == Applicability::MaybeIncorrect,
+        Applicability::HasPlaceholders | Applicability::Unspecified => false,
+    }
+}
+
+/// A suggestion kept for application.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fix {
+    /// The message of the suggestion, e.g. "remove this `mut`".
+    pub msg: String,
+    /// The parts of each substitution of the suggestion. Only suggestions
+    /// with a single substitution can be applied.
+    pub alternatives: Vec<Vec<SubstitutionPart>>,
+}
+
+impl Fix {
+    pub fn new(suggestion: &CodeSuggestion) -> Fix {
+        Fix {
+            msg: suggestion.msg.clone(),
+            alternatives: suggestion.substitutions.iter().map(|s| s.parts.clone()).collect(),
+        }
+    }
+
+    /// Returns the span of the first part of the suggestion, to point at in
+    /// the report.
+    pub fn span(&self) -> Option<Span> {
+        self.alternatives.iter().flatten().map(|part| part.span).min_by_key(|span| span.lo())
+    }
+}
+
+/// Why a suggestion was not applied.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SkipReason {
+    /// The suggestion offers more than one alternative.
+    Ambiguous,
+    /// The suggestion changes code that is not in a local source file, e.g.
+    /// code from another crate or from a macro expansion.
+    NotLocal,
+    /// The suggestion changes more than one file.
+    MultipleFiles,
+    /// The suggestion overlaps a suggestion that is applied.
+    Overlaps,
+    /// The file changed on disk after it was read by the compiler.
+    FileChanged,
+    /// Reading or writing the file failed.
+    Io(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Ambiguous => write!(f, "the suggestion has more than one alternative"),
+            SkipReason::NotLocal => write!(f, "the suggestion changes code outside of this crate"),
+            SkipReason::MultipleFiles => write!(f, "the suggestion changes more than one file"),
+            SkipReason::Overlaps => write!(f, "the suggestion overlaps another suggestion"),
+            SkipReason::FileChanged => write!(f, "the file changed while compiling"),
+            SkipReason::Io(err) => write!(f, "failed to update the file: {}", err),
+        }
+    }
+}
+
+/// A replacement of the bytes `start..end` of a file, as it is on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+impl Edit {
+    fn overlaps(&self, other: &Edit) -> bool {
+        // Two insertions at the same position conflict too, as the order
+        // of the inserted snippets would be arbitrary.
+        (self.start < other.end && other.start < self.end)
+            || (self.start == self.end && other.start == other.end && self.start == other.start)
+    }
+}
+
+/// The accepted suggestions for one source file.
+pub struct FileFixes {
+    pub file: Lrc<SourceFile>,
+    pub path: PathBuf,
+    pub fixes: Vec<(Fix, Vec<Edit>)>,
+}
+
+impl FileFixes {
+    /// Returns `src` with the edits of all suggestions applied.
+    pub fn splice(&self, src: &str) -> String {
+        let mut edits = self.fixes.iter().flat_map(|(_, edits)| edits).collect::<Vec<_>>();
+        edits.sort_by_key(|edit| (edit.start, edit.end));
+
+        let mut result = String::with_capacity(src.len());
+        let mut pos = 0;
+        for edit in edits {
+            result.push_str(&src[pos..edit.start]);
+            result.push_str(&edit.replacement);
+            pos = edit.end;
+        }
+        result.push_str(&src[pos..]);
+        result
+    }
+
+    /// Applies the suggestions to the file on disk.
+    pub fn apply(&self) -> Result<(), SkipReason> {
+        let src = fs::read_to_string(&self.path).map_err(|e| SkipReason::Io(e.to_string()))?;
+        if !self.file.src_hash.matches(&src) {
+            return Err(SkipReason::FileChanged);
+        }
+        fs::write(&self.path, self.splice(&src)).map_err(|e| SkipReason::Io(e.to_string()))
+    }
+}
+
+/// The outcome of resolving the kept suggestions.
+pub struct Resolution {
+    /// The accepted suggestions, grouped by file, in source order.
+    pub files: Vec<FileFixes>,
+    pub skipped: Vec<(Fix, SkipReason)>,
+}
+
+/// Decides which of `fixes` are applied, and the edits they make.
+pub fn resolve(fixes: Vec<Fix>, sm: &SourceMap) -> Resolution {
+    // The same suggestion may be kept more than once, e.g. for a lint that
+    // fires on every expansion of a macro. It is only applied once.
+    let mut unique: Vec<Fix> = Vec::with_capacity(fixes.len());
+    for fix in fixes {
+        if !unique.contains(&fix) {
+            unique.push(fix);
+        }
+    }
+    // The sort is stable, so suggestions starting at the same position are
+    // considered in the order in which they were emitted.
+    unique.sort_by_key(|fix| fix.span().map(|span| (span.lo(), span.hi())));
+
+    let mut files: FxIndexMap<PathBuf, FileFixes> = Default::default();
+    let mut skipped = vec![];
+    for fix in unique {
+        match edits(&fix, sm) {
+            Ok((file, path, edits)) => {
+                let file_fixes = files.entry(path.clone()).or_insert_with(|| FileFixes {
+                    file,
+                    path,
+                    fixes: vec![],
+                });
+                let overlaps = file_fixes.fixes.iter().any(|(_, accepted)| {
+                    accepted.iter().any(|a| edits.iter().any(|edit| edit.overlaps(a)))
+                });
+                if overlaps {
+                    skipped.push((fix, SkipReason::Overlaps));
+                } else {
+                    file_fixes.fixes.push((fix, edits));
+                }
+            }
+            Err(reason) => skipped.push((fix, reason)),
+        }
+    }
+
+    Resolution { files: files.into_iter().map(|(_, file)| file).collect(), skipped }
+}
+
+/// Returns the file that `fix` changes, and its edits in terms of the bytes
+/// of that file as it is on disk.
+fn edits(fix: &Fix, sm: &SourceMap) -> Result<(Lrc<SourceFile>, PathBuf, Vec<Edit>), SkipReason> {
+    let parts = match &fix.alternatives[..] {
+        [parts] => parts,
+        _ => return Err(SkipReason::Ambiguous),
+    };
+    let span = fix.span().ok_or(SkipReason::NotLocal)?;
+    if span.is_dummy() {
+        return Err(SkipReason::NotLocal);
+    }
+
+    let file = sm.lookup_source_file(span.lo());
+    // Remapped paths (`--remap-path-prefix`) may not exist on this machine.
+    let path = match file.unmapped_path.as_ref().unwrap_or(&file.name) {
+        FileName::Real(name) if !file.is_imported() => name.local_path().to_path_buf(),
+        _ => return Err(SkipReason::NotLocal),
+    };
+
+    let mut edits = Vec::with_capacity(parts.len());
+    for part in parts {
+        if part.span.is_dummy() || part.span.from_expansion() {
+            return Err(SkipReason::NotLocal);
+        }
+        if !file.contains(part.span.lo()) || !file.contains(part.span.hi()) {
+            return Err(SkipReason::MultipleFiles);
+        }
+        edits.push(Edit {
+            start: file.original_relative_byte_pos(part.span.lo()).0 as usize,
+            end: file.original_relative_byte_pos(part.span.hi()).0 as usize,
+            replacement: part.snippet.clone(),
+        });
+    }
+    Ok((file, path, edits))
+}
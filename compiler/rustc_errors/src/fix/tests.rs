@@ -0,0 +1,107 @@
+use super::*;
+
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::BytePos;
+
+use std::path::Path;
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Returns a source map with `src` as the file `test.rs`, and a function
+/// making spans from the byte offsets in `src` after normalization.
+fn source_map(src: &str) -> (SourceMap, impl Fn(u32, u32) -> Span) {
+    let sm = SourceMap::new(FilePathMapping::empty());
+    let file = sm.new_source_file(Path::new("test.rs").to_owned().into(), src.to_owned());
+    let start = file.start_pos;
+    (sm, move |lo, hi| Span::with_root_ctxt(start + BytePos(lo), start + BytePos(hi)))
+}
+
+fn fix(msg: &str, alternatives: Vec<Vec<(Span, &str)>>) -> Fix {
+    Fix {
+        msg: msg.to_string(),
+        alternatives: alternatives
+            .into_iter()
+            .map(|parts| {
+                parts
+                    .into_iter()
+                    .map(|(span, snippet)| SubstitutionPart { span, snippet: snippet.to_string() })
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn accepted_applicabilities() {
+    use Applicability::*;
+
+    assert!(is_accepted(MachineApplicable, MachineApplicable));
+    assert!(!is_accepted(MaybeIncorrect, MachineApplicable));
+    assert!(is_accepted(MachineApplicable, MaybeIncorrect));
+    assert!(is_accepted(MaybeIncorrect, MaybeIncorrect));
+    assert!(!is_accepted(HasPlaceholders, MaybeIncorrect));
+    assert!(!is_accepted(Unspecified, MaybeIncorrect));
+}
+
+#[test]
+fn overlapping_suggestions_are_skipped() {
+    with_default_session_globals(|| {
+        let src = "let mut x = y;\n";
+        let (sm, span) = source_map(src);
+
+        let remove_mut = fix("remove this `mut`", vec![vec![(span(4, 8), "")]]);
+        let rename = fix("rename the binding", vec![vec![(span(4, 9), "_x")]]);
+        let convert = fix("convert it", vec![vec![(span(12, 13), "y.into()")]]);
+        let resolution = resolve(
+            vec![convert.clone(), rename.clone(), remove_mut.clone(), convert.clone()],
+            &sm,
+        );
+
+        assert_eq!(resolution.skipped, vec![(rename, SkipReason::Overlaps)]);
+        assert_eq!(resolution.files.len(), 1);
+        let file = &resolution.files[0];
+        assert_eq!(file.path, Path::new("test.rs"));
+        assert_eq!(
+            file.fixes.iter().map(|(fix, _)| fix.clone()).collect::<Vec<_>>(),
+            vec![remove_mut, convert]
+        );
+        assert_eq!(file.splice(src), "let x = y.into();\n");
+    });
+}
+
+#[test]
+fn ambiguous_suggestions_are_skipped() {
+    with_default_session_globals(|| {
+        let (sm, span) = source_map("x.len();\n");
+
+        let methods = fix(
+            "there is a method with a similar name",
+            vec![vec![(span(2, 5), "length")], vec![(span(2, 5), "size")]],
+        );
+        let resolution = resolve(vec![methods.clone()], &sm);
+
+        assert!(resolution.files.is_empty());
+        assert_eq!(resolution.skipped, vec![(methods, SkipReason::Ambiguous)]);
+    });
+}
+
+#[test]
+fn edits_use_offsets_on_disk() {
+    with_default_session_globals(|| {
+        // The source map removes the BOM and the carriage returns, so the
+        // offsets of its spans are off from those in the file.
+        let src = "\u{feff}fn f() {\r\n    let mut x = 0;\r\n}\r\n";
+        let (sm, span) = source_map(src);
+
+        let remove_mut = fix("remove this `mut`", vec![vec![(span(17, 21), "")]]);
+        let resolution = resolve(vec![remove_mut], &sm);
+
+        assert!(resolution.skipped.is_empty());
+        let file = &resolution.files[0];
+        assert_eq!(file.fixes[0].1, vec![Edit { start: 21, end: 25, replacement: String::new() }]);
+        assert_eq!(file.splice(src), "\u{feff}fn f() {\r\n    let x = 0;\r\n}\r\n");
+    });
+}
@@ -0,0 +1,121 @@
+//! Grouping of similar diagnostics, for `-Z group-diagnostics`.
+//!
+//! An error in generic code or in a macro is often reported many times with
+//! the same root cause, once for every use of that code. With the flag, the
+//! handler holds back the errors and warnings that have a code, and clusters
+//! them by their code and the origin of their primary span: the place in the
+//! source that the span points to, along with the definition sites of the
+//! macros that the span was expanded from. When the session ends, each group
+//! is emitted once, as its first diagnostic with a note giving the number of
+//! diagnostics in the group and the locations of the other ones.
+//!
+//! The location of a diagnostic is the outermost macro call site of its
+//! primary span, i.e. the place in the user's code that it is reported for.
+
+use crate::{Diagnostic, DiagnosticId, Level};
+
+use rustc_span::hygiene::SyntaxContext;
+use rustc_span::source_map::SourceMap;
+use rustc_span::Span;
+
+#[cfg(test)]
+mod tests;
+
+/// The number of other locations listed in the note of a group. The JSON
+/// representation of a group lists all of them.
+const MAX_LISTED_LOCATIONS: usize = 10;
+
+/// What the diagnostics of a group have in common.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroupKey {
+    is_error: bool,
+    code: DiagnosticId,
+    /// The primary span, without its expansion information.
+    origin: Span,
+    /// The definition sites of the macros that the primary span was expanded
+    /// from, innermost first.
+    def_sites: Vec<Span>,
+}
+
+impl GroupKey {
+    /// Returns the key of the group of `diag`, or `None` if it cannot be
+    /// grouped: only errors and warnings with a code and a primary span are.
+    pub fn new(diag: &Diagnostic) -> Option<GroupKey> {
+        if !matches!(diag.level, Level::Error | Level::Warning | Level::ForceWarning) {
+            return None;
+        }
+        let code = diag.code.clone()?;
+        let span = diag.span.primary_span().filter(|span| !span.is_dummy())?;
+        Some(GroupKey {
+            is_error: diag.is_error(),
+            code,
+            origin: span.with_ctxt(SyntaxContext::root()),
+            def_sites: span.macro_backtrace().map(|expn| expn.def_site).collect(),
+        })
+    }
+}
+
+/// Diagnostics with the same `GroupKey`.
+#[derive(Clone, Debug)]
+pub struct DiagnosticGroup {
+    /// The first diagnostic of the group, which is emitted for all of them.
+    pub representative: Diagnostic,
+    /// The number of diagnostics in the group, including the representative.
+    pub count: usize,
+    /// The locations of the other diagnostics of the group, in the order in
+    /// which they were emitted, without duplicates or the location of the
+    /// representative.
+    pub locations: Vec<Span>,
+}
+
+impl DiagnosticGroup {
+    pub fn new(representative: Diagnostic) -> DiagnosticGroup {
+        DiagnosticGroup { representative, count: 1, locations: vec![] }
+    }
+
+    pub fn add(&mut self, diag: &Diagnostic) {
+        self.count += 1;
+        let location = match diag.span.primary_span() {
+            Some(span) => span.source_callsite(),
+            None => return,
+        };
+        if Some(location) != self.location() && !self.locations.contains(&location) {
+            self.locations.push(location);
+        }
+    }
+
+    /// Returns the location of the representative.
+    pub fn location(&self) -> Option<Span> {
+        self.representative.span.primary_span().map(|span| span.source_callsite())
+    }
+
+    /// Returns the representative, with a note about the rest of the group
+    /// if it has more than one diagnostic.
+    pub fn to_diagnostic(&self, sm: Option<&SourceMap>) -> Diagnostic {
+        let mut diag = self.representative.clone();
+        if self.count == 1 {
+            return diag;
+        }
+
+        let mut note = format!(
+            "this {} was reported {} times with the same origin",
+            diag.level.to_str(),
+            self.count
+        );
+        if let (Some(sm), false) = (sm, self.locations.is_empty()) {
+            note.push_str(", also at:");
+            for &location in self.locations.iter().take(MAX_LISTED_LOCATIONS) {
+                let loc = sm.lookup_char_pos(location.lo());
+                note.push_str(&format!("\n{}:{}:{}", loc.file.name, loc.line, loc.col.0 + 1));
+            }
+            if self.locations.len() > MAX_LISTED_LOCATIONS {
+                note.push_str(&format!(
+                    "\nand {} other locations",
+                    self.locations.len() - MAX_LISTED_LOCATIONS
+                ));
+            }
+        }
+        diag.note(&note);
+        diag
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::BytePos;
+
+use std::path::Path;
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Returns a source map with `src` as the file `test.rs`, and a function
+/// making spans from byte offsets in `src`.
+fn source_map(src: &str) -> (SourceMap, impl Fn(u32, u32) -> Span) {
+    let sm = SourceMap::new(FilePathMapping::empty());
+    let file = sm.new_source_file(Path::new("test.rs").to_owned().into(), src.to_owned());
+    let start = file.start_pos;
+    (sm, move |lo, hi| Span::with_root_ctxt(start + BytePos(lo), start + BytePos(hi)))
+}
+
+fn diagnostic(level: Level, code: Option<&str>, msg: &str, span: Span) -> Diagnostic {
+    let mut diag = Diagnostic::new(level, msg);
+    diag.code = code.map(|code| DiagnosticId::Error(code.to_string()));
+    diag.set_span(span);
+    diag
+}
+
+#[test]
+fn keys() {
+    with_default_session_globals(|| {
+        let (_sm, span) = source_map("let x: u8 = y;\nlet z: u8 = w;\n");
+        let key = |level, code, span| GroupKey::new(&diagnostic(level, code, "msg", span));
+
+        assert_eq!(
+            key(Level::Error, Some("E0308"), span(12, 13)),
+            key(Level::Error, Some("E0308"), span(12, 13))
+        );
+        assert_ne!(
+            key(Level::Error, Some("E0308"), span(12, 13)),
+            key(Level::Error, Some("E0277"), span(12, 13))
+        );
+        assert_ne!(
+            key(Level::Error, Some("E0308"), span(12, 13)),
+            key(Level::Error, Some("E0308"), span(27, 28))
+        );
+        assert_ne!(
+            key(Level::Error, Some("E0308"), span(12, 13)),
+            key(Level::Warning, Some("E0308"), span(12, 13))
+        );
+        assert_eq!(key(Level::Error, None, span(12, 13)), None);
+        assert_eq!(key(Level::Note, Some("E0308"), span(12, 13)), None);
+        assert_eq!(key(Level::Error, Some("E0308"), rustc_span::DUMMY_SP), None);
+    });
+}
+
+#[test]
+fn representative_notes_the_other_locations() {
+    with_default_session_globals(|| {
+        let (sm, span) = source_map("f(a);\nf(b);\nf(c);\n");
+        let error =
+            |lo| diagnostic(Level::Error, Some("E0277"), "the bound is not met", span(lo, lo + 4));
+
+        let mut group = DiagnosticGroup::new(error(0));
+        assert_eq!(group.to_diagnostic(Some(&sm)), error(0));
+
+        group.add(&error(6));
+        group.add(&error(0));
+        group.add(&error(12));
+        group.add(&error(6));
+        assert_eq!(group.count, 5);
+        assert_eq!(group.locations, vec![span(6, 10), span(12, 16)]);
+
+        let diag = group.to_diagnostic(Some(&sm));
+        assert_eq!(diag.message(), "the bound is not met");
+        assert_eq!(
+            diag.children.iter().map(|child| child.message()).collect::<Vec<_>>(),
+            vec![
+                "this error was reported 5 times with the same origin, also at:\n\
+                 test.rs:2:1\n\
+                 test.rs:3:1"
+            ]
+        );
+
+        let diag = group.to_diagnostic(None);
+        assert_eq!(
+            diag.children.iter().map(|child| child.message()).collect::<Vec<_>>(),
+            vec!["this error was reported 5 times with the same origin"]
+        );
+    });
+}
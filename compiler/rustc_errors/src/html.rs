@@ -0,0 +1,385 @@
+//! An HTML emitter for errors.
+//!
+//! This renders diagnostics for web pages, e.g. the comments of a code review
+//! bot. The output is a complete HTML document with a small stylesheet, in
+//! which each diagnostic is a `<div class="diagnostic">` holding its message,
+//! the snippets of code that its spans point at, and its sub-diagnostics.
+//!
+//! Snippets are syntax highlighted with `rustc_lexer`, using the CSS classes
+//! of rustdoc's highlighting (`kw`, `string`, `number`, `comment` and
+//! `lifetime`). The code that a span label points at is wrapped in
+//! `<mark class="primary">` or `<mark class="secondary">`, and the labels are
+//! written below its line. Suggestions are shown as inline diffs: removed
+//! lines in `<del>`, added lines in `<ins>`, and when a suggestion changes
+//! lines in place, the changed part of each line in `<mark class="change">`.
+
+use crate::diff;
+use crate::emitter::{Emitter, FileWithAnnotatedLines};
+use crate::snippet::Style;
+use crate::{CodeSuggestion, Diagnostic, DiagnosticId, Level, SubDiagnostic, SuggestionStyle};
+
+use rustc_data_structures::sync::Lrc;
+use rustc_lexer::{LiteralKind, TokenKind};
+use rustc_span::source_map::SourceMap;
+use rustc_span::symbol::Ident;
+use rustc_span::MultiSpan;
+
+use std::io::{self, Write};
+use std::ops::Range;
+
+#[cfg(test)]
+mod tests;
+
+const ANONYMIZED_LINE_NUM: &str = "LL";
+
+const STYLESHEET: &str = "\
+body { font-family: sans-serif; }
+.diagnostic { margin: 1em 0; }
+.diagnostic > p, .child > p { margin: 0.25em 0; }
+.error > p > .level { color: #c00; }
+.warning > p > .level { color: #b60; }
+.note > p > .level, .help > p > .level { color: #06c; }
+.child { margin-left: 2em; }
+pre { background: #f6f8fa; padding: 0.5em; margin: 0.25em 0; }
+.location { color: #555; }
+.line-number { display: inline-block; min-width: 4ch; margin-right: 1ch; \
+    text-align: right; color: #888; user-select: none; }
+mark.primary { background: #fdd; }
+mark.secondary { background: #ddf; }
+.label.primary { color: #c00; }
+.label.secondary { color: #06c; }
+del { display: block; background: #fee; text-decoration: none; }
+ins { display: block; background: #efe; text-decoration: none; }
+del > mark.change { background: #fbb; }
+ins > mark.change { background: #bfb; }
+.kw { color: #8959a8; }
+.string { color: #718c00; }
+.number { color: #c82829; }
+.comment { color: #8e908c; }
+.lifetime { color: #b76514; }
+";
+
+/// Writes diagnostics as HTML, for `--error-format=html`.
+pub struct HtmlEmitter {
+    dst: Box<dyn Write + Send>,
+    sm: Option<Lrc<SourceMap>>,
+    ui_testing: bool,
+    macro_backtrace: bool,
+    /// Whether the start of the document has been written. It is written
+    /// with the first diagnostic, and the end of the document is written
+    /// when the emitter is dropped, so nothing is written without diagnostics.
+    started: bool,
+}
+
+impl HtmlEmitter {
+    pub fn stderr(source_map: Option<Lrc<SourceMap>>, macro_backtrace: bool) -> Self {
+        HtmlEmitter::new(Box::new(io::BufWriter::new(io::stderr())), source_map, macro_backtrace)
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        source_map: Option<Lrc<SourceMap>>,
+        macro_backtrace: bool,
+    ) -> Self {
+        HtmlEmitter { dst, sm: source_map, ui_testing: false, macro_backtrace, started: false }
+    }
+
+    /// If `ui_testing` is true, line numbers are written as `LL` to prevent
+    /// noise in UI test diffs.
+    pub fn ui_testing(mut self, ui_testing: bool) -> Self {
+        self.ui_testing = ui_testing;
+        self
+    }
+
+    fn line_number(&self, line: usize) -> String {
+        if self.ui_testing { ANONYMIZED_LINE_NUM.to_string() } else { line.to_string() }
+    }
+
+    /// Renders the message of a diagnostic or sub-diagnostic, with its level.
+    fn render_message(
+        &self,
+        out: &mut String,
+        level: Level,
+        code: Option<&DiagnosticId>,
+        message: &[(String, Style)],
+    ) {
+        out.push_str("<p><span class=\"level\">");
+        out.push_str(&escape(level.to_str()));
+        if let Some(DiagnosticId::Error(code)) = code {
+            out.push_str(&format!("[{}]", escape(code)));
+        }
+        out.push_str("</span>: ");
+        for (text, style) in message {
+            match style {
+                Style::Highlight => out.push_str(&format!("<strong>{}</strong>", escape(text))),
+                _ => out.push_str(&escape(text)),
+            }
+        }
+        out.push_str("</p>\n");
+    }
+
+    /// Renders the lines that the labels of `msp` point at, grouped by file.
+    fn render_snippets(&self, out: &mut String, msp: &MultiSpan) {
+        let sm = match &self.sm {
+            Some(sm) => sm,
+            None => return,
+        };
+        let primary_lo = msp.primary_span().map(|span| sm.lookup_char_pos(span.lo()));
+        for annotated_file in FileWithAnnotatedLines::collect_annotations(msp, &self.sm) {
+            out.push_str("<pre class=\"snippet\">");
+            let location = match &primary_lo {
+                Some(lo) if lo.file.name == annotated_file.file.name => {
+                    format!("{}:{}:{}", lo.file.name, lo.line, lo.col.0 + 1)
+                }
+                _ => annotated_file.file.name.to_string(),
+            };
+            out.push_str(&format!("<span class=\"location\">{}</span>\n", escape(&location)));
+
+            for line in &annotated_file.lines {
+                let source = match annotated_file.file.get_line(line.line_index - 1) {
+                    Some(source) => source,
+                    None => continue,
+                };
+                let mut annotations = line
+                    .annotations
+                    .iter()
+                    .filter(|annotation| !annotation.is_line())
+                    .collect::<Vec<_>>();
+                // Marks of primary labels win over secondary ones where they overlap.
+                annotations
+                    .sort_by_key(|annotation| (!annotation.is_primary, annotation.start_col));
+                let marks = annotations
+                    .iter()
+                    .map(|annotation| {
+                        let range = char_range(&source, annotation.start_col..annotation.end_col);
+                        (range, if annotation.is_primary { "primary" } else { "secondary" })
+                    })
+                    .collect::<Vec<_>>();
+
+                out.push_str(&format!(
+                    "<span class=\"line-number\">{}</span>{}\n",
+                    self.line_number(line.line_index),
+                    highlight(&source, &marks)
+                ));
+
+                annotations.sort_by_key(|annotation| annotation.start_col);
+                for annotation in annotations {
+                    let label = match &annotation.label {
+                        Some(label) => label,
+                        None => continue,
+                    };
+                    let class = if annotation.is_primary { "primary" } else { "secondary" };
+                    let underline = if annotation.is_primary { "^" } else { "-" };
+                    let width = annotation.end_col.saturating_sub(annotation.start_col).max(1);
+                    out.push_str(&format!(
+                        "<span class=\"line-number\"></span>{}<span class=\"label {}\">{} {}</span>\n",
+                        " ".repeat(annotation.start_col),
+                        class,
+                        underline.repeat(width),
+                        escape(label)
+                    ));
+                }
+            }
+            out.push_str("</pre>\n");
+        }
+    }
+
+    /// Renders a suggestion as an inline diff for each of its substitutions.
+    fn render_suggestion(&self, out: &mut String, suggestion: &CodeSuggestion) {
+        out.push_str("<div class=\"child help\">\n");
+        self.render_message(out, Level::Help, None, &[(suggestion.msg.clone(), Style::NoStyle)]);
+        let sm = match &self.sm {
+            Some(sm) if suggestion.style != SuggestionStyle::HideCodeAlways => sm,
+            _ => {
+                out.push_str("</div>\n");
+                return;
+            }
+        };
+        for hunk in diff::suggestion_hunks(suggestion, sm).into_iter().flatten() {
+            let changes = hunk
+                .changed_lines()
+                .map(|(old, new)| diff::changed_ranges(old, new))
+                .collect::<Vec<_>>();
+            out.push_str("<pre class=\"diff\">");
+            out.push_str(&format!(
+                "<span class=\"location\">{}</span>\n",
+                escape(&hunk.file.to_string())
+            ));
+            for (i, line) in hunk.removed.iter().enumerate() {
+                let marks = changes.get(i).map(|(old, _)| vec![(old.clone(), "change")]);
+                out.push_str(&format!(
+                    "<del><span class=\"line-number\">{}</span>-{}</del>",
+                    self.line_number(hunk.line + i),
+                    highlight(line, marks.as_deref().unwrap_or_default())
+                ));
+            }
+            for (i, line) in hunk.added.iter().enumerate() {
+                let marks = changes.get(i).map(|(_, new)| vec![(new.clone(), "change")]);
+                out.push_str(&format!(
+                    "<ins><span class=\"line-number\">{}</span>+{}</ins>",
+                    self.line_number(hunk.line + i),
+                    highlight(line, marks.as_deref().unwrap_or_default())
+                ));
+            }
+            out.push_str("</pre>\n");
+        }
+        out.push_str("</div>\n");
+    }
+
+    fn render_child(&self, out: &mut String, child: &SubDiagnostic) {
+        out.push_str(&format!("<div class=\"child {}\">\n", level_class(child.level)));
+        self.render_message(out, child.level, None, &child.styled_message());
+        let span = child.render_span.as_ref().unwrap_or(&child.span);
+        if span.primary_span().is_some() {
+            self.render_snippets(out, span);
+        }
+        out.push_str("</div>\n");
+    }
+
+    fn write(&mut self, html: &str) -> io::Result<()> {
+        self.dst.write_all(html.as_bytes())?;
+        self.dst.flush()
+    }
+}
+
+impl Emitter for HtmlEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        let mut children = diag.children.clone();
+        let mut primary_span = diag.span.clone();
+        self.fix_multispans_in_extern_macros_and_render_macro_backtrace(
+            &self.sm,
+            &mut primary_span,
+            &mut children,
+            &diag.level,
+            self.macro_backtrace,
+        );
+
+        let mut out = String::new();
+        if !self.started {
+            out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+            out.push_str("<title>rustc diagnostics</title>\n");
+            out.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", STYLESHEET));
+            self.started = true;
+        }
+        out.push_str(&format!("<div class=\"diagnostic {}\">\n", level_class(diag.level)));
+        self.render_message(&mut out, diag.level, diag.code.as_ref(), &diag.styled_message());
+        self.render_snippets(&mut out, &primary_span);
+        for child in &children {
+            self.render_child(&mut out, child);
+        }
+        for suggestion in &diag.suggestions {
+            if suggestion.style != SuggestionStyle::CompletelyHidden {
+                self.render_suggestion(&mut out, suggestion);
+            }
+        }
+        out.push_str("</div>\n");
+        if let Err(e) = self.write(&out) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        self.sm.as_ref()
+    }
+}
+
+impl Drop for HtmlEmitter {
+    fn drop(&mut self) {
+        // This can run while unwinding from a fatal error, where panicking
+        // would abort, so the error is only reported.
+        if self.started {
+            if let Err(e) = self.write("</body>\n</html>\n") {
+                let _ = writeln!(io::stderr(), "failed to print diagnostics: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Returns the CSS class for diagnostics of `level`.
+fn level_class(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error | Level::Cancelled | Level::FailureNote => "error",
+        Level::Warning | Level::ForceWarning => "warning",
+        Level::Help => "help",
+        Level::Note | Level::Allow | Level::Expect(_) => "note",
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Converts a range of characters of `line` to a range of bytes.
+fn char_range(line: &str, chars: Range<usize>) -> Range<usize> {
+    let byte = |col| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+    byte(chars.start)..byte(chars.end)
+}
+
+/// Returns the CSS class that `rustc_lexer` tokens of `kind` are highlighted with.
+pub(crate) fn token_class(kind: TokenKind, text: &str) -> Option<&'static str> {
+    match kind {
+        TokenKind::LineComment { .. } | TokenKind::BlockComment { .. } => Some("comment"),
+        TokenKind::Ident if Ident::from_str(text).is_reserved() => Some("kw"),
+        TokenKind::Literal { kind, .. } => match kind {
+            LiteralKind::Int { .. } | LiteralKind::Float { .. } => Some("number"),
+            _ => Some("string"),
+        },
+        TokenKind::Lifetime { .. } => Some("lifetime"),
+        _ => None,
+    }
+}
+
+/// Returns `line` as HTML, with its tokens highlighted and the byte ranges
+/// of `marks` wrapped in a `<mark>` of their class. Where marks overlap, the
+/// first one wins.
+fn highlight(line: &str, marks: &[(Range<usize>, &'static str)]) -> String {
+    let mark_at = |pos: usize| marks.iter().find(|(range, _)| range.contains(&pos)).map(|m| m.1);
+
+    let mut out = String::with_capacity(line.len());
+    let mut open_mark = None;
+    let mut pos = 0;
+    for token in rustc_lexer::tokenize(line) {
+        let text = &line[pos..pos + token.len];
+        let class = token_class(token.kind, text);
+        // Split the token where marks start or end.
+        let mut start = pos;
+        while start < pos + token.len {
+            let mark = mark_at(start);
+            let end = (start + 1..pos + token.len)
+                .find(|&end| line.is_char_boundary(end) && mark_at(end) != mark)
+                .unwrap_or(pos + token.len);
+            if mark != open_mark {
+                if open_mark.is_some() {
+                    out.push_str("</mark>");
+                }
+                if let Some(mark) = mark {
+                    out.push_str(&format!("<mark class=\"{}\">", mark));
+                }
+                open_mark = mark;
+            }
+            let segment = escape(&line[start..end]);
+            match class {
+                Some(class) => {
+                    out.push_str(&format!("<span class=\"{}\">{}</span>", class, segment))
+                }
+                None => out.push_str(&segment),
+            }
+            start = end;
+        }
+        pos += token.len;
+    }
+    if open_mark.is_some() {
+        out.push_str("</mark>");
+    }
+    out
+}
@@ -0,0 +1,111 @@
+use super::*;
+
+use crate::{Applicability, Substitution, SubstitutionPart};
+
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Returns a source map with `src` as the file `test.rs`, and a function
+/// making spans from byte offsets in `src`.
+fn source_map(src: &str) -> (SourceMap, impl Fn(u32, u32) -> Span) {
+    let sm = SourceMap::new(FilePathMapping::empty());
+    let file = sm.new_source_file(Path::new("test.rs").to_owned().into(), src.to_owned());
+    let start = file.start_pos;
+    (sm, move |lo, hi| Span::with_root_ctxt(start + BytePos(lo), start + BytePos(hi)))
+}
+
+#[derive(Clone)]
+struct Shared<T>(Arc<Mutex<T>>);
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(escape("a < b && \"c\" > d"), "a &lt; b &amp;&amp; &quot;c&quot; &gt; d");
+}
+
+#[test]
+fn highlighting() {
+    with_default_session_globals(|| {
+        assert_eq!(
+            highlight("let s = \"<\"; // 1", &[]),
+            "<span class=\"kw\">let</span> s = <span class=\"string\">&quot;&lt;&quot;</span>; \
+             <span class=\"comment\">// 1</span>"
+        );
+        // Marks split tokens, and the first of overlapping marks wins.
+        assert_eq!(
+            highlight("f(xyz, 2)", &[(3..4, "primary"), (2..9, "secondary")]),
+            "f(<mark class=\"secondary\">x</mark><mark class=\"primary\">y</mark>\
+             <mark class=\"secondary\">z, <span class=\"number\">2</span>)</mark>"
+        );
+    });
+}
+
+#[test]
+fn document() {
+    with_default_session_globals(|| {
+        let src = "fn main() {\n    let x: &str = s;\n}\n";
+        let (sm, span) = source_map(src);
+        let output = Shared(Arc::new(Mutex::new(Vec::new())));
+
+        let mut emitter =
+            HtmlEmitter::new(Box::new(output.clone()), Some(Lrc::new(sm)), false).ui_testing(true);
+        let mut diag = Diagnostic::new(Level::Error, "mismatched types");
+        diag.code(DiagnosticId::Error("E0308".to_string()));
+        diag.set_span(span(30, 31));
+        diag.span.push_span_label(span(30, 31), "expected `&str`, found `String`".to_string());
+        diag.suggestions.push(CodeSuggestion {
+            substitutions: vec![Substitution {
+                parts: vec![SubstitutionPart { span: span(30, 30), snippet: "&".to_string() }],
+            }],
+            msg: "consider borrowing here".to_string(),
+            style: SuggestionStyle::ShowCode,
+            applicability: Applicability::MachineApplicable,
+        });
+        emitter.emit_diagnostic(&diag);
+        drop(emitter);
+
+        let html = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let body = &html[html.find("<body>").unwrap()..];
+        assert_eq!(
+            body,
+            "<body>\n\
+             <div class=\"diagnostic error\">\n\
+             <p><span class=\"level\">error[E0308]</span>: mismatched types</p>\n\
+             <pre class=\"snippet\"><span class=\"location\">test.rs:2:19</span>\n\
+             <span class=\"line-number\">LL</span>    <span class=\"kw\">let</span> x: &amp;str = \
+             <mark class=\"primary\">s</mark>;\n\
+             <span class=\"line-number\"></span>                  \
+             <span class=\"label primary\">^ expected `&amp;str`, found `String`</span>\n\
+             </pre>\n\
+             <div class=\"child help\">\n\
+             <p><span class=\"level\">help</span>: consider borrowing here</p>\n\
+             <pre class=\"diff\"><span class=\"location\">test.rs</span>\n\
+             <del><span class=\"line-number\">LL</span>-    <span class=\"kw\">let</span> \
+             x: &amp;str = s;</del>\
+             <ins><span class=\"line-number\">LL</span>+    <span class=\"kw\">let</span> \
+             x: &amp;str = <mark class=\"change\">&amp;</mark>s;</ins>\
+             </pre>\n\
+             </div>\n\
+             </div>\n\
+             </body>\n\
+             </html>\n"
+        );
+    });
+}
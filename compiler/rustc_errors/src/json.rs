@@ -12,6 +12,7 @@
 use rustc_span::source_map::{FilePathMapping, SourceMap};
 
 use crate::emitter::{Emitter, HumanReadableErrorType};
+use crate::grouping;
 use crate::registry::Registry;
 use crate::DiagnosticId;
 use crate::{CodeSuggestion, SubDiagnostic};
@@ -136,7 +137,7 @@ impl Emitter for JsonEmitter {
         let data: Vec<FutureBreakageItem> = diags
             .into_iter()
             .map(|(breakage, mut diag)| {
-                if diag.level == crate::Level::Allow {
+                if matches!(diag.level, crate::Level::Allow | crate::Level::Expect(_)) {
                     diag.level = crate::Level::Warning;
                 }
                 FutureBreakageItem {
@@ -157,6 +158,21 @@ impl Emitter for JsonEmitter {
         }
     }
 
+    fn emit_diagnostic_group(&mut self, group: &grouping::DiagnosticGroup) {
+        let diag = group.to_diagnostic(Some(&self.sm));
+        let data =
+            GroupedDiagnostic::new(Diagnostic::from_errors_diagnostic(&diag, self), group, self);
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", as_pretty_json(&data))
+        } else {
+            writeln!(&mut self.dst, "{}", as_json(&data))
+        }
+        .and_then(|_| self.dst.flush());
+        if let Err(e) = result {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+
     fn source_map(&self) -> Option<&Lrc<SourceMap>> {
         Some(&self.sm)
     }
@@ -182,6 +198,29 @@ struct Diagnostic {
     rendered: Option<String>,
 }
 
+/// A diagnostic standing for a group of similar diagnostics
+/// (`-Z group-diagnostics`). It has the fields of `Diagnostic`, followed by
+/// `group`.
+#[derive(Encodable)]
+struct GroupedDiagnostic {
+    message: String,
+    code: Option<DiagnosticCode>,
+    level: &'static str,
+    spans: Vec<DiagnosticSpan>,
+    children: Vec<Diagnostic>,
+    rendered: Option<String>,
+    group: DiagnosticGroup,
+}
+
+#[derive(Encodable)]
+struct DiagnosticGroup {
+    /// The number of diagnostics in the group, including this one.
+    count: usize,
+    /// Where the other diagnostics of the group were reported, i.e. the
+    /// outermost macro call sites of their primary spans.
+    locations: Vec<DiagnosticSpan>,
+}
+
 #[derive(Encodable)]
 struct DiagnosticSpan {
     file_name: String,
@@ -331,6 +370,26 @@ impl Diagnostic {
     }
 }
 
+impl GroupedDiagnostic {
+    fn new(diag: Diagnostic, group: &grouping::DiagnosticGroup, je: &JsonEmitter) -> Self {
+        let Diagnostic { message, code, level, spans, children, rendered } = diag;
+        let locations = group
+            .locations
+            .iter()
+            .map(|&span| DiagnosticSpan::from_span_etc(span, true, None, None, je))
+            .collect();
+        GroupedDiagnostic {
+            message,
+            code,
+            level,
+            spans,
+            children,
+            rendered,
+            group: DiagnosticGroup { count: group.count, locations },
+        }
+    }
+}
+
 impl DiagnosticSpan {
     fn from_span_label(
         span: SpanLabel,
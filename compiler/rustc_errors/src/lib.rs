@@ -21,7 +21,7 @@ use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
-use rustc_lint_defs::FutureBreakage;
+use rustc_lint_defs::{FutureBreakage, LintExpectationId};
 pub use rustc_lint_defs::{pluralize, Applicability};
 use rustc_span::source_map::SourceMap;
 use rustc_span::{Loc, MultiSpan, Span};
@@ -36,13 +36,21 @@ use termcolor::{Color, ColorSpec};
 pub mod annotate_snippet_emitter_writer;
 mod diagnostic;
 mod diagnostic_builder;
+pub mod diff;
 pub mod emitter;
+pub mod explain;
+pub mod fix;
+pub mod grouping;
+pub mod html;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
+pub mod translation;
 pub use snippet::Style;
+pub use translation::Catalog;
 
 pub type PResult<'a, T> = Result<T, DiagnosticBuilder<'a>>;
 
@@ -251,7 +259,9 @@ impl fmt::Display for ExplicitBug {
 
 impl error::Error for ExplicitBug {}
 
-pub use diagnostic::{Diagnostic, DiagnosticId, DiagnosticStyledString, SubDiagnostic};
+pub use diagnostic::{
+    Diagnostic, DiagnosticId, DiagnosticStyledString, EmittedAt, SubDiagnostic,
+};
 pub use diagnostic_builder::DiagnosticBuilder;
 
 /// A handler deals with errors and other compiler output.
@@ -260,6 +270,9 @@ pub use diagnostic_builder::DiagnosticBuilder;
 pub struct Handler {
     flags: HandlerFlags,
     inner: Lock<HandlerInner>,
+    /// The catalog to translate keyed messages with.
+    /// (rustc: see `-Z translation-locale`)
+    catalog: Option<Catalog>,
 }
 
 /// This inner struct exists to keep it all behind a single lock;
@@ -301,6 +314,20 @@ struct HandlerInner {
     deduplicated_warn_count: usize,
 
     future_breakage_diagnostics: Vec<Diagnostic>,
+
+    /// The expectations (`#[expect]` attributes) for which at least one lint
+    /// was emitted at `Level::Expect`.
+    fulfilled_expectations: FxHashSet<LintExpectationId>,
+
+    /// The suggestions to apply at the end of the session.
+    /// (rustc: see `-Z apply-suggestions`)
+    suggestion_fixes: Vec<fix::Fix>,
+
+    /// Diagnostics held back to be emitted once for each group of similar
+    /// diagnostics, at the end of the session. They are only counted towards
+    /// the error count that is reported to the user when they are emitted.
+    /// (rustc: see `-Z group-diagnostics`)
+    diagnostic_groups: FxIndexMap<grouping::GroupKey, grouping::DiagnosticGroup>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -333,11 +360,20 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If set, suggestions at least as certain as this are applied to the
+    /// source files at the end of the session.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub apply_suggestions: Option<Applicability>,
+    /// If true, diagnostics with the same code and origin are reported once,
+    /// with the locations of the others, at the end of the session.
+    /// (rustc: see `-Z group-diagnostics`)
+    pub group_diagnostics: bool,
 }
 
 impl Drop for HandlerInner {
     fn drop(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_diagnostic_groups();
 
         if !self.has_errors() {
             let bugs = std::mem::replace(&mut self.delayed_span_bugs, Vec::new());
@@ -415,10 +451,26 @@ impl Handler {
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                fulfilled_expectations: Default::default(),
+                suggestion_fixes: Vec::new(),
+                diagnostic_groups: Default::default(),
             }),
+            catalog: None,
         }
     }
 
+    /// Sets the catalog that `translate` looks up messages in.
+    pub fn with_catalog(self, catalog: Option<Catalog>) -> Self {
+        Self { catalog, ..self }
+    }
+
+    /// Returns the message `key` from the catalog of `-Z translation-locale`
+    /// with the given arguments, or `None` if there is no catalog or it does
+    /// not have the message. Callers fall back to the English message then.
+    pub fn translate(&self, key: &str, args: &[(&str, String)]) -> Option<String> {
+        self.catalog.as_ref()?.format(key, args)
+    }
+
     // This is here to not allow mutation of flags;
     // as of this writing it's only used in tests in librustc_middle.
     pub fn can_emit_warnings(&self) -> bool {
@@ -444,6 +496,9 @@ impl Handler {
         inner.emitted_diagnostic_codes = Default::default();
         inner.emitted_diagnostics = Default::default();
         inner.stashed_diagnostics = Default::default();
+        inner.fulfilled_expectations = Default::default();
+        inner.suggestion_fixes = Default::default();
+        inner.diagnostic_groups = Default::default();
     }
 
     /// Stash a given diagnostic with the given `Span` and `StashKey` as the key for later stealing.
@@ -497,6 +552,7 @@ impl Handler {
 
     /// Construct a builder at the `Warning` level at the given `span` and with the `msg`.
     /// Also include a code.
+    #[track_caller]
     pub fn struct_span_warn_with_code(
         &self,
         span: impl Into<MultiSpan>,
@@ -517,11 +573,49 @@ impl Handler {
         result
     }
 
+    /// Construct a builder at the `ForceWarning` level at the given `span` and with the `msg`.
+    pub fn struct_span_force_warn(
+        &self,
+        span: impl Into<MultiSpan>,
+        msg: &str,
+    ) -> DiagnosticBuilder<'_> {
+        let mut result = self.struct_force_warn(msg);
+        result.set_span(span);
+        result
+    }
+
+    /// Construct a builder at the `ForceWarning` level with the `msg`.
+    ///
+    /// Unlike `struct_warn`, the diagnostic is emitted even if warnings are
+    /// disabled, e.g. with `-A warnings`.
+    pub fn struct_force_warn(&self, msg: &str) -> DiagnosticBuilder<'_> {
+        DiagnosticBuilder::new(self, Level::ForceWarning, msg)
+    }
+
     /// Construct a builder at the `Allow` level with the `msg`.
     pub fn struct_allow(&self, msg: &str) -> DiagnosticBuilder<'_> {
         DiagnosticBuilder::new(self, Level::Allow, msg)
     }
 
+    /// Construct a builder at the `Expect` level at the given `span` and with the `msg`.
+    pub fn struct_span_expect(
+        &self,
+        span: impl Into<MultiSpan>,
+        msg: &str,
+        id: LintExpectationId,
+    ) -> DiagnosticBuilder<'_> {
+        let mut result = self.struct_expect(msg, id);
+        result.set_span(span);
+        result
+    }
+
+    /// Construct a builder at the `Expect` level with the `msg`.
+    ///
+    /// Emitting it prints nothing, but marks the expectation `id` as fulfilled.
+    pub fn struct_expect(&self, msg: &str, id: LintExpectationId) -> DiagnosticBuilder<'_> {
+        DiagnosticBuilder::new(self, Level::Expect(id), msg)
+    }
+
     /// Construct a builder at the `Error` level at the given `span` and with the `msg`.
     pub fn struct_span_err(&self, span: impl Into<MultiSpan>, msg: &str) -> DiagnosticBuilder<'_> {
         let mut result = self.struct_err(msg);
@@ -530,6 +624,7 @@ impl Handler {
     }
 
     /// Construct a builder at the `Error` level at the given `span`, with the `msg`, and `code`.
+    #[track_caller]
     pub fn struct_span_err_with_code(
         &self,
         span: impl Into<MultiSpan>,
@@ -548,6 +643,7 @@ impl Handler {
     }
 
     /// Construct a builder at the `Error` level with the `msg` and the `code`.
+    #[track_caller]
     pub fn struct_err_with_code(&self, msg: &str, code: DiagnosticId) -> DiagnosticBuilder<'_> {
         let mut result = self.struct_err(msg);
         result.code(code);
@@ -566,6 +662,7 @@ impl Handler {
     }
 
     /// Construct a builder at the `Fatal` level at the given `span`, with the `msg`, and `code`.
+    #[track_caller]
     pub fn struct_span_fatal_with_code(
         &self,
         span: impl Into<MultiSpan>,
@@ -597,6 +694,7 @@ impl Handler {
         FatalError
     }
 
+    #[track_caller]
     pub fn span_fatal_with_code(
         &self,
         span: impl Into<MultiSpan>,
@@ -611,6 +709,7 @@ impl Handler {
         self.emit_diag_at_span(Diagnostic::new(Error, msg), span);
     }
 
+    #[track_caller]
     pub fn span_err_with_code(&self, span: impl Into<MultiSpan>, msg: &str, code: DiagnosticId) {
         self.emit_diag_at_span(Diagnostic::new_with_code(Error, Some(code), msg), span);
     }
@@ -619,6 +718,7 @@ impl Handler {
         self.emit_diag_at_span(Diagnostic::new(Warning, msg), span);
     }
 
+    #[track_caller]
     pub fn span_warn_with_code(&self, span: impl Into<MultiSpan>, msg: &str, code: DiagnosticId) {
         self.emit_diag_at_span(Diagnostic::new_with_code(Warning, Some(code), msg), span);
     }
@@ -731,6 +831,49 @@ impl Handler {
     pub fn delay_as_bug(&self, diagnostic: Diagnostic) {
         self.inner.borrow_mut().delay_as_bug(diagnostic)
     }
+
+    /// Returns the expectations fulfilled by diagnostics emitted so far,
+    /// forgetting about them.
+    pub fn steal_fulfilled_expectation_ids(&self) -> FxHashSet<LintExpectationId> {
+        std::mem::take(&mut self.inner.borrow_mut().fulfilled_expectations)
+    }
+
+    /// Applies the suggestions of the diagnostics emitted so far to the
+    /// source files, if requested with `-Z apply-suggestions`, and reports
+    /// which files were changed and which suggestions were skipped.
+    pub fn apply_suggestions(&self, sm: &SourceMap) {
+        let fixes = std::mem::take(&mut self.inner.borrow_mut().suggestion_fixes);
+        if fixes.is_empty() {
+            return;
+        }
+
+        let fix::Resolution { files, mut skipped } = fix::resolve(fixes, sm);
+        for file in files {
+            match file.apply() {
+                Ok(()) => {
+                    let count = file.fixes.len();
+                    self.note_without_error(&format!(
+                        "applied {} suggestion{} to `{}`",
+                        count,
+                        pluralize!(count),
+                        file.path.display(),
+                    ));
+                }
+                Err(reason) => {
+                    skipped.extend(file.fixes.into_iter().map(|(fix, _)| (fix, reason.clone())))
+                }
+            }
+        }
+
+        for (fix, reason) in skipped {
+            let mut diag = Diagnostic::new(Note, &format!("suggestion not applied: {}", fix.msg));
+            if let Some(span) = fix.span() {
+                diag.set_span(span);
+            }
+            diag.note(&reason.to_string());
+            self.emit_diagnostic(&diag);
+        }
+    }
 }
 
 impl HandlerInner {
@@ -748,6 +891,18 @@ impl HandlerInner {
         diags.iter().for_each(|diag| self.emit_diagnostic(diag));
     }
 
+    /// Emit the diagnostics held back for `-Z group-diagnostics`, once per group.
+    fn emit_diagnostic_groups(&mut self) {
+        for (_, group) in self.diagnostic_groups.drain(..) {
+            self.emitter.emit_diagnostic_group(&group);
+            if group.representative.is_error() {
+                self.deduplicated_err_count += 1;
+            } else {
+                self.deduplicated_warn_count += 1;
+            }
+        }
+    }
+
     fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
         if diagnostic.cancelled() {
             return;
@@ -766,8 +921,23 @@ impl HandlerInner {
 
         (*TRACK_DIAGNOSTICS)(diagnostic);
 
-        if diagnostic.level == Allow {
-            return;
+        match diagnostic.level {
+            Allow => return,
+            Expect(id) => {
+                self.fulfilled_expectations.insert(id);
+                return;
+            }
+            _ => {}
+        }
+
+        if let Some(threshold) = self.flags.apply_suggestions {
+            self.suggestion_fixes.extend(
+                diagnostic
+                    .suggestions
+                    .iter()
+                    .filter(|s| fix::is_accepted(s.applicability, threshold))
+                    .map(fix::Fix::new),
+            );
         }
 
         if let Some(ref code) = diagnostic.code {
@@ -785,11 +955,26 @@ impl HandlerInner {
         // Only emit the diagnostic if we've been asked to deduplicate and
         // haven't already emitted an equivalent diagnostic.
         if !(self.flags.deduplicate_diagnostics && already_emitted(self)) {
-            self.emitter.emit_diagnostic(diagnostic);
-            if diagnostic.is_error() {
-                self.deduplicated_err_count += 1;
-            } else if diagnostic.level == Warning {
-                self.deduplicated_warn_count += 1;
+            let group_key = if self.flags.group_diagnostics {
+                grouping::GroupKey::new(diagnostic)
+            } else {
+                None
+            };
+            if let Some(key) = group_key {
+                match self.diagnostic_groups.get_mut(&key) {
+                    Some(group) => group.add(diagnostic),
+                    None => {
+                        let group = grouping::DiagnosticGroup::new(diagnostic.clone());
+                        self.diagnostic_groups.insert(key, group);
+                    }
+                }
+            } else {
+                self.emitter.emit_diagnostic(diagnostic);
+                if diagnostic.is_error() {
+                    self.deduplicated_err_count += 1;
+                } else if matches!(diagnostic.level, Warning | ForceWarning) {
+                    self.deduplicated_warn_count += 1;
+                }
             }
         }
         if diagnostic.is_error() {
@@ -809,6 +994,7 @@ impl HandlerInner {
 
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_diagnostic_groups();
 
         let warnings = match self.deduplicated_warn_count {
             0 => String::new(),
@@ -826,12 +1012,11 @@ impl HandlerInner {
 
         match (errors.len(), warnings.len()) {
             (0, 0) => return,
-            (0, _) => self.emit_diagnostic(&Diagnostic::new(Level::Warning, &warnings)),
-            (_, 0) => {
-                let _ = self.fatal(&errors);
-            }
+            (0, _) => self.emitter.emit_summary(&Diagnostic::new(Level::Warning, &warnings)),
+            (_, 0) => self.emitter.emit_summary(&Diagnostic::new(Level::Fatal, &errors)),
             (_, _) => {
-                let _ = self.fatal(&format!("{}; {}", &errors, &warnings));
+                let summary = format!("{}; {}", &errors, &warnings);
+                self.emitter.emit_summary(&Diagnostic::new(Level::Fatal, &summary));
             }
         }
 
@@ -1004,11 +1189,17 @@ pub enum Level {
     Fatal,
     Error,
     Warning,
+    /// A warning from a lint at `rustc_lint_defs::Level::ForceWarn`, which is
+    /// emitted even if warnings are disabled.
+    ForceWarning,
     Note,
     Help,
     Cancelled,
     FailureNote,
     Allow,
+    /// A lint at `rustc_lint_defs::Level::Expect`, which is not printed but
+    /// fulfills the given expectation.
+    Expect(LintExpectationId),
 }
 
 impl fmt::Display for Level {
@@ -1024,7 +1215,7 @@ impl Level {
             Bug | Fatal | Error => {
                 spec.set_fg(Some(Color::Red)).set_intense(true);
             }
-            Warning => {
+            Warning | ForceWarning => {
                 spec.set_fg(Some(Color::Yellow)).set_intense(cfg!(windows));
             }
             Note => {
@@ -1034,7 +1225,7 @@ impl Level {
                 spec.set_fg(Some(Color::Cyan)).set_intense(true);
             }
             FailureNote => {}
-            Allow | Cancelled => unreachable!(),
+            Allow | Expect(_) | Cancelled => unreachable!(),
         }
         spec
     }
@@ -1043,12 +1234,13 @@ impl Level {
         match self {
             Bug => "error: internal compiler error",
             Fatal | Error => "error",
-            Warning => "warning",
+            Warning | ForceWarning => "warning",
             Note => "note",
             Help => "help",
             FailureNote => "failure-note",
             Cancelled => panic!("Shouldn't call on cancelled error"),
             Allow => panic!("Shouldn't call on allowed error"),
+            Expect(_) => panic!("Shouldn't call on expected error"),
         }
     }
 
@@ -26,4 +26,11 @@ impl Registry {
     ) -> Result<Option<&'static str>, InvalidErrorCode> {
         self.long_descriptions.get(code).copied().ok_or(InvalidErrorCode)
     }
+
+    /// Returns all error codes of the registry, in order.
+    pub fn codes(&self) -> Vec<&'static str> {
+        let mut codes = self.long_descriptions.keys().copied().collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes
+    }
 }
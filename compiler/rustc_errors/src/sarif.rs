@@ -0,0 +1,381 @@
+//! A SARIF emitter for errors.
+//!
+//! SARIF (Static Analysis Results Interchange Format) is the OASIS standard
+//! format consumed by code-scanning tools. Unlike the JSON emitter, which
+//! prints one object per diagnostic as soon as it is emitted, a SARIF log is a
+//! single document: diagnostics are collected while the session runs and the
+//! whole log is written out when the emitter is dropped at the end of the
+//! session.
+//!
+//! The mapping to SARIF 2.1.0 is:
+//!
+//! * every diagnostic becomes a `result`, with its level mapped to the SARIF
+//!   `level` (`error`, `warning` or `note`);
+//! * a `DiagnosticId` becomes the `ruleId` of the result, and the tool's
+//!   `rules` list gets an entry for it, including the long explanation from
+//!   the registry if there is one, and for error codes the places in the
+//!   compiler that emitted the code, in the `emittedBy` property;
+//! * primary span labels become `locations`, and secondary span labels as
+//!   well as sub-diagnostics with spans become `relatedLocations`;
+//! * each substitution of a `CodeSuggestion` becomes a `fix`.
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::{CodeSuggestion, DiagnosticId, Level, SubDiagnostic};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{Json, Object, ToJson};
+use rustc_span::{Span, SpanLabel};
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Builds a JSON object from `"key" => value` entries. Entries written as
+/// `"key" => ? value` take an `Option` and are left out if it is `None`:
+/// SARIF consumers reject `null` where the schema expects a string or an
+/// object, so absent properties must not be written at all.
+macro_rules! object {
+    (@insert $object:ident; $(,)?) => {};
+    (@insert $object:ident; $key:literal => ? $value:expr, $($rest:tt)*) => {
+        if let Some(value) = $value {
+            $object.insert($key.to_string(), value.to_json());
+        }
+        object!(@insert $object; $($rest)*);
+    };
+    (@insert $object:ident; $key:literal => $value:expr, $($rest:tt)*) => {
+        $object.insert($key.to_string(), $value.to_json());
+        object!(@insert $object; $($rest)*);
+    };
+    ($($entries:tt)*) => {{
+        let mut object = Object::new();
+        object!(@insert object; $($entries)*,);
+        Json::Object(object)
+    }};
+}
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    pretty: bool,
+    ui_testing: bool,
+    /// Rule descriptors, in the order in which their codes were first seen.
+    /// Results refer to them by index.
+    rules: FxIndexMap<String, Json>,
+    /// The places in the compiler that emitted each error code, as recorded
+    /// by `Diagnostic::code`.
+    emission_sites: FxIndexMap<String, FxIndexSet<String>>,
+    results: Vec<Json>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>, pretty: bool) -> Self {
+        SarifEmitter::new(Box::new(io::BufWriter::new(io::stderr())), registry, source_map, pretty)
+    }
+
+    pub fn basic(pretty: bool) -> Self {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(None, Lrc::new(SourceMap::new(file_path_mapping)), pretty)
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        pretty: bool,
+    ) -> Self {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            pretty,
+            ui_testing: false,
+            rules: Default::default(),
+            emission_sites: Default::default(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn ui_testing(mut self, ui_testing: bool) -> Self {
+        // The log is written when the emitter is dropped, so it cannot be
+        // rebuilt with struct update syntax.
+        self.ui_testing = ui_testing;
+        self
+    }
+
+    /// Returns the SARIF log for all diagnostics emitted so far.
+    fn log(&self) -> Json {
+        let driver = object! {
+            "name" => "rustc",
+            "informationUri" => "https://www.rust-lang.org/",
+            // Keep the output of UI tests stable across releases.
+            "version" => ? (if self.ui_testing { None } else { option_env!("CFG_VERSION") }),
+            "rules" => self.rules().collect::<Vec<_>>(),
+        };
+        let run = object! {
+            "tool" => object! { "driver" => driver },
+            "results" => self.results.clone(),
+        };
+        object! {
+            "$schema" => SARIF_SCHEMA,
+            "version" => SARIF_VERSION,
+            "runs" => vec![run],
+        }
+    }
+
+    /// Returns the rule descriptors, with the places that emitted their
+    /// codes. The places are left out of UI tests, as they change with every
+    /// edit of the compiler.
+    fn rules(&self) -> impl Iterator<Item = Json> + '_ {
+        self.rules.iter().map(move |(id, rule)| {
+            let mut rule = rule.clone();
+            let sites = self.emission_sites.get(id).filter(|_| !self.ui_testing);
+            if let (Json::Object(object), Some(sites)) = (&mut rule, sites) {
+                let sites = sites.iter().map(|site| site.to_json()).collect::<Vec<_>>();
+                object.insert("properties".to_string(), object! { "emittedBy" => sites });
+            }
+            rule
+        })
+    }
+
+    /// Returns the index of the rule for `code`, registering it first if
+    /// this is the first diagnostic with that code.
+    fn rule_index(&mut self, code: &DiagnosticId) -> (String, usize) {
+        let (id, is_error_code) = match code {
+            DiagnosticId::Error(s) => (s.clone(), true),
+            DiagnosticId::Lint { name, has_future_breakage: _ } => (name.clone(), false),
+        };
+        if let Some((index, ..)) = self.rules.get_full(&id) {
+            return (id, index);
+        }
+
+        let explanation = self
+            .registry
+            .as_ref()
+            .and_then(|registry| registry.try_find_description(&id).ok())
+            .flatten();
+        let rule = object! {
+            "id" => id.as_str(),
+            "fullDescription" => ? (explanation.map(|text| object! {
+                "text" => text,
+                "markdown" => text,
+            })),
+            "helpUri" => ? (if is_error_code {
+                Some(format!("https://doc.rust-lang.org/error-index.html#{}", id))
+            } else {
+                None
+            }),
+        };
+        let (index, _) = self.rules.insert_full(id.clone(), rule);
+        (id, index)
+    }
+
+    fn result(&mut self, diag: &crate::Diagnostic) -> Json {
+        let rule = diag.code.as_ref().map(|code| self.rule_index(code));
+        if let (Some((id, _)), Some(site)) = (&rule, &diag.emitted_at.0) {
+            self.emission_sites.entry(id.clone()).or_default().insert(site.clone());
+        }
+
+        let mut message = diag.message();
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for span_label in diag.span.span_labels() {
+            let is_primary = span_label.is_primary;
+            if let Some(location) = self.location(span_label) {
+                if is_primary {
+                    locations.push(location);
+                } else {
+                    related_locations.push(location);
+                }
+            }
+        }
+
+        // Notes and help messages that point somewhere are related locations;
+        // the others are appended to the message, as they would be rendered.
+        for child in &diag.children {
+            let child_locations = self.sub_diagnostic_locations(child);
+            if child_locations.is_empty() {
+                message.push_str(&format!("\n{}: {}", child.level, child.message()));
+            } else {
+                related_locations.extend(child_locations);
+            }
+        }
+
+        // SARIF identifies related locations by id, starting at zero.
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            if let Json::Object(object) = location {
+                object.insert("id".to_string(), id.to_json());
+            }
+        }
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flat_map(|suggestion| self.fixes(suggestion))
+            .collect::<Vec<_>>();
+
+        object! {
+            "ruleId" => ? (rule.as_ref().map(|(id, _)| id.as_str())),
+            "ruleIndex" => ? (rule.as_ref().map(|&(_, index)| index)),
+            "level" => sarif_level(diag.level),
+            "message" => object! { "text" => message },
+            "locations" => locations,
+            "relatedLocations" => ? (Some(related_locations).filter(|l| !l.is_empty())),
+            "fixes" => ? (Some(fixes).filter(|f| !f.is_empty())),
+        }
+    }
+
+    fn sub_diagnostic_locations(&self, diag: &SubDiagnostic) -> Vec<Json> {
+        let span = diag.render_span.as_ref().unwrap_or(&diag.span);
+        let message = diag.message();
+        span.primary_spans()
+            .iter()
+            .filter_map(|&span| {
+                let label = Some(format!("{}: {}", diag.level, message));
+                self.location(SpanLabel { span, is_primary: false, label })
+            })
+            .collect()
+    }
+
+    fn location(&self, span_label: SpanLabel) -> Option<Json> {
+        let physical_location = self.physical_location(span_label.span)?;
+        Some(object! {
+            "physicalLocation" => physical_location,
+            "message" => ? (span_label.label.map(|text| object! { "text" => text })),
+        })
+    }
+
+    fn physical_location(&self, span: Span) -> Option<Json> {
+        let (uri, region) = self.region(span)?;
+        Some(object! {
+            "artifactLocation" => object! { "uri" => uri },
+            "region" => region,
+        })
+    }
+
+    /// Returns the file and region that `span` covers, or `None` for dummy
+    /// spans, which do not point at any source.
+    fn region(&self, span: Span) -> Option<(String, Json)> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let region = object! {
+            // All 1-based; columns count characters.
+            "startLine" => start.line,
+            "startColumn" => start.col.0 + 1,
+            "endLine" => end.line,
+            "endColumn" => end.col.0 + 1,
+            "byteOffset" => byte_start as usize,
+            "byteLength" => (byte_end - byte_start) as usize,
+        };
+        Some((start.file.name.to_string(), region))
+    }
+
+    /// Returns one fix per substitution of `suggestion`, grouping its
+    /// replacements by the file they apply to.
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Json> {
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut changes: FxIndexMap<String, Vec<Json>> = Default::default();
+                for part in &substitution.parts {
+                    if let Some((uri, region)) = self.region(part.span) {
+                        changes.entry(uri).or_default().push(object! {
+                            "deletedRegion" => region,
+                            "insertedContent" => object! { "text" => part.snippet.as_str() },
+                        });
+                    }
+                }
+                let artifact_changes = changes
+                    .into_iter()
+                    .map(|(uri, replacements)| {
+                        object! {
+                            "artifactLocation" => object! { "uri" => uri },
+                            "replacements" => replacements,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                object! {
+                    "description" => object! { "text" => suggestion.msg.as_str() },
+                    "artifactChanges" => artifact_changes,
+                    "properties" => object! {
+                        "applicability" => applicability_str(suggestion.applicability),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        // The notes about `--explain` are not results either.
+        if diag.level.is_failure_note() {
+            return;
+        }
+        let result = self.result(diag);
+        self.results.push(result);
+    }
+
+    fn emit_summary(&mut self, _diag: &crate::Diagnostic) {
+        // "aborting due to previous error" and friends summarize the other
+        // results; they are not results themselves.
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = self.log();
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", log.pretty())
+        } else {
+            writeln!(&mut self.dst, "{}", log)
+        }
+        .and_then(|_| self.dst.flush());
+        // This can run while unwinding from a fatal error, where panicking
+        // would abort, so the error is only reported.
+        if let Err(e) = result {
+            let _ = writeln!(io::stderr(), "failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => "error",
+        Level::Warning | Level::ForceWarning => "warning",
+        Level::Note | Level::Help | Level::FailureNote => "note",
+        Level::Allow | Level::Expect(_) => "none",
+        Level::Cancelled => panic!("Shouldn't emit a cancelled error"),
+    }
+}
+
+fn applicability_str(applicability: Applicability) -> &'static str {
+    match applicability {
+        Applicability::MachineApplicable => "MachineApplicable",
+        Applicability::MaybeIncorrect => "MaybeIncorrect",
+        Applicability::HasPlaceholders => "HasPlaceholders",
+        Applicability::Unspecified => "Unspecified",
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+use crate::{DiagnosticId, Handler};
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+/// Runs `f` with a handler that emits SARIF, and returns the log that is
+/// written when the handler is dropped.
+fn sarif_log(code: &str, f: impl FnOnce(&Handler)) -> Json {
+    sarif_log_with_ui_testing(code, true, f)
+}
+
+fn sarif_log_with_ui_testing(code: &str, ui_testing: bool, f: impl FnOnce(&Handler)) -> Json {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    with_default_session_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+
+        let registry = Registry::new(&[("E0308", Some("Expected type did not match.\n"))]);
+        let emitter =
+            SarifEmitter::new(Box::new(Shared { data: output.clone() }), Some(registry), sm, false)
+                .ui_testing(ui_testing);
+        let handler = Handler::with_emitter(true, None, Box::new(emitter));
+        f(&handler);
+    });
+
+    let bytes = output.lock().unwrap();
+    rustc_serialize::json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+}
+
+fn find<'a>(json: &'a Json, path: &[&str]) -> &'a Json {
+    json.find_path(path).unwrap_or_else(|| panic!("missing {:?} in {}", path, json))
+}
+
+#[test]
+fn empty_log() {
+    let log = sarif_log("", |_| {});
+    assert_eq!(find(&log, &["version"]).as_string(), Some("2.1.0"));
+    let run = &find(&log, &["runs"]).as_array().unwrap()[0];
+    assert_eq!(find(run, &["tool", "driver", "name"]).as_string(), Some("rustc"));
+    assert!(find(run, &["results"]).as_array().unwrap().is_empty());
+}
+
+#[test]
+fn result_with_rule_locations_and_fix() {
+    let log = sarif_log("let x: u8 = y;\n", |handler| {
+        handler
+            .struct_span_err_with_code(
+                span(12, 13),
+                "mismatched types",
+                DiagnosticId::Error("E0308".to_string()),
+            )
+            .span_label(span(12, 13), "expected `u8`")
+            .span_label(span(7, 9), "expected due to this")
+            .span_suggestion(
+                span(12, 13),
+                "convert it",
+                "y.into()".to_string(),
+                Applicability::MaybeIncorrect,
+            )
+            .note("a note without a span")
+            .emit();
+    });
+
+    let run = &find(&log, &["runs"]).as_array().unwrap()[0];
+    let rules = find(run, &["tool", "driver", "rules"]).as_array().unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(find(&rules[0], &["id"]).as_string(), Some("E0308"));
+    assert_eq!(
+        find(&rules[0], &["fullDescription", "text"]).as_string(),
+        Some("Expected type did not match.\n")
+    );
+
+    let results = find(run, &["results"]).as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert_eq!(find(result, &["ruleId"]).as_string(), Some("E0308"));
+    assert_eq!(find(result, &["ruleIndex"]).as_u64(), Some(0));
+    assert_eq!(find(result, &["level"]).as_string(), Some("error"));
+    assert_eq!(
+        find(result, &["message", "text"]).as_string(),
+        Some("mismatched types\nnote: a note without a span")
+    );
+
+    let location = &find(result, &["locations"]).as_array().unwrap()[0];
+    assert_eq!(find(location, &["message", "text"]).as_string(), Some("expected `u8`"));
+    let physical = find(location, &["physicalLocation"]);
+    assert_eq!(find(physical, &["artifactLocation", "uri"]).as_string(), Some("test.rs"));
+    assert_eq!(find(physical, &["region", "startLine"]).as_u64(), Some(1));
+    assert_eq!(find(physical, &["region", "startColumn"]).as_u64(), Some(13));
+    assert_eq!(find(physical, &["region", "endColumn"]).as_u64(), Some(14));
+    assert_eq!(find(physical, &["region", "byteOffset"]).as_u64(), Some(12));
+    assert_eq!(find(physical, &["region", "byteLength"]).as_u64(), Some(1));
+
+    let related = find(result, &["relatedLocations"]).as_array().unwrap();
+    assert_eq!(related.len(), 1);
+    assert_eq!(find(&related[0], &["id"]).as_u64(), Some(0));
+    assert_eq!(find(&related[0], &["message", "text"]).as_string(), Some("expected due to this"));
+
+    let fix = &find(result, &["fixes"]).as_array().unwrap()[0];
+    assert_eq!(find(fix, &["description", "text"]).as_string(), Some("convert it"));
+    assert_eq!(find(fix, &["properties", "applicability"]).as_string(), Some("MaybeIncorrect"));
+    let change = &find(fix, &["artifactChanges"]).as_array().unwrap()[0];
+    let replacement = &find(change, &["replacements"]).as_array().unwrap()[0];
+    assert_eq!(find(replacement, &["deletedRegion", "byteOffset"]).as_u64(), Some(12));
+    assert_eq!(find(replacement, &["insertedContent", "text"]).as_string(), Some("y.into()"));
+}
+
+#[test]
+fn absent_properties_are_omitted() {
+    let log = sarif_log("fn main() {}\n", |handler| {
+        handler.struct_warn("unused thing").emit();
+    });
+
+    let result = &find(&log, &["runs"]).as_array().unwrap()[0];
+    let result = &find(result, &["results"]).as_array().unwrap()[0];
+    assert_eq!(find(result, &["level"]).as_string(), Some("warning"));
+    assert!(result.find("ruleId").is_none());
+    assert!(result.find("relatedLocations").is_none());
+    assert!(result.find("fixes").is_none());
+    assert!(find(result, &["locations"]).as_array().unwrap().is_empty());
+}
+
+#[test]
+fn emission_sites() {
+    let line = line!() + 3;
+    let log = sarif_log_with_ui_testing("fn main() {}\n", false, |handler| {
+        let code = DiagnosticId::Error("E0308".into());
+        handler.struct_err_with_code("mismatched types", code).emit();
+    });
+
+    let run = &find(&log, &["runs"]).as_array().unwrap()[0];
+    let rule = &find(run, &["tool", "driver", "rules"]).as_array().unwrap()[0];
+    let sites = find(rule, &["properties", "emittedBy"]).as_array().unwrap();
+    assert_eq!(sites.len(), 1);
+    let site = sites[0].as_string().unwrap();
+    assert!(site.starts_with(&format!("{}:{}:", file!(), line)), "{}", site);
+
+    // The sites are left out of UI tests.
+    let log = sarif_log("fn main() {}\n", |handler| {
+        let code = DiagnosticId::Error("E0308".into());
+        handler.struct_err_with_code("mismatched types", code).emit();
+    });
+    let run = &find(&log, &["runs"]).as_array().unwrap()[0];
+    let rule = &find(run, &["tool", "driver", "rules"]).as_array().unwrap()[0];
+    assert!(rule.find("properties").is_none());
+}
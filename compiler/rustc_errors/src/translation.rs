@@ -0,0 +1,212 @@
+//! Message catalogs for translating diagnostics.
+//!
+//! Diagnostics declared with `#[derive(SessionDiagnostic)]` and a
+//! `#[slug = "..."]` attribute have keyed messages: the primary message is
+//! keyed by the slug, and the message of a label or suggestion on a field is
+//! keyed by `<slug>.<field>`. The English strings written in the attributes
+//! are the default catalog. With `-Z translation-locale=<locale>`, the
+//! messages are looked up in the catalog for that locale first, which is
+//! read from the `share/locale/<locale>` directory of the sysroot.
+//!
+//! Catalogs are written in a subset of the [Fluent] syntax: a message is a
+//! line `key = text`, and its labels and suggestions are the indented
+//! attribute lines `.field = text` that follow it. Other indented lines
+//! continue the previous value on a new line. Arguments are written
+//! `{ $name }` and refer to the fields that the English message uses:
+//!
+//! ```text
+//! # Errors from `rustc_typeck/src/errors.rs`.
+//! typeck-field-already-declared = フィールド `{ $field_name }` は既に宣言されています
+//!     .span = フィールドは既に宣言されています
+//! ```
+//!
+//! [Fluent]: https://projectfluent.org/fluent/guide/
+
+use rustc_data_structures::fx::FxHashMap;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+/// The translated messages of one locale.
+#[derive(Debug)]
+pub struct Catalog {
+    locale: String,
+    messages: FxHashMap<String, String>,
+}
+
+/// An error found while loading a catalog.
+#[derive(Debug, PartialEq)]
+pub enum CatalogError {
+    /// There is no catalog for the locale.
+    NotFound { locale: String, dir: String },
+    /// A catalog file could not be read.
+    Io { file: String, error: String },
+    /// A line of a catalog file is not valid.
+    Syntax { file: String, line: usize, msg: &'static str },
+    /// A message is defined more than once.
+    Duplicate { file: String, line: usize, key: String },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::NotFound { locale, dir } => {
+                write!(f, "no translation catalog for locale `{}` in `{}`", locale, dir)
+            }
+            CatalogError::Io { file, error } => {
+                write!(f, "failed to read translation catalog `{}`: {}", file, error)
+            }
+            CatalogError::Syntax { file, line, msg } => write!(f, "{}:{}: {}", file, line, msg),
+            CatalogError::Duplicate { file, line, key } => {
+                write!(f, "{}:{}: message `{}` is defined more than once", file, line, key)
+            }
+        }
+    }
+}
+
+impl Catalog {
+    /// Loads the catalog for `locale` from the `.ftl` files in
+    /// `<sysroot>/share/locale/<locale>`.
+    pub fn load(sysroot: &Path, locale: &str) -> Result<Catalog, CatalogError> {
+        let dir = sysroot.join("share").join("locale").join(locale);
+        let not_found = || CatalogError::NotFound {
+            locale: locale.to_string(),
+            dir: dir.display().to_string(),
+        };
+
+        let mut files = fs::read_dir(&dir)
+            .map_err(|_| not_found())?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "ftl"))
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return Err(not_found());
+        }
+        // Report duplicate messages in the same order on all platforms.
+        files.sort();
+
+        let mut catalog = Catalog { locale: locale.to_string(), messages: Default::default() };
+        for file in files {
+            let name = file.display().to_string();
+            let src = fs::read_to_string(&file)
+                .map_err(|e| CatalogError::Io { file: name.clone(), error: e.to_string() })?;
+            catalog.add_messages(&name, &src)?;
+        }
+        Ok(catalog)
+    }
+
+    /// Parses a catalog from the contents of a single file.
+    pub fn parse(locale: &str, file: &str, src: &str) -> Result<Catalog, CatalogError> {
+        let mut catalog = Catalog { locale: locale.to_string(), messages: Default::default() };
+        catalog.add_messages(file, src)?;
+        Ok(catalog)
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    fn add_messages(&mut self, file: &str, src: &str) -> Result<(), CatalogError> {
+        let syntax = |line, msg| Err(CatalogError::Syntax { file: file.to_string(), line, msg });
+
+        // The message that attributes belong to, and the key of the value
+        // that continuation lines are added to.
+        let mut message: Option<String> = None;
+        let mut last_key: Option<String> = None;
+        for (i, line) in src.lines().enumerate() {
+            let line_number = i + 1;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let indented = line.starts_with(|c: char| c.is_whitespace());
+            let key = if indented && trimmed.starts_with('.') {
+                let (name, value) = match split_entry(&trimmed[1..]) {
+                    Some(entry) => entry,
+                    None => return syntax(line_number, "expected `.attribute = text`"),
+                };
+                let key = match &message {
+                    Some(message) => format!("{}.{}", message, name),
+                    None => return syntax(line_number, "attribute outside of a message"),
+                };
+                self.insert(file, line_number, key, value)?
+            } else if indented {
+                match last_key.as_ref().and_then(|key| self.messages.get_mut(key)) {
+                    Some(value) => {
+                        value.push('\n');
+                        value.push_str(trimmed);
+                        continue;
+                    }
+                    None => return syntax(line_number, "continuation line outside of a message"),
+                }
+            } else {
+                let (key, value) = match split_entry(trimmed) {
+                    Some(entry) => entry,
+                    None => return syntax(line_number, "expected `message = text`"),
+                };
+                message = Some(key.to_string());
+                self.insert(file, line_number, key.to_string(), value)?
+            };
+            last_key = Some(key);
+        }
+        Ok(())
+    }
+
+    fn insert(
+        &mut self,
+        file: &str,
+        line: usize,
+        key: String,
+        value: &str,
+    ) -> Result<String, CatalogError> {
+        if self.messages.insert(key.clone(), value.to_string()).is_some() {
+            return Err(CatalogError::Duplicate { file: file.to_string(), line, key });
+        }
+        Ok(key)
+    }
+
+    /// Returns the message `key` with its arguments replaced by `args`, or
+    /// `None` if the catalog does not have the message.
+    ///
+    /// Arguments that are not in `args` are left as they are written.
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> Option<String> {
+        let template = self.messages.get(key)?;
+
+        let mut result = String::with_capacity(template.len());
+        let mut rest = &template[..];
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            let end = match placeholder.find('}') {
+                Some(end) => end + 1,
+                None => {
+                    rest = placeholder;
+                    break;
+                }
+            };
+            let name = placeholder[1..end - 1].trim();
+            match name.strip_prefix('$').and_then(|name| args.iter().find(|(n, _)| *n == name)) {
+                Some((_, value)) => result.push_str(value),
+                None => result.push_str(&placeholder[..end]),
+            }
+            rest = &placeholder[end..];
+        }
+        result.push_str(rest);
+        Some(result)
+    }
+}
+
+/// Splits `key = value` into its key and value, checking that the key is
+/// an identifier as Fluent allows them.
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_at(line.find('=')?);
+    let key = key.trim();
+    let is_valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if is_valid_key { Some((key, value[1..].trim())) } else { None }
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+fn catalog(src: &str) -> Catalog {
+    Catalog::parse("xx", "test.ftl", src).unwrap()
+}
+
+#[test]
+fn messages_and_attributes() {
+    let catalog = catalog(
+        "# A comment.\n\
+         field-already-declared = field `{ $field_name }` is already declared\n    \
+             .span = field already declared\n    \
+             .prev_span = `{ $field_name }` first declared here\n\
+         \n\
+         missing-type-params = missing type parameters\n",
+    );
+
+    let args = [("field_name", "x".to_string())];
+    assert_eq!(
+        catalog.format("field-already-declared", &args).as_deref(),
+        Some("field `x` is already declared")
+    );
+    assert_eq!(
+        catalog.format("field-already-declared.span", &args).as_deref(),
+        Some("field already declared")
+    );
+    assert_eq!(
+        catalog.format("field-already-declared.prev_span", &args).as_deref(),
+        Some("`x` first declared here")
+    );
+    assert_eq!(
+        catalog.format("missing-type-params", &[]).as_deref(),
+        Some("missing type parameters")
+    );
+    assert_eq!(catalog.format("missing-type-params.span", &[]), None);
+}
+
+#[test]
+fn continuation_lines() {
+    let catalog = catalog(
+        "long-message = the first line\n    \
+             and the second one\n    \
+             .note = a note\n    \
+             that goes on\n",
+    );
+
+    assert_eq!(
+        catalog.format("long-message", &[]).as_deref(),
+        Some("the first line\nand the second one")
+    );
+    assert_eq!(catalog.format("long-message.note", &[]).as_deref(), Some("a note\nthat goes on"));
+}
+
+#[test]
+fn unknown_arguments_are_kept() {
+    let catalog = catalog("message = { $known } and { $unknown } or {literal} {\n");
+
+    let args = [("known", "1".to_string())];
+    assert_eq!(
+        catalog.format("message", &args).as_deref(),
+        Some("1 and { $unknown } or {literal} {")
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    let error = |src| Catalog::parse("xx", "test.ftl", src).unwrap_err();
+    let syntax = |line, msg| CatalogError::Syntax { file: "test.ftl".to_string(), line, msg };
+
+    assert_eq!(error("message\n"), syntax(1, "expected `message = text`"));
+    assert_eq!(error("1message = text\n"), syntax(1, "expected `message = text`"));
+    assert_eq!(error("\n    .span = text\n"), syntax(2, "attribute outside of a message"));
+    assert_eq!(error("    continued\n"), syntax(1, "continuation line outside of a message"));
+    assert_eq!(error("message = text\n    .span\n"), syntax(2, "expected `.attribute = text`"));
+}
+
+#[test]
+fn duplicate_messages() {
+    let error = Catalog::parse("xx", "test.ftl", "message = a\n    .span = b\n    .span = c\n")
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        CatalogError::Duplicate {
+            file: "test.ftl".to_string(),
+            line: 3,
+            key: "message.span".to_string()
+        }
+    );
+    assert_eq!(error.to_string(), "test.ftl:3: message `message.span` is defined more than once");
+}
@@ -323,6 +323,8 @@ language_item_table! {
 
     PinNewUnchecked,         sym::new_unchecked,       new_unchecked_fn,           Target::Method(MethodKind::Inherent);
 
+    SliceLen,                sym::slice_len_fn,        slice_len_fn,               Target::Method(MethodKind::Inherent);
+
     RangeFrom,               sym::RangeFrom,           range_from_struct,          Target::Struct;
     RangeFull,               sym::RangeFull,           range_full_struct,          Target::Struct;
     RangeInclusiveStruct,    sym::RangeInclusive,      range_inclusive_struct,     Target::Struct;
@@ -194,6 +194,7 @@ pub fn register_plugins<'a>(
         sess.unstable_options(),
     );
     register_lints(&sess, &mut lint_store);
+    rustc_lint::register_lint_rules(&sess, &mut lint_store);
 
     let registrars =
         sess.time("plugin_loading", || plugin::load::load_plugins(sess, metadata_loader, &krate));
@@ -554,6 +555,12 @@ fn write_out_deps(
             .map(|fmap| escape_dep_filename(&fmap.unmapped_path.as_ref().unwrap_or(&fmap.name)))
             .collect();
 
+        // Files that are not source files, but that the output depends on.
+        let file_depinfo = sess.parse_sess.file_depinfo.borrow();
+        for path in file_depinfo.iter() {
+            files.push(escape_dep_filename(&FileName::from(PathBuf::from(&*path.as_str()))));
+        }
+
         if let Some(ref backend) = sess.opts.debugging_opts.codegen_backend {
             files.push(backend.to_string());
         }
@@ -770,6 +777,7 @@ pub fn create_global_ctxt<'tcx>(
             TyCtxt::create_global_ctxt(
                 sess,
                 lint_store,
+                Box::new(mir::util::MirBrowser::default()),
                 local_providers,
                 extern_providers,
                 arena,
@@ -999,6 +1007,10 @@ pub fn start_codegen<'tcx>(
         codegen_backend.codegen_crate(tcx, metadata, need_metadata_module)
     });
 
+    // Code generation computes the optimized MIR of all items, which emits
+    // the last lints, so only now can expectations be checked.
+    tcx.sess.time("lint_expectation_checking", || rustc_lint::check_expectations(tcx));
+
     info!("Post-codegen\n{:?}", tcx.debug_stats());
 
     if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
@@ -68,6 +68,11 @@ pub struct LintStore {
 
     /// Map of registered lint groups to what lints they expand to.
     lint_groups: FxHashMap<&'static str, LintGroup>,
+
+    /// The lints that are not `static`s, e.g. those of a lint rules file,
+    /// and their names and descriptions. See `LintStore::alloc_lint`.
+    owned_lints: Vec<Box<Lint>>,
+    owned_strs: Vec<Box<str>>,
 }
 
 impl SessionLintStore for LintStore {
@@ -139,6 +144,8 @@ impl LintStore {
             late_module_passes: vec![],
             by_name: Default::default(),
             lint_groups: Default::default(),
+            owned_lints: vec![],
+            owned_strs: vec![],
         }
     }
 
@@ -187,6 +194,32 @@ impl LintStore {
         self.late_module_passes.push(Box::new(pass));
     }
 
+    /// Keeps a lint that is defined at runtime, for `register_lints`, which
+    /// only takes `'static` lints.
+    ///
+    /// The lint is freed with the store, so it must not be used after the
+    /// store is dropped. This holds for the lints that are only registered in
+    /// the store, as the store outlives every use of its lints: it is created
+    /// for a session and dropped with it.
+    crate fn alloc_lint(&mut self, lint: Lint) -> &'static Lint {
+        let lint = Box::new(lint);
+        // SAFETY: the lint is boxed, so it does not move, and the box is only
+        // dropped with the store.
+        let lint_ref = unsafe { &*(&*lint as *const Lint) };
+        self.owned_lints.push(lint);
+        lint_ref
+    }
+
+    /// Keeps a string for the name or description of a lint or lint group
+    /// that is defined at runtime, like `alloc_lint`.
+    crate fn alloc_str(&mut self, s: String) -> &'static str {
+        let s = s.into_boxed_str();
+        // SAFETY: as in `alloc_lint`.
+        let s_ref = unsafe { &*(&*s as *const str) };
+        self.owned_strs.push(s);
+        s_ref
+    }
+
     // Helper method for register_early/late_pass
     pub fn register_lints(&mut self, lints: &[&'static Lint]) {
         for lint in lints {
@@ -325,8 +358,10 @@ impl LintStore {
                 match level {
                     Level::Allow => "-A",
                     Level::Warn => "-W",
+                    Level::ForceWarn => "--force-warn",
                     Level::Deny => "-D",
                     Level::Forbid => "-F",
+                    Level::Expect => unreachable!("`expect` cannot be set on the command line"),
                 },
                 lint_name
             );
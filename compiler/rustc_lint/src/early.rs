@@ -85,11 +85,22 @@ impl<'a, T: EarlyLintPass> ast_visit::Visitor<'a> for EarlyContextAndPass<'a, T>
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
+        let module = match it.kind {
+            ast::ItemKind::Mod(ref module) => Some(self.context.builder.push_module(
+                it.ident.name,
+                module.inner,
+                &self.context.lint_store,
+            )),
+            _ => None,
+        };
         self.with_lint_attrs(it.id, &it.attrs, |cx| {
             run_early_pass!(cx, check_item, it);
             ast_visit::walk_item(cx, it);
             run_early_pass!(cx, check_item_post, it);
-        })
+        });
+        if let Some(push) = module {
+            self.context.builder.pop_module(push);
+        }
     }
 
     fn visit_foreign_item(&mut self, it: &'a ast::ForeignItem) {
@@ -0,0 +1,53 @@
+//! Reporting of unfulfilled lint expectations.
+//!
+//! A lint at `Level::Expect` is emitted as a diagnostic that the handler
+//! does not print, but records as fulfilling the `#[expect]` attribute that
+//! set the level. Once all lints have run, every expectation that was not
+//! fulfilled is reported with the `unfulfilled_lint_expectations` lint.
+
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::lint::struct_lint_level;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::lint::builtin::UNFULFILLED_LINT_EXPECTATIONS;
+
+/// Reports all `#[expect]` attributes of the local crate for which no lint
+/// has been emitted so far.
+///
+/// This must run after all lint passes, including those registered by
+/// tools such as Clippy. Lints that are only emitted during code generation
+/// (for example those from MIR optimizations) are missed if code generation
+/// has not happened yet, or never happens, as with `--emit=metadata`.
+pub fn check_expectations(tcx: TyCtxt<'_>) {
+    let fulfilled = tcx.sess.diagnostic().steal_fulfilled_expectation_ids();
+    let lint_levels = tcx.lint_levels(LOCAL_CRATE);
+
+    for expectation in &lint_levels.expectations {
+        if fulfilled.contains(&expectation.id) {
+            continue;
+        }
+
+        let (level, src) = lint_levels.sets.get_lint_level(
+            UNFULFILLED_LINT_EXPECTATIONS,
+            expectation.set,
+            None,
+            tcx.sess,
+        );
+        struct_lint_level(
+            tcx.sess,
+            UNFULFILLED_LINT_EXPECTATIONS,
+            level,
+            src,
+            Some(expectation.id.span.into()),
+            |lint| {
+                let mut db = lint.build(&format!(
+                    "this lint expectation is unfulfilled: no `{}` lint was emitted",
+                    expectation.lint_name
+                ));
+                if let Some(rationale) = expectation.reason {
+                    db.note(&format!("the expectation was: {}", rationale));
+                }
+                db.emit();
+            },
+        );
+    }
+}
@@ -12,16 +12,22 @@ use rustc_hir::{intravisit, HirId};
 use rustc_middle::hir::map::Map;
 use rustc_middle::lint::LevelSource;
 use rustc_middle::lint::LintDiagnosticBuilder;
-use rustc_middle::lint::{struct_lint_level, LintLevelMap, LintLevelSets, LintSet, LintSource};
+use rustc_middle::lint::{
+    struct_lint_level, LintExpectation, LintLevelMap, LintLevelSets, LintSet, LintSource,
+};
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
-use rustc_session::lint::{builtin, Level, Lint, LintId};
+use rustc_session::lint::config::{LintScope, LintTable};
+use rustc_session::lint::{builtin, Level, Lint, LintExpectationId, LintId};
 use rustc_session::parse::feature_err;
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{source_map::MultiSpan, Span, DUMMY_SP};
+use rustc_span::{source_map::MultiSpan, FileName, Span, DUMMY_SP};
 
 use std::cmp;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
 
 fn lint_levels(tcx: TyCtxt<'_>, cnum: CrateNum) -> LintLevelMap {
     assert_eq!(cnum, LOCAL_CRATE);
@@ -47,6 +53,13 @@ pub struct LintLevelsBuilder<'s> {
     id_to_set: FxHashMap<HirId, u32>,
     cur: u32,
     warn_about_weird_lints: bool,
+    expectations: Vec<LintExpectation>,
+    /// The path of the current module, starting with the crate name, for the
+    /// `[module]` tables of the lint configuration file.
+    module_path: String,
+    /// The source file of the current module, for the `[directory]` tables of
+    /// the lint configuration file.
+    module_file: Option<PathBuf>,
 }
 
 pub struct BuilderPush {
@@ -54,6 +67,13 @@ pub struct BuilderPush {
     pub changed: bool,
 }
 
+/// The state to restore when leaving a module, see `LintLevelsBuilder::push_module`.
+pub struct ModulePush {
+    pub levels: BuilderPush,
+    path_len: usize,
+    file: Option<PathBuf>,
+}
+
 impl<'s> LintLevelsBuilder<'s> {
     pub fn new(sess: &'s Session, warn_about_weird_lints: bool, store: &LintStore) -> Self {
         let mut builder = LintLevelsBuilder {
@@ -62,6 +82,9 @@ impl<'s> LintLevelsBuilder<'s> {
             cur: 0,
             id_to_set: Default::default(),
             warn_about_weird_lints,
+            expectations: Vec::new(),
+            module_path: String::new(),
+            module_file: None,
         };
         builder.process_command_line(sess, store);
         assert_eq!(builder.sets.list.len(), 1);
@@ -79,7 +102,9 @@ impl<'s> LintLevelsBuilder<'s> {
             // If the cap is less than this specified level, e.g., if we've got
             // `--cap-lints allow` but we've also got `-D foo` then we ignore
             // this specification as the lint cap will set it to allow anyway.
-            let level = cmp::min(level, self.sets.lint_cap);
+            // `--force-warn` is the exception, as it overrides the cap.
+            let level =
+                if level == Level::ForceWarn { level } else { cmp::min(level, self.sets.lint_cap) };
 
             let lint_flag_val = Symbol::intern(lint_name);
 
@@ -127,6 +152,12 @@ impl<'s> LintLevelsBuilder<'s> {
                     LintSource::CommandLine(_, _) => {
                         diag_builder.note("`forbid` lint level was set on command line");
                     }
+                    LintSource::ConfigFile(_, table) => {
+                        diag_builder.note(&format!(
+                            "`forbid` lint level was set by `[{}]` in the lint configuration file",
+                            table
+                        ));
+                    }
                 }
                 diag_builder.emit();
                 return;
@@ -155,7 +186,15 @@ impl<'s> LintLevelsBuilder<'s> {
         store: &LintStore,
         is_crate_node: bool,
     ) -> BuilderPush {
+        let prev = self.cur;
+        if is_crate_node {
+            // The levels of the lint configuration file are below the crate
+            // attributes, and are restored along with them by `pop`.
+            self.push_crate_config(attrs, store);
+        }
+
         let mut specs = FxHashMap::default();
+        let mut expectations = Vec::new();
         let sess = self.sess;
         let bad_attr = |span| struct_span_err!(sess, span, E0452, "malformed lint attribute input");
         for attr in attrs {
@@ -263,6 +302,9 @@ impl<'s> LintLevelsBuilder<'s> {
                             self.check_gated_lint(id, attr.span);
                             self.insert_spec(&mut specs, id, (level, src));
                         }
+                        if level == Level::Expect {
+                            expectations.push((name, li.span(), reason));
+                        }
                     }
 
                     CheckLintNameResult::Tool(result) => {
@@ -277,6 +319,9 @@ impl<'s> LintLevelsBuilder<'s> {
                                 for id in ids {
                                     self.insert_spec(&mut specs, *id, (level, src));
                                 }
+                                if level == Level::Expect {
+                                    expectations.push((src.name(), li.span(), reason));
+                                }
                             }
                             Err((Some(ids), new_lint_name)) => {
                                 let lint = builtin::RENAMED_AND_REMOVED_LINTS;
@@ -314,6 +359,9 @@ impl<'s> LintLevelsBuilder<'s> {
                                 for id in ids {
                                     self.insert_spec(&mut specs, *id, (level, src));
                                 }
+                                if level == Level::Expect {
+                                    expectations.push((src.name(), li.span(), reason));
+                                }
                             }
                             Err((None, _)) => {
                                 // If Tool(Err(None, _)) is returned, then either the lint does not
@@ -424,6 +472,7 @@ impl<'s> LintLevelsBuilder<'s> {
                 LintSource::Default => id.to_string(),
                 LintSource::Node(name, _, _) => name.to_string(),
                 LintSource::CommandLine(name, _) => name.to_string(),
+                LintSource::ConfigFile(name, _) => name.to_string(),
             };
             let (lint_attr_name, lint_attr_span) = match *src {
                 LintSource::Node(name, span, _) => (name, span),
@@ -450,18 +499,185 @@ impl<'s> LintLevelsBuilder<'s> {
                 LintSource::CommandLine(_, _) => {
                     diag_builder.note("`forbid` lint level was set on command line");
                 }
+                LintSource::ConfigFile(_, table) => {
+                    diag_builder.note(&format!(
+                        "`forbid` lint level was set by `[{}]` in the lint configuration file",
+                        table
+                    ));
+                }
             }
             diag_builder.emit();
             // don't set a separate error for every lint in the group
             break;
         }
 
+        let parent = self.cur;
+        if !specs.is_empty() {
+            self.cur = self.sets.list.len() as u32;
+            self.sets.list.push(LintSet::Node { specs, parent });
+        }
+
+        let set = self.cur;
+        self.expectations.extend(expectations.into_iter().map(|(lint_name, span, reason)| {
+            LintExpectation { id: LintExpectationId { span }, lint_name, reason, set }
+        }));
+
+        BuilderPush { prev, changed: prev != self.cur }
+    }
+
+    /// Pushes the levels that the lint configuration file sets for the crate
+    /// root, whose attributes are `attrs`.
+    fn push_crate_config(&mut self, attrs: &[ast::Attribute], store: &LintStore) {
+        let sess = self.sess;
+        let config = match &sess.lint_config {
+            Some(config) => config,
+            None => return,
+        };
+
+        // This is the name that `find_crate_name` determines, which is not
+        // known yet when the early lint passes run.
+        let attr_crate_name = sess.first_attr_value_str_by_name(attrs, sym::crate_name);
+        let crate_name = match (&sess.opts.crate_name, attr_crate_name) {
+            (Some(name), _) => name.clone(),
+            (None, Some(name)) => name.to_string(),
+            (None, None) => sess
+                .local_crate_source_file
+                .as_ref()
+                .and_then(|path| path.file_stem()?.to_str())
+                .map_or_else(|| "rust_out".to_string(), |stem| stem.replace("-", "_")),
+        };
+        self.module_file = sess
+            .local_crate_source_file
+            .as_ref()
+            .map(|path| fs::canonicalize(path).unwrap_or_else(|_| path.clone()));
+
+        let mut tables = config
+            .tables
+            .iter()
+            .filter(|table| match &table.scope {
+                LintScope::All => true,
+                LintScope::Crate(name) => *name == crate_name,
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        tables.extend(self.module_tables(&crate_name, None));
+        self.module_path = crate_name;
+        self.push_config(tables, store);
+    }
+
+    /// Pushes the levels that the lint configuration file sets for the module
+    /// `name`, a child of the current module, whose contents are at `inner`.
+    ///
+    /// The levels apply to the attributes of the module, so this is called
+    /// before `push` for them. Call `pop_module` when the module is exited.
+    pub(crate) fn push_module(
+        &mut self,
+        name: Symbol,
+        inner: Span,
+        store: &LintStore,
+    ) -> ModulePush {
+        let path_len = self.module_path.len();
+        let parent_file = self.module_file.clone();
+        if self.sess.lint_config.is_none() {
+            let levels = BuilderPush { prev: self.cur, changed: false };
+            return ModulePush { levels, path_len, file: parent_file };
+        }
+
+        self.module_path.push_str("::");
+        self.module_path.push_str(&name.as_str());
+        if !inner.is_dummy() {
+            self.module_file = self.source_file_path(inner);
+        }
+        let path = self.module_path.clone();
+        let tables = self.module_tables(&path, parent_file.as_ref());
+        let levels = self.push_config(tables, store);
+        ModulePush { levels, path_len, file: parent_file }
+    }
+
+    /// Called after `pop` for the attributes of a module when it is exited.
+    pub(crate) fn pop_module(&mut self, push: ModulePush) {
+        self.module_path.truncate(push.path_len);
+        self.module_file = push.file;
+        self.pop(push.levels);
+    }
+
+    /// Returns the tables of the lint configuration file for the module at
+    /// `path`, whose parent module is in `parent_file`: the `[directory]`
+    /// tables of the directories that the module enters, and its `[module]`
+    /// table.
+    fn module_tables(&self, path: &str, parent_file: Option<&PathBuf>) -> Vec<&'s LintTable> {
+        let sess = self.sess;
+        let config = match &sess.lint_config {
+            Some(config) => config,
+            None => return vec![],
+        };
+        let in_dir =
+            |file: Option<&PathBuf>, dir: &Path| file.map_or(false, |f| f.starts_with(dir));
+        let directories = config.tables.iter().filter(|table| match &table.scope {
+            LintScope::Directory(dir) => {
+                in_dir(self.module_file.as_ref(), dir) && !in_dir(parent_file, dir)
+            }
+            _ => false,
+        });
+        let modules = config.tables.iter().filter(|table| match &table.scope {
+            LintScope::Module(module) => module == path,
+            _ => false,
+        });
+        directories.chain(modules).collect()
+    }
+
+    /// Returns the canonical path of the source file that `span` is in, if
+    /// it is a file on disk.
+    fn source_file_path(&self, span: Span) -> Option<PathBuf> {
+        match &self.sess.source_map().lookup_source_file(span.lo()).name {
+            FileName::Real(name) => {
+                let path = name.local_path();
+                Some(fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Pushes the levels set by `tables` of the lint configuration file, in
+    /// order, so that later tables override earlier ones.
+    fn push_config(&mut self, tables: Vec<&LintTable>, store: &LintStore) -> BuilderPush {
+        let mut specs = FxHashMap::default();
+        for table in tables {
+            let header = Symbol::intern(&table.header);
+            for (name, level) in &table.levels {
+                let ids = match store.find_lints(name) {
+                    Ok(ids) => ids,
+                    Err(_) => {
+                        // The lints of tools are only known when the tool runs.
+                        if self.warn_about_weird_lints && !name.contains("::") {
+                            self.sess.warn(&format!(
+                                "unknown lint `{}` in `[{}]` of the lint configuration file",
+                                name, table.header
+                            ));
+                        }
+                        continue;
+                    }
+                };
+                for id in ids {
+                    // Forbidden lints keep their level, whether they are
+                    // forbidden on the command line or by an earlier table.
+                    let forbidden = matches!(specs.get(&id), Some(&(Level::Forbid, _)))
+                        || self.sets.get_lint_id_level(id, self.cur, None).0 == Some(Level::Forbid);
+                    if forbidden && *level != Level::Forbid {
+                        continue;
+                    }
+                    self.check_gated_lint(id, DUMMY_SP);
+                    let src = LintSource::ConfigFile(Symbol::intern(name), header);
+                    specs.insert(id, (*level, src));
+                }
+            }
+        }
+
         let prev = self.cur;
         if !specs.is_empty() {
             self.cur = self.sets.list.len() as u32;
             self.sets.list.push(LintSet::Node { specs, parent: prev });
         }
-
         BuilderPush { prev, changed: prev != self.cur }
     }
 
@@ -513,7 +729,7 @@ impl<'s> LintLevelsBuilder<'s> {
     }
 
     pub fn build_map(self) -> LintLevelMap {
-        LintLevelMap { sets: self.sets, id_to_set: self.id_to_set }
+        LintLevelMap { sets: self.sets, id_to_set: self.id_to_set, expectations: self.expectations }
     }
 }
 
@@ -552,9 +768,22 @@ impl<'tcx> intravisit::Visitor<'tcx> for LintLevelMapBuilder<'_, 'tcx> {
     }
 
     fn visit_item(&mut self, it: &'tcx hir::Item<'tcx>) {
+        let module = match it.kind {
+            hir::ItemKind::Mod(ref module) => {
+                let push = self.levels.push_module(it.ident.name, module.inner, self.store);
+                if push.levels.changed {
+                    self.levels.register_id(it.hir_id);
+                }
+                Some(push)
+            }
+            _ => None,
+        };
         self.with_lint_attrs(it.hir_id, &it.attrs, |builder| {
             intravisit::walk_item(builder, it);
         });
+        if let Some(push) = module {
+            self.levels.pop_module(push);
+        }
     }
 
     fn visit_foreign_item(&mut self, it: &'tcx hir::ForeignItem<'tcx>) {
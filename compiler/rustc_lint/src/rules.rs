@@ -0,0 +1,133 @@
+//! The lints defined by a lint rules file, for `--lint-rules`.
+//!
+//! See `rustc_session::lint::rules` for the format of the file. Each rule
+//! becomes a `Lint` in the `rules` tool namespace, registered with the
+//! `LintStore` like a built-in lint, and a single late lint pass reports all
+//! of them.
+
+use crate::{LateContext, LateLintPass, LintContext, LintStore};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_hir::{ExprKind, ItemKind, Node};
+use rustc_session::lint::rules::{LintPattern, LintRule};
+use rustc_session::lint::{Lint, LintId, LintPass};
+use rustc_session::Session;
+use rustc_span::symbol::Symbol;
+use rustc_span::Span;
+
+/// Registers the lints of the lint rules file given with `--lint-rules`,
+/// their groups, and the pass that reports them.
+pub fn register_lint_rules(sess: &Session, store: &mut LintStore) {
+    let rules = match &sess.lint_rules {
+        Some(rules) => rules,
+        None => return,
+    };
+
+    // The names of the lints and of the groups are checked when the file is
+    // loaded, and they cannot clash with the lints of rustc as they are in
+    // the `rules` namespace.
+    let mut lints = vec![];
+    let mut groups: Vec<(&str, Vec<LintId>)> = vec![];
+    for rule in &rules.lints {
+        // The lints are kept by the store rather than leaked, since a process
+        // can run many sessions, e.g. for rustdoc's doctests.
+        let lint = Lint {
+            name: store.alloc_str(format!("rules::{}", rule.name.to_uppercase())),
+            default_level: rule.default_level,
+            desc: store.alloc_str(rule.description.clone()),
+            ..Lint::default_fields_for_macro()
+        };
+        let lint = store.alloc_lint(lint);
+        store.register_lints(&[lint]);
+        if let Some(group) = &rule.group {
+            match groups.iter_mut().find(|(name, _)| *name == group.as_str()) {
+                Some((_, ids)) => ids.push(LintId::of(lint)),
+                None => groups.push((group.as_str(), vec![LintId::of(lint)])),
+            }
+        }
+        lints.push((lint, rule.clone()));
+    }
+    for (group, ids) in groups {
+        let name = store.alloc_str(format!("rules::{}", group));
+        store.register_group(false, name, None, ids);
+    }
+
+    let lints = Lrc::new(lints);
+    store.register_late_pass(move || {
+        box LintRules { lints: lints.clone(), def_paths: FxHashMap::default() }
+    });
+}
+
+struct LintRules {
+    lints: Lrc<Vec<(&'static Lint, LintRule)>>,
+    /// The paths of the items used so far, see `LateContext::get_def_path`.
+    def_paths: FxHashMap<DefId, Vec<Symbol>>,
+}
+
+impl LintPass for LintRules {
+    fn name(&self) -> &'static str {
+        "LintRules"
+    }
+}
+
+impl LintRules {
+    /// Reports the lints whose pattern matches a use of `def_id` at `span`.
+    fn check_use(&mut self, cx: &LateContext<'_>, def_id: DefId, span: Span, is_call: bool) {
+        let lints = &self.lints;
+        let patterns = lints.iter().filter(|(_, rule)| match rule.pattern {
+            LintPattern::Call(_) => is_call,
+            LintPattern::Path(_) => !is_call,
+        });
+        let mut patterns = patterns.peekable();
+        if patterns.peek().is_none() {
+            return;
+        }
+
+        let def_path = self.def_paths.entry(def_id).or_insert_with(|| cx.get_def_path(def_id));
+        for (lint, rule) in patterns {
+            let segments = rule.pattern.segments();
+            let matches = def_path.len() == segments.len()
+                && def_path
+                    .iter()
+                    .zip(segments)
+                    .all(|(name, segment)| segment == "*" || *name.as_str() == **segment);
+            if matches {
+                cx.struct_span_lint(lint, span, |lint| {
+                    let mut err = lint.build(&rule.message);
+                    if let Some(help) = &rule.help {
+                        err.help(help);
+                    }
+                    err.emit();
+                });
+            }
+        }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for LintRules {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'tcx>) {
+        let callee = match expr.kind {
+            ExprKind::Call(callee, _) => match callee.kind {
+                ExprKind::Path(ref qpath) => cx.qpath_res(qpath, callee.hir_id).opt_def_id(),
+                _ => None,
+            },
+            ExprKind::MethodCall(..) => cx.typeck_results().type_dependent_def_id(expr.hir_id),
+            _ => None,
+        };
+        if let Some(def_id) = callee {
+            self.check_use(cx, def_id, expr.span, true);
+        }
+    }
+
+    fn check_path(&mut self, cx: &LateContext<'tcx>, path: &'tcx hir::Path<'tcx>, id: hir::HirId) {
+        // Importing an item is not a use of it.
+        if let Some(Node::Item(hir::Item { kind: ItemKind::Use(..), .. })) = cx.tcx.hir().find(id) {
+            return;
+        }
+        if let Some(def_id) = path.res.opt_def_id() {
+            self.check_use(cx, def_id, path.span, false);
+        }
+    }
+}
@@ -538,6 +538,36 @@ declare_lint! {
     "detects attributes that were not used by the compiler"
 }
 
+declare_lint! {
+    /// The `unfulfilled_lint_expectations` lint detects `#[expect]`
+    /// attributes whose lints were never emitted.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![feature(lint_reasons)]
+    ///
+    /// #[expect(unused_variables)]
+    /// fn main() {
+    ///     let x = 5;
+    ///     println!("{}", x);
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// `#[expect(lint)]` silences `lint` like `#[allow(lint)]` does, but it
+    /// also states that the lint is expected to fire in its scope. Once the
+    /// code has changed so that the lint is no longer emitted, the attribute
+    /// has become useless and should be removed.
+    pub UNFULFILLED_LINT_EXPECTATIONS,
+    Warn,
+    "unfulfilled lint expectation",
+    @feature_gate = sym::lint_reasons;
+}
+
 declare_lint! {
     /// The `unreachable_code` lint detects unreachable code paths.
     ///
@@ -2769,6 +2799,7 @@ declare_lint_pass! {
         BINDINGS_WITH_VARIANT_NAME,
         UNUSED_MACROS,
         WARNINGS,
+        UNFULFILLED_LINT_EXPECTATIONS,
         UNUSED_FEATURES,
         STABLE_FEATURES,
         UNKNOWN_CRATE_TYPES,
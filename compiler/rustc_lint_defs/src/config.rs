@@ -0,0 +1,251 @@
+//! Lint configuration files, for `-Z lint-config`.
+//!
+//! A lint configuration file sets lint levels outside of the source, e.g.
+//! for a whole workspace from a file checked in next to it. It is written in
+//! a subset of [TOML]: tables of lint or lint group names and levels, where
+//! the header of a table says which code the levels apply to.
+//!
+//! ```toml
+//! # Every crate compiled with this file.
+//! [lints]
+//! missing_docs = "warn"
+//!
+//! # The crate `my_crate`.
+//! [crate.my_crate]
+//! unsafe_code = "forbid"
+//!
+//! # The module `my_crate::ffi` and its submodules.
+//! [module."my_crate::ffi"]
+//! non_camel_case_types = "allow"
+//!
+//! # The modules in source files in `src/generated`, relative to the
+//! # directory of this file.
+//! [directory."src/generated"]
+//! unused = "allow"
+//! "clippy::all" = "allow"
+//! ```
+//!
+//! The levels are `allow`, `warn`, `deny` and `forbid`. They override the
+//! levels set on the command line, and are overridden by lint attributes,
+//! in the same way as an attribute on the crate or module would be: the
+//! tables are applied from the least to the most specific, i.e. `[lints]`,
+//! then `[crate]`, then for each module from the crate root inward, the
+//! `[directory]` tables that the module enters and its `[module]` table, and
+//! then the lint attributes of the module. Lints that are forbidden keep
+//! their level, and `--force-warn` and `--cap-lints` apply as usual.
+//!
+//! [TOML]: https://toml.io
+
+use crate::Level;
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The contents of a lint configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintConfig {
+    /// The path of the file, as given on the command line.
+    pub path: PathBuf,
+    pub tables: Vec<LintTable>,
+}
+
+/// A table of a lint configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintTable {
+    /// The header of the table as written, without the brackets.
+    pub header: String,
+    pub scope: LintScope,
+    /// The lint and lint group names of the table, with their levels, in the
+    /// order of the file.
+    pub levels: Vec<(String, Level)>,
+}
+
+/// The code that the levels of a `LintTable` apply to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintScope {
+    /// `[lints]`: every crate.
+    All,
+    /// `[crate.<name>]`: the crate with the given name.
+    Crate(String),
+    /// `[module."<path>"]`: the module with the given path, starting with the
+    /// crate name, and its submodules.
+    Module(String),
+    /// `[directory."<path>"]`: the modules whose source file is in the given
+    /// directory. The path is resolved relative to the directory of the
+    /// configuration file when it is loaded.
+    Directory(PathBuf),
+}
+
+/// An error found while loading a lint configuration or lint rules file.
+#[derive(Debug, PartialEq)]
+pub enum LintConfigError {
+    /// The file could not be read.
+    Io { file: String, error: String },
+    /// A line of the file is not valid.
+    Syntax { file: String, line: usize, msg: String },
+}
+
+impl fmt::Display for LintConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintConfigError::Io { file, error } => {
+                write!(f, "failed to read `{}`: {}", file, error)
+            }
+            LintConfigError::Syntax { file, line, msg } => write!(f, "{}:{}: {}", file, line, msg),
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn load(path: &Path) -> Result<LintConfig, LintConfigError> {
+        let src = fs::read_to_string(path).map_err(|e| LintConfigError::Io {
+            file: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+        LintConfig::parse(path, &src)
+    }
+
+    /// Parses the contents `src` of the file at `path`.
+    pub fn parse(path: &Path, src: &str) -> Result<LintConfig, LintConfigError> {
+        let syntax = |line, msg: String| {
+            Err(LintConfigError::Syntax { file: path.display().to_string(), line, msg })
+        };
+
+        let mut tables: Vec<LintTable> = vec![];
+        for (i, line) in src.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let (keys, rest) = match parse_header(header) {
+                    Some(header) => header,
+                    None => return syntax(line_number, "expected a table header".to_string()),
+                };
+                if !is_end(rest) {
+                    return syntax(line_number, "expected the end of the line".to_string());
+                }
+                let header = header[..header.len() - rest.len() - 1].trim().to_string();
+                let scope = match &keys[..] {
+                    [table] if table == "lints" => LintScope::All,
+                    [table, name] if table == "crate" => LintScope::Crate(name.clone()),
+                    [table, module] if table == "module" => LintScope::Module(module.clone()),
+                    [table, dir] if table == "directory" => {
+                        let dir = path.parent().unwrap_or_else(|| Path::new("")).join(dir);
+                        LintScope::Directory(fs::canonicalize(&dir).unwrap_or(dir))
+                    }
+                    _ => {
+                        let msg = format!(
+                            "unknown table `[{}]`, expected `[lints]`, `[crate.<name>]`, \
+                             `[module.\"<path>\"]` or `[directory.\"<path>\"]`",
+                            header
+                        );
+                        return syntax(line_number, msg);
+                    }
+                };
+                if tables.iter().any(|table| table.scope == scope) {
+                    let msg = format!("table `[{}]` is defined more than once", header);
+                    return syntax(line_number, msg);
+                }
+                tables.push(LintTable { header, scope, levels: vec![] });
+                continue;
+            }
+
+            let (name, level) = match parse_entry(line) {
+                Some(entry) => entry,
+                None => return syntax(line_number, "expected `lint = \"level\"`".to_string()),
+            };
+            let level = match parse_level(&level) {
+                Ok(level) => level,
+                Err(msg) => return syntax(line_number, msg),
+            };
+            let table = match tables.last_mut() {
+                Some(table) => table,
+                None => return syntax(line_number, "lint level outside of a table".to_string()),
+            };
+            if table.levels.iter().any(|(n, _)| *n == name) {
+                return syntax(line_number, format!("`{}` is set more than once", name));
+            }
+            table.levels.push((name, level));
+        }
+        Ok(LintConfig { path: path.to_path_buf(), tables })
+    }
+}
+
+/// Parses a lint level, which can be `allow`, `warn`, `deny` or `forbid`.
+pub(crate) fn parse_level(level: &str) -> Result<Level, String> {
+    match Level::from_str(level) {
+        Some(Level::Expect) | Some(Level::ForceWarn) | None => {
+            Err(format!("invalid level `{}`, expected `allow`, `warn`, `deny` or `forbid`", level))
+        }
+        Some(level) => Ok(level),
+    }
+}
+
+/// Parses the keys of a table header, after its opening bracket, returning
+/// them and the rest of the line after the closing bracket.
+pub(crate) fn parse_header(mut s: &str) -> Option<(Vec<String>, &str)> {
+    let mut keys = vec![];
+    loop {
+        let (key, rest) = parse_key(s)?;
+        keys.push(key);
+        let rest = rest.trim_start();
+        if let Some(rest) = rest.strip_prefix('.') {
+            s = rest;
+        } else {
+            return Some((keys, rest.strip_prefix(']')?));
+        }
+    }
+}
+
+/// Parses a `key = "value"` line.
+pub(crate) fn parse_entry(s: &str) -> Option<(String, String)> {
+    let (key, rest) = parse_key(s)?;
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start().strip_prefix('"')?;
+    let (value, rest) = parse_string(rest)?;
+    if is_end(rest) { Some((key, value)) } else { None }
+}
+
+/// Parses a bare key or a quoted key at the start of `s`, returning it and
+/// the rest of `s`.
+fn parse_key(s: &str) -> Option<(String, &str)> {
+    let s = s.trim_start();
+    if let Some(rest) = s.strip_prefix('"') {
+        return parse_string(rest);
+    }
+    let end = s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+    let end = end.unwrap_or(s.len());
+    if end == 0 { None } else { Some((s[..end].to_string(), &s[end..])) }
+}
+
+/// Parses a basic string after its opening quote, returning its value and
+/// the rest of `s` after the closing quote. Only the `\"` and `\\` escapes
+/// are supported.
+fn parse_string(s: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &s[i + 1..])),
+            '\\' => match chars.next()?.1 {
+                '"' => value.push('"'),
+                '\\' => value.push('\\'),
+                _ => return None,
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Returns whether `s` is the end of a line, possibly with a comment.
+pub(crate) fn is_end(s: &str) -> bool {
+    let s = s.trim_start();
+    s.is_empty() || s.starts_with('#')
+}
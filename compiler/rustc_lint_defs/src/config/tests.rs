@@ -0,0 +1,84 @@
+use super::*;
+
+fn parse(src: &str) -> Result<LintConfig, LintConfigError> {
+    LintConfig::parse(Path::new("lints.toml"), src)
+}
+
+fn syntax_error(line: usize, msg: &str) -> Result<LintConfig, LintConfigError> {
+    Err(LintConfigError::Syntax { file: "lints.toml".to_string(), line, msg: msg.to_string() })
+}
+
+#[test]
+fn tables() {
+    let config = parse(
+        "# Lint levels.\n\
+         [lints]\n\
+         missing_docs = \"warn\"\n\
+         \n\
+         [crate.my_crate]  # comment\n\
+         unsafe_code = \"forbid\"\n\
+         \"clippy::all\" = \"allow\"\n\
+         [ module . \"my_crate::ffi\" ]\n\
+         non_camel_case_types = \"allow\" # comment\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config.tables,
+        vec![
+            LintTable {
+                header: "lints".to_string(),
+                scope: LintScope::All,
+                levels: vec![("missing_docs".to_string(), Level::Warn)],
+            },
+            LintTable {
+                header: "crate.my_crate".to_string(),
+                scope: LintScope::Crate("my_crate".to_string()),
+                levels: vec![
+                    ("unsafe_code".to_string(), Level::Forbid),
+                    ("clippy::all".to_string(), Level::Allow),
+                ],
+            },
+            LintTable {
+                header: "module . \"my_crate::ffi\"".to_string(),
+                scope: LintScope::Module("my_crate::ffi".to_string()),
+                levels: vec![("non_camel_case_types".to_string(), Level::Allow)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn directories_are_relative_to_the_file() {
+    let config =
+        LintConfig::parse(Path::new("config/lints.toml"), "[directory.\"src/gen\"]\n").unwrap();
+    assert_eq!(config.tables[0].scope, LintScope::Directory(PathBuf::from("config/src/gen")));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(parse("unused = \"allow\""), syntax_error(1, "lint level outside of a table"));
+    assert_eq!(
+        parse("[lints]\nunused = \"expect\""),
+        syntax_error(2, "invalid level `expect`, expected `allow`, `warn`, `deny` or `forbid`")
+    );
+    assert_eq!(
+        parse("[lints]\nunused = \"allow\"\nunused = \"warn\""),
+        syntax_error(3, "`unused` is set more than once")
+    );
+    assert_eq!(
+        parse("[lints]\n[crate.a]\n[lints]"),
+        syntax_error(3, "table `[lints]` is defined more than once")
+    );
+    assert_eq!(
+        parse("[lint]"),
+        syntax_error(
+            1,
+            "unknown table `[lint]`, expected `[lints]`, `[crate.<name>]`, \
+             `[module.\"<path>\"]` or `[directory.\"<path>\"]`"
+        )
+    );
+    assert_eq!(parse("[lints]\nunused = 'allow'"), syntax_error(2, "expected `lint = \"level\"`"));
+    assert_eq!(parse("[[lints]]"), syntax_error(1, "expected a table header"));
+    assert_eq!(parse("[lints] x"), syntax_error(1, "expected the end of the line"));
+    assert_eq!(parse("[module.\"a\\n\"]"), syntax_error(1, "expected a table header"));
+}
@@ -8,6 +8,8 @@ use rustc_span::edition::Edition;
 use rustc_span::{sym, symbol::Ident, MultiSpan, Span, Symbol};
 
 pub mod builtin;
+pub mod config;
+pub mod rules;
 
 #[macro_export]
 macro_rules! pluralize {
@@ -44,7 +46,15 @@ pub enum Applicability {
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 pub enum Level {
     Allow,
+    /// Like `Allow`, but the `#[expect]` attribute that set this level is
+    /// itself reported if the lint is never emitted in its scope. See
+    /// `LintExpectationId`.
+    Expect,
     Warn,
+    /// A warning requested with `--force-warn`. It overrides the level set
+    /// by any attribute and by `--cap-lints`, and is never turned into an
+    /// error, not even by `-D warnings`.
+    ForceWarn,
     Deny,
     Forbid,
 }
@@ -56,7 +66,9 @@ impl Level {
     pub fn as_str(self) -> &'static str {
         match self {
             Level::Allow => "allow",
+            Level::Expect => "expect",
             Level::Warn => "warn",
+            Level::ForceWarn => "force-warn",
             Level::Deny => "deny",
             Level::Forbid => "forbid",
         }
@@ -66,7 +78,9 @@ impl Level {
     pub fn from_str(x: &str) -> Option<Level> {
         match x {
             "allow" => Some(Level::Allow),
+            "expect" => Some(Level::Expect),
             "warn" => Some(Level::Warn),
+            "force-warn" => Some(Level::ForceWarn),
             "deny" => Some(Level::Deny),
             "forbid" => Some(Level::Forbid),
             _ => None,
@@ -77,12 +91,32 @@ impl Level {
     pub fn from_symbol(x: Symbol) -> Option<Level> {
         match x {
             sym::allow => Some(Level::Allow),
+            sym::expect => Some(Level::Expect),
             sym::warn => Some(Level::Warn),
             sym::deny => Some(Level::Deny),
             sym::forbid => Some(Level::Forbid),
             _ => None,
         }
     }
+
+    /// Whether a lint at this level is silenced where it is emitted, i.e.
+    /// whether it is allowed or expected.
+    pub fn is_allow_like(self) -> bool {
+        matches!(self, Level::Allow | Level::Expect)
+    }
+}
+
+/// Identifies one lint name in an `#[expect]` attribute, by the span of that
+/// name.
+///
+/// Lints emitted at `Level::Expect` carry this id to the diagnostic handler,
+/// which records it as fulfilled instead of emitting anything. Spans are used
+/// rather than `HirId`s so that lints from the early (AST-based) lint passes,
+/// which use their own lint level builder, refer to the same expectations as
+/// the late ones.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Encodable, Decodable, HashStable_Generic)]
+pub struct LintExpectationId {
+    pub span: Span,
 }
 
 /// Specification of a single lint.
@@ -0,0 +1,216 @@
+//! Lint rules files, for `--lint-rules`.
+//!
+//! A lint rules file defines lints outside of the compiler, without a
+//! plugin: each lint reports the uses of the functions, methods, types or
+//! other items with a given path. The lints and their groups are in the
+//! `rules` tool namespace, e.g. `rules::option_unwrap`, and they are
+//! registered like the built-in ones, so their levels are set in the same
+//! way, e.g. with `-D rules::option_unwrap` or `#[allow(rules::acme)]`, and
+//! they are capped by `--cap-lints`. As for other tool lints, attributes
+//! that name lints of the namespace that are not defined are ignored, so the
+//! code still builds without the file. The file is written in the same
+//! subset of TOML as lint configuration files (see `config`), with a table
+//! for each lint.
+//!
+//! ```toml
+//! [lint.option_unwrap]
+//! # What the lint detects, as listed by `rustc -W help`.
+//! description = "calls of `Option::unwrap`"
+//! # The calls of the function or method with this path.
+//! call = "core::option::Option::unwrap"
+//! message = "called `Option::unwrap`"
+//! # Optional: a help message, the default level (`warn` by default), and a
+//! # lint group that the lint belongs to.
+//! help = "handle the `None` case, or use `expect` with a reason"
+//! level = "deny"
+//! group = "acme"
+//!
+//! [lint.rc]
+//! description = "uses of `Rc`"
+//! # The uses of the item with this path, e.g. as a type or in an
+//! # expression.
+//! path = "alloc::rc::Rc"
+//! message = "used `Rc`"
+//! ```
+//!
+//! Paths start with the name of the crate that defines the item, which for
+//! items of the standard library is usually `core` or `alloc` rather than
+//! `std`. A segment `*` matches any name, e.g. `std::process::*`.
+
+use crate::config::{is_end, parse_entry, parse_header, parse_level, LintConfigError};
+use crate::Level;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The contents of a lint rules file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintRules {
+    /// The path of the file, as given on the command line.
+    pub path: PathBuf,
+    pub lints: Vec<LintRule>,
+}
+
+/// A lint defined by a lint rules file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintRule {
+    /// The name of the lint, in lowercase.
+    pub name: String,
+    pub default_level: Level,
+    pub description: String,
+    /// The name of the lint group that the lint belongs to.
+    pub group: Option<String>,
+    pub pattern: LintPattern,
+    /// The message of the lint's diagnostics.
+    pub message: String,
+    pub help: Option<String>,
+}
+
+/// The code that a `LintRule` reports, given by the path of an item split
+/// into segments, where `*` matches any name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintPattern {
+    /// `call = "<path>"`: the calls of a function or method.
+    Call(Vec<String>),
+    /// `path = "<path>"`: the uses of an item.
+    Path(Vec<String>),
+}
+
+impl LintPattern {
+    pub fn segments(&self) -> &[String] {
+        match self {
+            LintPattern::Call(segments) | LintPattern::Path(segments) => segments,
+        }
+    }
+}
+
+/// Returns whether `name` is a valid name for a lint or a lint group.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_'))
+}
+
+impl LintRules {
+    pub fn load(path: &Path) -> Result<LintRules, LintConfigError> {
+        let src = fs::read_to_string(path).map_err(|e| LintConfigError::Io {
+            file: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+        LintRules::parse(path, &src)
+    }
+
+    /// Parses the contents `src` of the file at `path`.
+    pub fn parse(path: &Path, src: &str) -> Result<LintRules, LintConfigError> {
+        let syntax = |line, msg: String| LintConfigError::Syntax {
+            file: path.display().to_string(),
+            line,
+            msg,
+        };
+
+        // The lints, with the line of their table and their keys.
+        let mut tables: Vec<(usize, String, Vec<(String, String)>)> = vec![];
+        for (i, line) in src.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let name = match parse_header(header) {
+                    Some((keys, rest)) if is_end(rest) => match &keys[..] {
+                        [table, name] if table == "lint" => name.clone(),
+                        _ => {
+                            let msg = "expected a `[lint.<name>]` table".to_string();
+                            return Err(syntax(line_number, msg));
+                        }
+                    },
+                    _ => return Err(syntax(line_number, "expected a table header".to_string())),
+                };
+                if !is_valid_name(&name) {
+                    let msg = format!(
+                        "invalid lint name `{}`, expected lowercase letters, digits and `_`",
+                        name
+                    );
+                    return Err(syntax(line_number, msg));
+                }
+                if tables.iter().any(|(_, n, _)| *n == name) {
+                    let msg = format!("lint `{}` is defined more than once", name);
+                    return Err(syntax(line_number, msg));
+                }
+                tables.push((line_number, name, vec![]));
+                continue;
+            }
+
+            let (key, value) = match parse_entry(line) {
+                Some(entry) => entry,
+                None => return Err(syntax(line_number, "expected `key = \"value\"`".to_string())),
+            };
+            let keys = match tables.last_mut() {
+                Some((_, _, keys)) => keys,
+                None => return Err(syntax(line_number, "key outside of a table".to_string())),
+            };
+            match &key[..] {
+                "description" | "call" | "path" | "message" | "help" | "level" | "group" => {}
+                _ => return Err(syntax(line_number, format!("unknown key `{}`", key))),
+            }
+            if keys.iter().any(|(k, _)| *k == key) {
+                return Err(syntax(line_number, format!("`{}` is set more than once", key)));
+            }
+            if key == "level" {
+                parse_level(&value).map_err(|msg| syntax(line_number, msg))?;
+            }
+            keys.push((key, value));
+        }
+
+        // A group cannot be named like a lint, as both are in the `rules`
+        // namespace.
+        for (line_number, name, keys) in &tables {
+            let group = match keys.iter().find(|(k, _)| k == "group") {
+                Some((_, group)) => group,
+                None => continue,
+            };
+            if !is_valid_name(group) {
+                let msg = format!(
+                    "invalid lint group name `{}`, expected lowercase letters, digits and `_`",
+                    group
+                );
+                return Err(syntax(*line_number, msg));
+            }
+            if tables.iter().any(|(_, n, _)| n == group) {
+                let msg = format!("the group `{}` of lint `{}` is also a lint", group, name);
+                return Err(syntax(*line_number, msg));
+            }
+        }
+
+        let mut lints = vec![];
+        for (line_number, name, keys) in tables {
+            let get = |key: &str| keys.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+            let required = |key: &str| {
+                get(key)
+                    .ok_or_else(|| syntax(line_number, format!("lint `{}` has no `{}`", name, key)))
+            };
+            let split = |path: String| path.split("::").map(|s| s.trim().to_string()).collect();
+            let pattern = match (get("call"), get("path")) {
+                (Some(call), None) => LintPattern::Call(split(call)),
+                (None, Some(path)) => LintPattern::Path(split(path)),
+                _ => {
+                    let msg = format!("lint `{}` must have either a `call` or a `path`", name);
+                    return Err(syntax(line_number, msg));
+                }
+            };
+            lints.push(LintRule {
+                default_level: get("level").map_or(Level::Warn, |l| parse_level(&l).unwrap()),
+                description: required("description")?,
+                group: get("group"),
+                pattern,
+                message: required("message")?,
+                help: get("help"),
+                name,
+            });
+        }
+        Ok(LintRules { path: path.to_path_buf(), lints })
+    }
+}
@@ -0,0 +1,96 @@
+use super::*;
+
+fn parse(src: &str) -> Result<LintRules, LintConfigError> {
+    LintRules::parse(Path::new("rules.toml"), src)
+}
+
+fn syntax_error(line: usize, msg: &str) -> Result<LintRules, LintConfigError> {
+    Err(LintConfigError::Syntax { file: "rules.toml".to_string(), line, msg: msg.to_string() })
+}
+
+#[test]
+fn lints() {
+    let rules = parse(
+        "[lint.option_unwrap]\n\
+         description = \"calls of `Option::unwrap`\"\n\
+         call = \"core::option::Option::unwrap\"\n\
+         message = \"called `Option::unwrap`\"\n\
+         help = \"use `expect`\"\n\
+         level = \"deny\"\n\
+         group = \"acme\"\n\
+         \n\
+         # Any item of the module.\n\
+         [lint.process]\n\
+         description = \"uses of `std::process`\"\n\
+         path = \"std :: process :: *\"\n\
+         message = \"used `std::process`\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        rules.lints,
+        vec![
+            LintRule {
+                name: "option_unwrap".to_string(),
+                default_level: Level::Deny,
+                description: "calls of `Option::unwrap`".to_string(),
+                group: Some("acme".to_string()),
+                pattern: LintPattern::Call(vec![
+                    "core".to_string(),
+                    "option".to_string(),
+                    "Option".to_string(),
+                    "unwrap".to_string(),
+                ]),
+                message: "called `Option::unwrap`".to_string(),
+                help: Some("use `expect`".to_string()),
+            },
+            LintRule {
+                name: "process".to_string(),
+                default_level: Level::Warn,
+                description: "uses of `std::process`".to_string(),
+                group: None,
+                pattern: LintPattern::Path(vec![
+                    "std".to_string(),
+                    "process".to_string(),
+                    "*".to_string(),
+                ]),
+                message: "used `std::process`".to_string(),
+                help: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(parse("[lints]"), syntax_error(1, "expected a `[lint.<name>]` table"));
+    assert_eq!(
+        parse("[lint.Unwrap]"),
+        syntax_error(1, "invalid lint name `Unwrap`, expected lowercase letters, digits and `_`")
+    );
+    assert_eq!(parse("[lint.a]\n[lint.a]"), syntax_error(2, "lint `a` is defined more than once"));
+    assert_eq!(parse("call = \"f\""), syntax_error(1, "key outside of a table"));
+    assert_eq!(parse("[lint.a]\nkind = \"call\""), syntax_error(2, "unknown key `kind`"));
+    assert_eq!(
+        parse("[lint.a]\nlevel = \"expect\""),
+        syntax_error(2, "invalid level `expect`, expected `allow`, `warn`, `deny` or `forbid`")
+    );
+    assert_eq!(
+        parse("[lint.a]\ncall = \"f\"\npath = \"f\""),
+        syntax_error(1, "lint `a` must have either a `call` or a `path`")
+    );
+    assert_eq!(
+        parse("[lint.a]\ncall = \"f\"\nmessage = \"m\""),
+        syntax_error(1, "lint `a` has no `description`")
+    );
+    assert_eq!(
+        parse("[lint.a]\ngroup = \"Acme\""),
+        syntax_error(
+            1,
+            "invalid lint group name `Acme`, expected lowercase letters, digits and `_`"
+        )
+    );
+    assert_eq!(
+        parse("[lint.a]\ngroup = \"b\"\n[lint.b]"),
+        syntax_error(1, "the group `b` of lint `a` is also a lint")
+    );
+}
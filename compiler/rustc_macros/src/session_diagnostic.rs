@@ -39,6 +39,14 @@ use std::collections::{BTreeSet, HashMap};
 ///     opt_sugg: Some(suggestion, Applicability::MachineApplicable),
 /// });
 /// ```
+///
+/// With a `#[slug = "borrowck-move-out-of-borrow"]` attribute on the struct, the messages can be
+/// translated (see `rustc_errors::translation`): the primary message is looked up in the catalog
+/// of `-Z translation-locale` as `borrowck-move-out-of-borrow`, and the label and suggestion
+/// messages of a field as `borrowck-move-out-of-borrow.<field>`, e.g.
+/// `borrowck-move-out-of-borrow.other_span`. The fields that an English message refers to are
+/// passed as arguments to the translated message, formatted with `Display`. If there is no
+/// translation, the English message is used.
 pub fn session_diagnostic_derive(s: synstructure::Structure<'_>) -> proc_macro2::TokenStream {
     // Names for the diagnostic we build and the session we build it from.
     let diag = format_ident!("diag");
@@ -149,7 +157,13 @@ impl<'a> SessionDiagnosticDerive<'a> {
         }
 
         Self {
-            builder: SessionDiagnosticDeriveBuilder { diag, sess, fields: fields_map, kind: None },
+            builder: SessionDiagnosticDeriveBuilder {
+                diag,
+                sess,
+                fields: fields_map,
+                kind: None,
+                slug: None,
+            },
             structure,
         }
     }
@@ -161,6 +175,15 @@ impl<'a> SessionDiagnosticDerive<'a> {
 
         let implementation = {
             if let syn::Data::Struct(..) = ast.data {
+                // The slug is needed to build all messages, including those of attributes that
+                // come before it.
+                let slug = attrs
+                    .iter()
+                    .filter(|attr| attr.path.is_ident("slug"))
+                    .map(|attr| builder.set_slug_once(attr))
+                    .collect::<Result<Vec<_>, _>>();
+                let slug_error = slug.err().map(|e| e.to_compile_error());
+
                 let preamble = {
                     let preamble = attrs.iter().map(|attr| {
                         builder
@@ -168,6 +191,7 @@ impl<'a> SessionDiagnosticDerive<'a> {
                             .unwrap_or_else(|v| v.to_compile_error())
                     });
                     quote! {
+                        #slug_error
                         #(#preamble)*;
                     }
                 };
@@ -268,6 +292,10 @@ struct SessionDiagnosticDeriveBuilder<'a> {
     /// stores at what Span the kind was first set at (for error reporting purposes, if the kind
     /// was multiply specified).
     kind: Option<(DiagnosticId, proc_macro2::Span)>,
+
+    /// The key of the primary message in the translation catalogs, from the `#[slug = "..."]`
+    /// attribute. If it is not set, the messages are not translated.
+    slug: Option<String>,
 }
 
 impl<'a> SessionDiagnosticDeriveBuilder<'a> {
@@ -277,17 +305,20 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
     ) -> Result<proc_macro2::TokenStream, SessionDiagnosticDeriveError> {
         Ok(match attr.parse_meta()? {
             syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(s), .. }) => {
-                let formatted_str = self.build_format(&s.value(), attr.span());
                 let name = attr.path.segments.last().unwrap().ident.to_string();
                 let name = name.as_str();
                 match name {
+                    // Already handled before any other attribute.
+                    "slug" => quote! {},
                     "message" => {
                         let diag = &self.diag;
+                        let message = self.build_message(None, &s.value(), attr.span());
                         quote! {
-                            #diag.set_primary_message(#formatted_str);
+                            #diag.set_primary_message(#message);
                         }
                     }
                     attr @ "error" | attr @ "lint" => {
+                        let formatted_str = self.build_format(&s.value(), attr.span());
                         self.set_kind_once(
                             if attr == "error" {
                                 DiagnosticId::Error(formatted_str)
@@ -315,6 +346,22 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
         })
     }
 
+    fn set_slug_once(&mut self, attr: &syn::Attribute) -> Result<(), SessionDiagnosticDeriveError> {
+        match attr.parse_meta()? {
+            syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(s), .. }) => {
+                if self.slug.is_some() {
+                    throw_span_err!(attr.span().unwrap(), "`slug` specified multiple times");
+                }
+                self.slug = Some(s.value());
+                Ok(())
+            }
+            _ => throw_span_err!(
+                attr.span().unwrap(),
+                "the `slug` attribute must be of the form `#[slug = \"...\"]`"
+            ),
+        }
+    }
+
     #[must_use]
     fn set_kind_once(
         &mut self,
@@ -382,16 +429,26 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
         let name = name.as_str();
         // At this point, we need to dispatch based on the attribute key + the
         // type.
+        // The key of label and suggestion messages in the translation catalogs.
+        let field_key = info.binding.ast().ident.as_ref().map(|ident| ident.to_string());
+        if self.slug.is_some() && field_key.is_none() {
+            throw_span_err!(
+                attr.span().unwrap(),
+                "translated messages can only be used on named fields",
+                |diag| diag.help("the messages of a field are keyed by the name of the field")
+            );
+        }
         let meta = attr.parse_meta()?;
         Ok(match meta {
             syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(s), .. }) => {
-                let formatted_str = self.build_format(&s.value(), attr.span());
+                let value = s.value();
                 match name {
                     "message" => {
                         if type_matches_path(&info.ty, &["rustc_span", "Span"]) {
+                            let message = self.build_message(None, &value, attr.span());
                             quote! {
                                 #diag.set_span(*#field_binding);
-                                #diag.set_primary_message(#formatted_str);
+                                #diag.set_primary_message(#message);
                             }
                         } else {
                             throw_span_err!(
@@ -402,8 +459,10 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
                     }
                     "label" => {
                         if type_matches_path(&info.ty, &["rustc_span", "Span"]) {
+                            let message =
+                                self.build_message(field_key.as_deref(), &value, attr.span());
                             quote! {
-                                #diag.span_label(*#field_binding, #formatted_str);
+                                #diag.span_label(*#field_binding, #message);
                             }
                         } else {
                             throw_span_err!(
@@ -512,13 +571,16 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
                                         .ident
                                         .to_string();
                                     let name = name.as_str();
-                                    let formatted_str = self.build_format(&s.value(), arg.span());
                                     match name {
                                         "message" => {
-                                            msg = Some(formatted_str);
+                                            msg = Some(self.build_message(
+                                                field_key.as_deref(),
+                                                &s.value(),
+                                                arg.span(),
+                                            ));
                                         }
                                         "code" => {
-                                            code = Some(formatted_str);
+                                            code = Some(self.build_format(&s.value(), arg.span()));
                                         }
                                         other => throw_span_err!(
                                             arg.span().unwrap(),
@@ -575,47 +637,8 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
     /// ```
     /// This function builds the entire call to format!.
     fn build_format(&self, input: &String, span: proc_macro2::Span) -> proc_macro2::TokenStream {
-        // This set is used later to generate the final format string. To keep builds reproducible,
-        // the iteration order needs to be deterministic, hence why we use a BTreeSet here instead
-        // of a HashSet.
-        let mut referenced_fields: BTreeSet<String> = BTreeSet::new();
-
-        // At this point, we can start parsing the format string.
-        let mut it = input.chars().peekable();
-        // Once the start of a format string has been found, process the format string and spit out
-        // the referenced fields. Leaves `it` sitting on the closing brace of the format string, so the
-        // next call to `it.next()` retrieves the next character.
-        while let Some(c) = it.next() {
-            if c == '{' && *it.peek().unwrap_or(&'\0') != '{' {
-                #[must_use]
-                let mut eat_argument = || -> Option<String> {
-                    let mut result = String::new();
-                    // Format specifiers look like
-                    // format   := '{' [ argument ] [ ':' format_spec ] '}' .
-                    // Therefore, we only need to eat until ':' or '}' to find the argument.
-                    while let Some(c) = it.next() {
-                        result.push(c);
-                        let next = *it.peek().unwrap_or(&'\0');
-                        if next == '}' {
-                            break;
-                        } else if next == ':' {
-                            // Eat the ':' character.
-                            assert_eq!(it.next().unwrap(), ':');
-                            break;
-                        }
-                    }
-                    // Eat until (and including) the matching '}'
-                    while it.next()? != '}' {
-                        continue;
-                    }
-                    Some(result)
-                };
+        let referenced_fields = referenced_fields(input);
 
-                if let Some(referenced_field) = eat_argument() {
-                    referenced_fields.insert(referenced_field);
-                }
-            }
-        }
         // At this point, `referenced_fields` contains a set of the unique fields that were
         // referenced in the format string. Generate the corresponding "x = self.x" format
         // string parameters:
@@ -645,6 +668,92 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
             format!(#input #(,#args)*)
         }
     }
+
+    /// Builds the expression for a message written as `input` in the attributes. Without a slug,
+    /// this is the call to format! from `build_format`. With a slug, the message is first looked
+    /// up in the translation catalog, as the slug itself for the primary message, or as
+    /// `<slug>.<field>` for the messages of the field `field`:
+    /// ```ignore (not-usage-example)
+    /// sess.diagnostic()
+    ///     .translate("my-slug.span", &[("x", self.x.to_string()), ("y", self.y.to_string())])
+    ///     .unwrap_or_else(|| format!("Expected a point greater than ({x}, {y})", ...))
+    /// ```
+    fn build_message(
+        &self,
+        field: Option<&str>,
+        input: &String,
+        span: proc_macro2::Span,
+    ) -> proc_macro2::TokenStream {
+        let formatted_str = self.build_format(input, span);
+        let slug = match &self.slug {
+            Some(slug) => slug,
+            None => return formatted_str,
+        };
+        let key = match field {
+            Some(field) => format!("{}.{}", slug, field),
+            None => slug.clone(),
+        };
+        // `build_format` has already reported the references to fields that don't exist.
+        let args = referenced_fields(input)
+            .into_iter()
+            .filter(|f| self.fields.contains_key(f))
+            .map(|field| {
+                let field_ident = format_ident!("{}", field);
+                quote! {
+                    (#field, self.#field_ident.to_string())
+                }
+            });
+        let sess = &self.sess;
+        quote! {
+            #sess.diagnostic().translate(#key, &[#(#args),*]).unwrap_or_else(|| #formatted_str)
+        }
+    }
+}
+
+/// Returns the fields referenced in the format string `input`, e.g. `x` and `y` for
+/// `"Expected a point greater than ({x}, {y})"`.
+fn referenced_fields(input: &str) -> BTreeSet<String> {
+    // To keep builds reproducible, the iteration order needs to be deterministic, hence why we
+    // use a BTreeSet here instead of a HashSet.
+    let mut referenced_fields: BTreeSet<String> = BTreeSet::new();
+
+    let mut it = input.chars().peekable();
+    // Once the start of a format string has been found, process the format string and spit out
+    // the referenced fields. Leaves `it` sitting on the closing brace of the format string, so the
+    // next call to `it.next()` retrieves the next character.
+    while let Some(c) = it.next() {
+        if c == '{' && *it.peek().unwrap_or(&'\0') != '{' {
+            #[must_use]
+            let mut eat_argument = || -> Option<String> {
+                let mut result = String::new();
+                // Format specifiers look like
+                // format   := '{' [ argument ] [ ':' format_spec ] '}' .
+                // Therefore, we only need to eat until ':' or '}' to find the argument.
+                while let Some(c) = it.next() {
+                    result.push(c);
+                    let next = *it.peek().unwrap_or(&'\0');
+                    if next == '}' {
+                        break;
+                    } else if next == ':' {
+                        // Eat the ':' character.
+                        assert_eq!(it.next().unwrap(), ':');
+                        break;
+                    }
+                }
+                // Eat until (and including) the matching '}'
+                while it.next()? != '}' {
+                    continue;
+                }
+                Some(result)
+            };
+
+            if let Some(referenced_field) = eat_argument() {
+                referenced_fields.insert(referenced_field);
+            }
+        }
+    }
+
+    referenced_fields
 }
 
 /// If `ty` is an Option, returns Some(inner type). Else, returns None.
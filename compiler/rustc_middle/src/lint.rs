@@ -5,7 +5,7 @@ use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_errors::{DiagnosticBuilder, DiagnosticId};
 use rustc_hir::HirId;
-use rustc_session::lint::{builtin, Level, Lint, LintId};
+use rustc_session::lint::{builtin, Level, Lint, LintExpectationId, LintId};
 use rustc_session::{DiagnosticMessageId, Session};
 use rustc_span::hygiene::MacroKind;
 use rustc_span::source_map::{DesugaringKind, ExpnKind, MultiSpan};
@@ -25,6 +25,11 @@ pub enum LintSource {
     /// The provided `Level` is the level specified on the command line -
     /// the actual level may be lower due to `--cap-lints`
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by a table of the lint configuration file, see
+    /// `rustc_session::lint::config`. The second `Symbol` is the header of
+    /// the table.
+    ConfigFile(Symbol, Symbol),
 }
 
 impl LintSource {
@@ -33,6 +38,7 @@ impl LintSource {
             LintSource::Default => symbol::kw::Default,
             LintSource::Node(name, _, _) => name,
             LintSource::CommandLine(name, _) => name,
+            LintSource::ConfigFile(name, _) => name,
         }
     }
 
@@ -41,6 +47,7 @@ impl LintSource {
             LintSource::Default => DUMMY_SP,
             LintSource::Node(_, span, _) => span,
             LintSource::CommandLine(_, _) => DUMMY_SP,
+            LintSource::ConfigFile(_, _) => DUMMY_SP,
         }
     }
 }
@@ -77,7 +84,19 @@ impl LintLevelSets {
         aux: Option<&FxHashMap<LintId, LevelSource>>,
         sess: &Session,
     ) -> LevelSource {
-        let (level, mut src) = self.get_lint_id_level(LintId::of(lint), idx, aux);
+        let id = LintId::of(lint);
+
+        // `--force-warn` takes precedence over all attributes, the `warnings`
+        // lint and `--cap-lints`. Only the driver can still turn it off.
+        if let Some(src) = self.force_warn_source(id) {
+            let level = match sess.driver_lint_caps.get(&id) {
+                Some(&driver_level) if driver_level < Level::Warn => driver_level,
+                _ => Level::ForceWarn,
+            };
+            return (level, src);
+        }
+
+        let (level, mut src) = self.get_lint_id_level(id, idx, aux);
 
         // If `level` is none then we actually assume the default level for this
         // lint.
@@ -100,7 +119,7 @@ impl LintLevelSets {
         // Ensure that we never exceed the `--cap-lints` argument.
         level = cmp::min(level, self.lint_cap);
 
-        if let Some(driver_level) = sess.driver_lint_caps.get(&LintId::of(lint)) {
+        if let Some(driver_level) = sess.driver_lint_caps.get(&id) {
             // Ensure that we never exceed driver level.
             level = cmp::min(*driver_level, level);
         }
@@ -108,6 +127,17 @@ impl LintLevelSets {
         (level, src)
     }
 
+    /// Returns the source of the `--force-warn` flag for `id`, if there is one.
+    fn force_warn_source(&self, id: LintId) -> Option<LintSource> {
+        match self.list.first() {
+            Some(LintSet::CommandLine { specs }) => match specs.get(&id) {
+                Some(&(Level::ForceWarn, src)) => Some(src),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub fn get_lint_id_level(
         &self,
         id: LintId,
@@ -138,9 +168,23 @@ impl LintLevelSets {
     }
 }
 
+/// A lint name in an `#[expect]` attribute, which is reported by the
+/// `unfulfilled_lint_expectations` lint unless a lint it covers is emitted.
+#[derive(Clone, Copy, HashStable)]
+pub struct LintExpectation {
+    pub id: LintExpectationId,
+    /// The lint or lint group name, as written in the attribute.
+    pub lint_name: Symbol,
+    pub reason: Option<Symbol>,
+    /// The lint set of the node the attribute is on, which determines the
+    /// level of `unfulfilled_lint_expectations` for this expectation.
+    pub set: u32,
+}
+
 pub struct LintLevelMap {
     pub sets: LintLevelSets,
     pub id_to_set: FxHashMap<HirId, u32>,
+    pub expectations: Vec<LintExpectation>,
 }
 
 impl LintLevelMap {
@@ -164,7 +208,7 @@ impl LintLevelMap {
 impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
     #[inline]
     fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHasher) {
-        let LintLevelMap { ref sets, ref id_to_set } = *self;
+        let LintLevelMap { ref sets, ref id_to_set, ref expectations } = *self;
 
         id_to_set.hash_stable(hcx, hasher);
 
@@ -190,6 +234,8 @@ impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
                     }
                 }
             }
+
+            expectations.hash_stable(hcx, hasher);
         })
     }
 }
@@ -235,6 +281,22 @@ pub fn struct_lint_level<'s, 'd>(
             future_incompatible.map_or(false, |incompat| incompat.future_breakage.is_some());
 
         let mut err = match (level, span) {
+            (Level::Expect, span) => {
+                let id = match src {
+                    LintSource::Node(_, span, _) => LintExpectationId { span },
+                    _ => bug!("`expect` level for `{}` not set by an attribute", lint.name_lower()),
+                };
+                let mut err = sess.struct_expect("", id);
+                if let Some(span) = span {
+                    err.set_span(span);
+                }
+                // The diagnostic is never shown, so skip the notes about the
+                // lint level below: adding them would use up the notes shown
+                // only once per lint.
+                err.code(DiagnosticId::Lint { name: lint.name_lower(), has_future_breakage });
+                decorate(LintDiagnosticBuilder::new(err));
+                return;
+            }
             (Level::Allow, span) => {
                 if has_future_breakage {
                     if let Some(span) = span {
@@ -248,6 +310,8 @@ pub fn struct_lint_level<'s, 'd>(
             }
             (Level::Warn, Some(span)) => sess.struct_span_warn(span, ""),
             (Level::Warn, None) => sess.struct_warn(""),
+            (Level::ForceWarn, Some(span)) => sess.struct_span_force_warn(span, ""),
+            (Level::ForceWarn, None) => sess.struct_force_warn(""),
             (Level::Deny | Level::Forbid, Some(span)) => sess.struct_span_err(span, ""),
             (Level::Deny | Level::Forbid, None) => sess.struct_err(""),
         };
@@ -287,6 +351,8 @@ pub fn struct_lint_level<'s, 'd>(
                     Level::Deny => "-D",
                     Level::Forbid => "-F",
                     Level::Allow => "-A",
+                    Level::ForceWarn => "--force-warn",
+                    Level::Expect => bug!("`expect` cannot be set on the command line"),
                 };
                 let hyphen_case_lint_name = name.replace("_", "-");
                 if lint_flag_val.as_str() == name {
@@ -310,6 +376,23 @@ pub fn struct_lint_level<'s, 'd>(
                     );
                 }
             }
+            LintSource::ConfigFile(lint_config_name, table) => {
+                let level_str = level.as_str();
+                let file = match &sess.lint_config {
+                    Some(config) => config.path.display().to_string(),
+                    None => bug!("lint level set by a configuration file without one"),
+                };
+                let set_by = format!("set by `[{}]` in `{}`", table, file);
+                let msg = if lint_config_name.as_str() == name {
+                    format!("`{}({})` {}", level_str, name, set_by)
+                } else {
+                    format!(
+                        "`{}({})` implied by `{}({})` {}",
+                        level_str, name, level_str, lint_config_name, set_by
+                    )
+                };
+                sess.diag_note_once(&mut err, DiagnosticMessageId::from(lint), &msg);
+            }
             LintSource::Node(lint_attr_name, src, reason) => {
                 if let Some(rationale) = reason {
                     err.note(&rationale.as_str());
@@ -338,6 +338,27 @@ rustc_queries! {
             }
         }
 
+        /// Checks whether `key.1` is reachable from `key.0` in the call graph,
+        /// in which case inlining `key.0` into `key.1` could be a query cycle.
+        query mir_callgraph_reachable(key: (ty::Instance<'tcx>, LocalDefId)) -> bool {
+            fatal_cycle
+            desc { |tcx|
+                "computing if `{}` (transitively) calls `{}`",
+                key.0,
+                tcx.def_path_str(key.1.to_def_id()),
+            }
+        }
+
+        /// The functions called by an instance, as they appear in its MIR
+        /// before optimizations, for the cycle detection of the MIR inliner.
+        query mir_inliner_callees(key: ty::InstanceDef<'tcx>) -> &'tcx [(DefId, SubstsRef<'tcx>)] {
+            fatal_cycle
+            desc { |tcx|
+                "computing all function calls in `{}`",
+                tcx.def_path_str(key.def_id()),
+            }
+        }
+
         /// Returns coverage summary info for a function, after executing the `InstrumentCoverage`
         /// MIR pass (assuming the -Zinstrument-coverage option is enabled).
         query coverageinfo(key: DefId) -> mir::CoverageInfo {
@@ -898,6 +898,10 @@ pub struct GlobalCtxt<'tcx> {
     /// we can upcast to `Any` for some additional type safety.
     pub lint_store: Lrc<dyn Any + sync::Sync + sync::Send>,
 
+    /// This only ever stores the `MirBrowser` of `rustc_mir`, which keeps the MIR dumps of
+    /// `-Z dump-mir-browser` until the end of the compilation.
+    pub mir_browser: Box<dyn Any + sync::Sync + sync::Send>,
+
     pub dep_graph: DepGraph,
 
     pub prof: SelfProfilerRef,
@@ -1071,6 +1075,7 @@ impl<'tcx> TyCtxt<'tcx> {
     pub fn create_global_ctxt(
         s: &'tcx Session,
         lint_store: Lrc<dyn Any + sync::Send + sync::Sync>,
+        mir_browser: Box<dyn Any + sync::Send + sync::Sync>,
         local_providers: ty::query::Providers,
         extern_providers: ty::query::Providers,
         arena: &'tcx WorkerLocal<Arena<'tcx>>,
@@ -1119,6 +1124,7 @@ impl<'tcx> TyCtxt<'tcx> {
         GlobalCtxt {
             sess: s,
             lint_store,
+            mir_browser,
             cstore,
             arena,
             interners,
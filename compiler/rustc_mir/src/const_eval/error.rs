@@ -59,6 +59,9 @@ pub struct ConstEvalErr<'tcx> {
     pub span: Span,
     pub error: InterpError<'tcx>,
     pub stacktrace: Vec<FrameInfo<'tcx>>,
+    /// The most executed terminator and how often it was executed, if the
+    /// evaluation ran out of steps while being profiled.
+    pub hottest_loop: Option<(Span, u64)>,
 }
 
 impl<'tcx> ConstEvalErr<'tcx> {
@@ -75,7 +78,12 @@ impl<'tcx> ConstEvalErr<'tcx> {
     {
         error.print_backtrace();
         let stacktrace = ecx.generate_stacktrace();
-        ConstEvalErr { error: error.kind, stacktrace, span: span.unwrap_or_else(|| ecx.cur_span()) }
+        ConstEvalErr {
+            error: error.kind,
+            stacktrace,
+            span: span.unwrap_or_else(|| ecx.cur_span()),
+            hottest_loop: None,
+        }
     }
 
     pub fn struct_error(
@@ -169,6 +177,12 @@ impl<'tcx> ConstEvalErr<'tcx> {
                     err.span_label(frame_info.span, frame_info.to_string());
                 }
             }
+            if let Some((span, count)) = self.hottest_loop {
+                err.span_note(
+                    span,
+                    &format!("most of the steps were spent in this loop, executed {} times", count),
+                );
+            }
             // Let the caller finish the job.
             emit(err)
         };
@@ -91,8 +91,8 @@ pub(super) fn mk_eval_cx<'mir, 'tcx>(
         tcx,
         root_span,
         param_env,
-        CompileTimeInterpreter::new(tcx.sess.const_eval_limit()),
-        MemoryExtra { can_access_statics },
+        CompileTimeInterpreter::new(tcx.sess.const_eval_limit(), false),
+        MemoryExtra { can_access_statics, allocations: 0, allocated_bytes: 0 },
     )
 }
 
@@ -225,7 +225,8 @@ pub fn eval_to_const_value_raw_provider<'tcx>(
         };
         return eval_nullary_intrinsic(tcx, key.param_env, def_id, substs).map_err(|error| {
             let span = tcx.def_span(def_id);
-            let error = ConstEvalErr { error: error.kind, stacktrace: vec![], span };
+            let error =
+                ConstEvalErr { error: error.kind, stacktrace: vec![], span, hottest_loop: None };
             error.report_as_error(tcx.at(span), "could not evaluate nullary intrinsic")
         });
     }
@@ -282,14 +283,28 @@ pub fn eval_to_allocation_raw_provider<'tcx>(
         tcx,
         tcx.def_span(def.did),
         key.param_env,
-        CompileTimeInterpreter::new(tcx.sess.const_eval_limit()),
-        MemoryExtra { can_access_statics: is_static },
+        CompileTimeInterpreter::new(
+            tcx.sess.const_eval_limit(),
+            tcx.sess.opts.debugging_opts.const_eval_profile,
+        ),
+        MemoryExtra { can_access_statics: is_static, allocations: 0, allocated_bytes: 0 },
     );
 
     let res = ecx.load_mir(cid.instance.def, cid.promoted);
-    match res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, &body)) {
+    let res = res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, &body));
+
+    let mut hottest_loop = None;
+    if let Some(profile) = ecx.machine.profile.take() {
+        hottest_loop = profile.hottest_loop();
+        profile.record(tcx, cid, &ecx.memory.extra);
+    }
+
+    match res {
         Err(error) => {
-            let err = ConstEvalErr::new(&ecx, error, None);
+            let mut err = ConstEvalErr::new(&ecx, error, None);
+            if let err_exhaust!(StepLimitReached) = err.error {
+                err.hottest_loop = hottest_loop;
+            }
             // errors in statics are always emitted as fatal errors
             if is_static {
                 // Ensure that if the above error was either `TooGeneric` or `Reported`
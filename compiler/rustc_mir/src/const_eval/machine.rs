@@ -3,6 +3,7 @@ use rustc_middle::ty::layout::HasTyCtxt;
 use rustc_middle::ty::{self, Ty};
 use std::borrow::Borrow;
 use std::collections::hash_map::Entry;
+use std::fmt;
 use std::hash::Hash;
 
 use rustc_data_structures::fx::FxHashMap;
@@ -12,6 +13,7 @@ use rustc_hir::def_id::DefId;
 use rustc_middle::mir::AssertMessage;
 use rustc_session::Limit;
 use rustc_span::symbol::{sym, Symbol};
+use rustc_target::abi::{Align, Size};
 
 use crate::interpret::{
     self, compile_time_machine, AllocId, Allocation, Frame, GlobalId, ImmTy, InterpCx,
@@ -19,6 +21,7 @@ use crate::interpret::{
 };
 
 use super::error::*;
+use super::EvalProfile;
 
 impl<'mir, 'tcx> InterpCx<'mir, 'tcx, CompileTimeInterpreter<'mir, 'tcx>> {
     /// Evaluate a const function where all arguments (if any) are zero-sized types.
@@ -97,6 +100,9 @@ pub struct CompileTimeInterpreter<'mir, 'tcx> {
 
     /// The virtual call stack.
     pub(crate) stack: Vec<Frame<'mir, 'tcx, (), ()>>,
+
+    /// The profile of the evaluation, if `-Z const-eval-profile` is set.
+    pub(crate) profile: Option<EvalProfile<'tcx>>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -108,11 +114,19 @@ pub struct MemoryExtra {
     /// * Pointers to allocations inside of statics can never leak outside, to a non-static global.
     /// This boolean here controls the second part.
     pub(super) can_access_statics: bool,
+
+    /// The number of allocations created so far, and their total size in bytes.
+    pub(super) allocations: u64,
+    pub(super) allocated_bytes: u64,
 }
 
 impl<'mir, 'tcx> CompileTimeInterpreter<'mir, 'tcx> {
-    pub(super) fn new(const_eval_limit: Limit) -> Self {
-        CompileTimeInterpreter { steps_remaining: const_eval_limit.0, stack: Vec::new() }
+    pub(super) fn new(const_eval_limit: Limit, profile: bool) -> Self {
+        CompileTimeInterpreter {
+            steps_remaining: const_eval_limit.0,
+            stack: Vec::new(),
+            profile: profile.then(EvalProfile::new),
+        }
     }
 }
 
@@ -169,11 +183,30 @@ impl<K: Hash + Eq, V> interpret::AllocMap<K, V> for FxHashMap<K, V> {
 crate type CompileTimeEvalContext<'mir, 'tcx> =
     InterpCx<'mir, 'tcx, CompileTimeInterpreter<'mir, 'tcx>>;
 
-impl interpret::MayLeak for ! {
+/// The memory kinds of compile-time evaluation, besides the ones of the interpreter.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MemoryKind {
+    /// Memory allocated by the `const_allocate` intrinsic. It is freed by `const_deallocate`,
+    /// or it becomes immutable static memory if the final value of the evaluation has a
+    /// shared reference to it.
+    Heap,
+}
+
+impl fmt::Display for MemoryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryKind::Heap => write!(f, "heap allocation"),
+        }
+    }
+}
+
+impl interpret::MayLeak for MemoryKind {
     #[inline(always)]
     fn may_leak(self) -> bool {
-        // `self` is uninhabited
-        self
+        match self {
+            // Leaked heap allocations are reported by interning.
+            MemoryKind::Heap => false,
+        }
     }
 }
 
@@ -295,6 +328,39 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
                 };
                 ecx.write_scalar(Scalar::from_bool(cmp), dest)?;
             }
+            sym::const_allocate => {
+                let size = ecx.read_scalar(args[0])?.to_machine_usize(ecx)?;
+                let align = ecx.read_scalar(args[1])?.to_machine_usize(ecx)?;
+                let align = match Align::from_bytes(align) {
+                    Ok(align) => align,
+                    Err(err) => throw_ub_format!("align has to be a power of 2, {}", err),
+                };
+
+                let ptr = ecx.memory.allocate(
+                    Size::from_bytes(size),
+                    align,
+                    interpret::MemoryKind::Machine(MemoryKind::Heap),
+                );
+                ecx.write_scalar(Scalar::Ptr(ptr), dest)?;
+            }
+            sym::const_deallocate => {
+                let ptr = ecx.read_scalar(args[0])?.check_init()?;
+                let size = ecx.read_scalar(args[1])?.to_machine_usize(ecx)?;
+                let align = ecx.read_scalar(args[2])?.to_machine_usize(ecx)?;
+                let align = match Align::from_bytes(align) {
+                    Ok(align) => align,
+                    Err(err) => throw_ub_format!("align has to be a power of 2, {}", err),
+                };
+
+                // Deallocating memory that was not allocated by `const_allocate`, e.g. a
+                // local or a static, is an error of `deallocate`.
+                let ptr = ecx.force_ptr(ptr)?;
+                ecx.memory.deallocate(
+                    ptr,
+                    Some((Size::from_bytes(size), align)),
+                    interpret::MemoryKind::Machine(MemoryKind::Heap),
+                )?;
+            }
             _ => {
                 return Err(ConstEvalErrKind::NeedsRfc(format!(
                     "calling intrinsic `{}`",
@@ -354,6 +420,11 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
     }
 
     fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profile) = &mut ecx.machine.profile {
+            let frame = ecx.machine.stack.last().expect("no call frames exist");
+            profile.step(frame.instance, frame.current_span(), &ecx.memory.extra);
+        }
+
         // The step limit has already been hit in a previous call to `before_terminator`.
         if ecx.machine.steps_remaining == 0 {
             return Ok(());
@@ -367,6 +438,12 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
         Ok(())
     }
 
+    #[inline(always)]
+    fn after_allocation(memory_extra: &mut MemoryExtra, _id: AllocId, size: Size) {
+        memory_extra.allocations += 1;
+        memory_extra.allocated_bytes += size.bytes();
+    }
+
     #[inline(always)]
     fn init_frame_extra(
         ecx: &mut InterpCx<'mir, 'tcx, Self>,
@@ -15,11 +15,13 @@ mod error;
 mod eval_queries;
 mod fn_queries;
 mod machine;
+mod profile;
 
 pub use error::*;
 pub use eval_queries::*;
 pub use fn_queries::*;
 pub use machine::*;
+pub use profile::*;
 
 pub(crate) fn const_caller_location(
     tcx: TyCtxt<'tcx>,
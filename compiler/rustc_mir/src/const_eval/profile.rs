@@ -0,0 +1,84 @@
+//! Profiling of const evaluation, enabled by `-Z const-eval-profile`.
+//!
+//! The `CompileTimeInterpreter` charges the cost of the code executed since
+//! the previous terminator to the function of the topmost frame whenever it
+//! executes a terminator, so each function is charged its own cost but not
+//! that of its callees. The profile of an evaluation is recorded in the
+//! session and printed at the end of the compilation.
+
+use std::time::Instant;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::mir::interpret::GlobalId;
+use rustc_middle::ty::{self, print::with_no_trimmed_paths, TyCtxt};
+use rustc_session::ConstEvalCost;
+use rustc_span::Span;
+
+use super::MemoryExtra;
+
+pub struct EvalProfile<'tcx> {
+    start: Instant,
+    /// When the previous terminator was executed, and the allocation counters
+    /// of the `MemoryExtra` at that time.
+    last_step: (Instant, u64, u64),
+    functions: FxHashMap<ty::Instance<'tcx>, ConstEvalCost>,
+    /// How often each terminator was executed.
+    terminators: FxHashMap<Span, u64>,
+}
+
+impl<'tcx> EvalProfile<'tcx> {
+    pub fn new() -> Self {
+        let start = Instant::now();
+        EvalProfile {
+            start,
+            last_step: (start, 0, 0),
+            functions: Default::default(),
+            terminators: Default::default(),
+        }
+    }
+
+    /// Charges the cost since the previous terminator and the terminator at
+    /// `span` to `instance`.
+    pub fn step(&mut self, instance: ty::Instance<'tcx>, span: Span, memory_extra: &MemoryExtra) {
+        let now = Instant::now();
+        let (last_time, last_allocations, last_allocated_bytes) = self.last_step;
+        let cost = self.functions.entry(instance).or_default();
+        cost.steps += 1;
+        cost.allocations += memory_extra.allocations - last_allocations;
+        cost.allocated_bytes += memory_extra.allocated_bytes - last_allocated_bytes;
+        cost.time += now - last_time;
+        self.last_step = (now, memory_extra.allocations, memory_extra.allocated_bytes);
+
+        *self.terminators.entry(span).or_insert(0) += 1;
+    }
+
+    /// Returns the terminator that was executed the most, and how often. Unless
+    /// the evaluation recursed, this is the header of its hottest loop.
+    pub fn hottest_loop(&self) -> Option<(Span, u64)> {
+        self.terminators
+            .iter()
+            .max_by_key(|&(&span, &count)| (count, span))
+            .map(|(&span, &count)| (span, count))
+    }
+
+    /// Records the profile of the evaluation of `cid` in the session.
+    pub fn record(self, tcx: TyCtxt<'tcx>, cid: GlobalId<'tcx>, memory_extra: &MemoryExtra) {
+        let cost = ConstEvalCost {
+            steps: self.functions.values().map(|cost| cost.steps).sum(),
+            allocations: memory_extra.allocations,
+            allocated_bytes: memory_extra.allocated_bytes,
+            time: self.start.elapsed(),
+        };
+        let (description, functions) = with_no_trimmed_paths(|| {
+            let promoted = cid.promoted.map_or(String::new(), |p| format!("::promoted[{:?}]", p));
+            let description = format!("{}{}", tcx.def_path_str(cid.instance.def_id()), promoted);
+            let functions = self
+                .functions
+                .into_iter()
+                .map(|(instance, cost)| (instance.to_string(), cost))
+                .collect();
+            (description, functions)
+        });
+        tcx.sess.code_stats.record_const_eval(description, cost, functions);
+    }
+}
@@ -24,17 +24,20 @@ use rustc_target::abi::Size;
 
 use rustc_ast::Mutability;
 
-use super::{AllocId, Allocation, InterpCx, MPlaceTy, Machine, MemoryKind, Scalar, ValueVisitor};
+use super::{
+    AllocId, AllocMap, Allocation, InterpCx, MPlaceTy, Machine, MemoryKind, Scalar, ValueVisitor,
+};
+use crate::const_eval;
 
 pub trait CompileTimeMachine<'mir, 'tcx> = Machine<
     'mir,
     'tcx,
-    MemoryKind = !,
+    MemoryKind = const_eval::MemoryKind,
     PointerTag = (),
     ExtraFnVal = !,
     FrameExtra = (),
     AllocExtra = (),
-    MemoryMap = FxHashMap<AllocId, (MemoryKind<!>, Allocation)>,
+    MemoryMap = FxHashMap<AllocId, (MemoryKind<const_eval::MemoryKind>, Allocation)>,
 >;
 
 struct InternVisitor<'rt, 'mir, 'tcx, M: CompileTimeMachine<'mir, 'tcx>> {
@@ -104,12 +107,28 @@ fn intern_shallow<'rt, 'mir, 'tcx, M: CompileTimeMachine<'mir, 'tcx>>(
     // This match is just a canary for future changes to `MemoryKind`, which most likely need
     // changes in this function.
     match kind {
-        MemoryKind::Stack | MemoryKind::Vtable | MemoryKind::CallerLocation => {}
+        MemoryKind::Stack
+        | MemoryKind::Machine(const_eval::MemoryKind::Heap)
+        | MemoryKind::Vtable
+        | MemoryKind::CallerLocation => {}
     }
     // Set allocation mutability as appropriate. This is used by LLVM to put things into
     // read-only memory, and also by Miri when evaluating other globals that
     // access this one.
-    if let InternMode::Static(mutability) = mode {
+    if kind == MemoryKind::Machine(const_eval::MemoryKind::Heap) {
+        // The heap of the evaluation becomes static memory, which only shared references
+        // may point to: it must be neither mutated nor deallocated at runtime. Memory that is
+        // reached otherwise, e.g. through a `Box`, is rejected in
+        // `intern_const_alloc_recursive`.
+        let frozen = ty.map_or(true, |ty| ty.is_freeze(ecx.tcx, ecx.param_env));
+        if mode == InternMode::Static(Mutability::Mut) || !frozen {
+            tcx.sess.span_err(
+                ecx.tcx.span,
+                "mutable heap allocation in the final value of a constant or static",
+            );
+        }
+        alloc.mutability = Mutability::Not;
+    } else if let InternMode::Static(mutability) = mode {
         // For this, we need to take into account `UnsafeCell`. When `ty` is `None`, we assume
         // no interior mutability.
         let frozen = ty.map_or(true, |ty| ty.is_freeze(ecx.tcx, ecx.param_env));
@@ -363,7 +382,17 @@ where
 
     let mut todo: Vec<_> = leftover_allocations.iter().cloned().collect();
     while let Some(alloc_id) = todo.pop() {
-        if let Some((_, mut alloc)) = ecx.memory.alloc_map.remove(&alloc_id) {
+        if let Some((kind, mut alloc)) = ecx.memory.alloc_map.remove(&alloc_id) {
+            if kind == MemoryKind::Machine(const_eval::MemoryKind::Heap) {
+                // The final value owns the allocation, e.g. with a `Box` or a raw pointer,
+                // and could deallocate it at runtime, but it is not in the runtime heap.
+                ecx.tcx.sess.span_err(
+                    ecx.tcx.span,
+                    "heap allocation in the final value of a constant or static that is not \
+                     behind a shared reference",
+                );
+                return Err(ErrorReported);
+            }
             // We can't call the `intern_shallow` method here, as its logic is tailored to safe
             // references and a `leftover_allocations` set (where we only have a todo-list here).
             // So we hand-roll the interning logic here again.
@@ -415,6 +444,18 @@ where
             span_bug!(ecx.tcx.span, "encountered unknown alloc id {:?}", alloc_id);
         }
     }
+
+    // The heap allocations that are neither deallocated nor part of the final value are leaked.
+    let leaked = ecx.memory.alloc_map.filter_map_collect(|_, &(kind, _)| {
+        (kind == MemoryKind::Machine(const_eval::MemoryKind::Heap)).then_some(())
+    });
+    if !leaked.is_empty() {
+        ecx.tcx.sess.span_err(
+            ecx.tcx.span,
+            "heap allocation leaked: it is neither deallocated nor part of the final value",
+        );
+        return Err(ErrorReported);
+    }
     Ok(())
 }
 
@@ -4,6 +4,7 @@
 
 use std::convert::TryFrom;
 
+use rustc_apfloat::Float;
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir::{
     self,
@@ -315,6 +316,36 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
                 let result = Scalar::from_uint(truncated_bits, layout.size);
                 self.write_scalar(result, dest)?;
             }
+            sym::fabsf32 => {
+                let f = self.read_scalar(args[0])?.to_f32()?;
+                self.write_scalar(Scalar::from_f32(f.abs()), dest)?;
+            }
+            sym::fabsf64 => {
+                let f = self.read_scalar(args[0])?.to_f64()?;
+                self.write_scalar(Scalar::from_f64(f.abs()), dest)?;
+            }
+            sym::minnumf32 | sym::maxnumf32 | sym::copysignf32 => {
+                let a = self.read_scalar(args[0])?.to_f32()?;
+                let b = self.read_scalar(args[1])?.to_f32()?;
+                let res = match intrinsic_name {
+                    sym::minnumf32 => a.min(b),
+                    sym::maxnumf32 => a.max(b),
+                    sym::copysignf32 => a.copy_sign(b),
+                    _ => bug!("Already checked for float ops"),
+                };
+                self.write_scalar(Scalar::from_f32(res), dest)?;
+            }
+            sym::minnumf64 | sym::maxnumf64 | sym::copysignf64 => {
+                let a = self.read_scalar(args[0])?.to_f64()?;
+                let b = self.read_scalar(args[1])?.to_f64()?;
+                let res = match intrinsic_name {
+                    sym::minnumf64 => a.min(b),
+                    sym::maxnumf64 => a.max(b),
+                    sym::copysignf64 => a.copy_sign(b),
+                    _ => bug!("Already checked for float ops"),
+                };
+                self.write_scalar(Scalar::from_f64(res), dest)?;
+            }
             sym::offset => {
                 let ptr = self.read_scalar(args[0])?.check_init()?;
                 let offset_count = self.read_scalar(args[1])?.to_machine_isize(self)?;
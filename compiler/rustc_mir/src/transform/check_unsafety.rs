@@ -11,7 +11,6 @@ use rustc_middle::ty::cast::CastTy;
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, TyCtxt};
 use rustc_session::lint::builtin::{SAFE_PACKED_BORROWS, UNSAFE_OP_IN_UNSAFE_FN, UNUSED_UNSAFE};
-use rustc_session::lint::Level;
 use rustc_span::symbol::sym;
 
 use std::ops::Bound;
@@ -732,5 +731,5 @@ pub fn check_unsafety(tcx: TyCtxt<'_>, def_id: LocalDefId) {
 }
 
 fn unsafe_op_in_unsafe_fn_allowed(tcx: TyCtxt<'_>, id: HirId) -> bool {
-    tcx.lint_level_at_node(UNSAFE_OP_IN_UNSAFE_FN, id).0 == Level::Allow
+    tcx.lint_level_at_node(UNSAFE_OP_IN_UNSAFE_FN, id).0.is_allow_like()
 }
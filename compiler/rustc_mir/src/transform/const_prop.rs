@@ -1,10 +1,20 @@
 //! Propagates constants for early reporting of statically known
-//! assertion failures
-
-use std::cell::Cell;
+//! assertion failures, and replaces the values that are known with
+//! constants.
+//!
+//! This is sparse conditional constant propagation: a forward dataflow
+//! analysis tracks the scalars of the locals whose address is never taken,
+//! including the fields of tuples, structs and closures and the discriminant
+//! of enums, and it does not propagate the state along the edges of a
+//! `SwitchInt` that cannot be taken. Values are computed by the interpreter.
+//! Since the state is joined where control flow merges, loops and branches
+//! need no special handling, and the blocks that are never reached are
+//! known: their terminator is replaced with `Unreachable`. They are still
+//! linted, with the values computed within the block, as they may only be
+//! dead for the current configuration, e.g. under `if cfg!(debug_assertions)`.
 
 use rustc_ast::Mutability;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def::DefKind;
 use rustc_hir::HirId;
 use rustc_index::bit_set::BitSet;
@@ -13,32 +23,34 @@ use rustc_middle::mir::visit::{
     MutVisitor, MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor,
 };
 use rustc_middle::mir::{
-    AssertKind, BasicBlock, BinOp, Body, ClearCrossCrate, Constant, Local, LocalDecl, LocalKind,
-    Location, Operand, Place, Rvalue, SourceInfo, SourceScope, SourceScopeData, Statement,
-    StatementKind, Terminator, TerminatorKind, UnOp, RETURN_PLACE,
-};
-use rustc_middle::ty::layout::{HasTyCtxt, LayoutError, TyAndLayout};
-use rustc_middle::ty::subst::{InternalSubsts, Subst};
-use rustc_middle::ty::{
-    self, ConstInt, ConstKind, Instance, ParamEnv, ScalarInt, Ty, TyCtxt, TypeFoldable,
+    AssertKind, BasicBlock, BasicBlockData, BinOp, Body, CastKind, ClearCrossCrate, Constant,
+    Field, InlineAsmOperand, Local, Location, NullOp, Operand, Place, PlaceRef, ProjectionElem,
+    Rvalue, SourceInfo, Statement, StatementKind, Terminator, TerminatorKind, UnOp,
 };
+use rustc_middle::ty::{self, ConstInt, ConstKind, ParamEnv, ScalarInt, Ty, TyCtxt, TypeFoldable};
 use rustc_session::lint;
 use rustc_span::{def_id::DefId, Span};
-use rustc_target::abi::{HasDataLayout, LayoutOf, Size, TargetDataLayout};
+use rustc_target::abi::{LayoutOf, Size};
 use rustc_trait_selection::traits;
 
 use crate::const_eval::ConstEvalErr;
+use crate::dataflow::fmt::DebugWithContext;
+use crate::dataflow::lattice::{FlatSet, JoinSemiLattice};
+use crate::dataflow::{
+    Analysis, AnalysisDomain, Results, ResultsRefCursor, SwitchIntEdgeEffects, SwitchIntTarget,
+};
 use crate::interpret::{
-    self, compile_time_machine, AllocId, Allocation, ConstValue, CtfeValidationMode, Frame, ImmTy,
-    Immediate, InterpCx, InterpResult, LocalState, LocalValue, MemPlace, Memory, MemoryKind, OpTy,
-    Operand as InterpOperand, PlaceTy, Pointer, Scalar, ScalarMaybeUninit, StackPopCleanup,
+    self, compile_time_machine, AllocId, Allocation, ConstValue, Frame, ImmTy, Immediate, InterpCx,
+    InterpResult, Memory, OpTy, PlaceTy, Pointer, Scalar, ScalarMaybeUninit,
 };
 use crate::transform::MirPass;
 
-/// The maximum number of bytes that we'll allocate space for a local or the return value.
-/// Needed for #66397, because otherwise we eval into large places and that can cause OOM or just
-/// Severely regress performance.
-const MAX_ALLOC_LIMIT: u64 = 1024;
+/// The maximum number of scalars that are tracked in a body. The dataflow state
+/// at the entry of each block holds a value for each of them.
+const MAX_TRACKED_VALUES: usize = 256;
+
+/// The maximum depth of the fields that are tracked, e.g. `_1.0.1` has depth 2.
+const MAX_FIELD_DEPTH: usize = 3;
 
 /// Macro for machine-specific `InterpError` without allocation.
 /// (These will never be shown to the user, but they help diagnose ICEs.)
@@ -130,51 +142,36 @@ impl<'tcx> MirPass<'tcx> for ConstProp {
 
         trace!("ConstProp starting for {:?}", def_id);
 
-        let dummy_body = &Body::new(
-            body.source,
-            body.basic_blocks().clone(),
-            body.source_scopes.clone(),
-            body.local_decls.clone(),
-            Default::default(),
-            body.arg_count,
-            Default::default(),
-            tcx.def_span(def_id),
-            body.generator_kind,
-        );
-
-        // FIXME(oli-obk, eddyb) Optimize locals (or even local paths) to hold
-        // constants, instead of just checking for const-folding succeeding.
-        // That would require an uniform one-def no-mutation analysis
-        // and RPO (or recursing when needing the value of a local).
-        let mut optimization_finder = ConstPropagator::new(body, dummy_body, tcx);
-        optimization_finder.visit_body(body);
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        let map = PlaceMap::new(tcx, param_env, body);
+        let results = ConstAnalysis::new(tcx, param_env, body, map)
+            .into_engine(tcx, body)
+            .pass_name("const_prop")
+            .iterate_to_fixpoint();
+        let patch = Collector::new(tcx, body, &results).collect();
+        drop(results);
+
+        // Without optimizations, only report the lints.
+        let mir_opt_level = tcx.sess.opts.debugging_opts.mir_opt_level;
+        if mir_opt_level > 0 {
+            let mut propagator = ConstPropagator::new(tcx, param_env, body, patch);
+            propagator.visit_body(body);
+
+            for &block in &propagator.patch.unreachable_blocks {
+                trace!("{:?} is unreachable", block);
+                let data = &mut body.basic_blocks_mut()[block];
+                data.statements.clear();
+                data.terminator_mut().kind = TerminatorKind::Unreachable;
+            }
+        }
 
         trace!("ConstProp done for {:?}", def_id);
     }
 }
 
 struct ConstPropMachine<'mir, 'tcx> {
-    /// The virtual call stack.
+    /// The virtual call stack. It stays empty, since no function is evaluated.
     stack: Vec<Frame<'mir, 'tcx, (), ()>>,
-    /// `OnlyInsideOwnBlock` locals that were written in the current block get erased at the end.
-    written_only_inside_own_block_locals: FxHashSet<Local>,
-    /// Locals that need to be cleared after every block terminates.
-    only_propagate_inside_block_locals: BitSet<Local>,
-    can_const_prop: IndexVec<Local, ConstPropMode>,
-}
-
-impl<'mir, 'tcx> ConstPropMachine<'mir, 'tcx> {
-    fn new(
-        only_propagate_inside_block_locals: BitSet<Local>,
-        can_const_prop: IndexVec<Local, ConstPropMode>,
-    ) -> Self {
-        Self {
-            stack: Vec::new(),
-            written_only_inside_own_block_locals: Default::default(),
-            only_propagate_inside_block_locals,
-            can_const_prop,
-        }
-    }
 }
 
 impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for ConstPropMachine<'mir, 'tcx> {
@@ -231,40 +228,6 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for ConstPropMachine<'mir, 'tcx>
         throw_machine_stop_str!("can't const prop heap allocations")
     }
 
-    fn access_local(
-        _ecx: &InterpCx<'mir, 'tcx, Self>,
-        frame: &Frame<'mir, 'tcx, Self::PointerTag, Self::FrameExtra>,
-        local: Local,
-    ) -> InterpResult<'tcx, InterpOperand<Self::PointerTag>> {
-        let l = &frame.locals[local];
-
-        if l.value == LocalValue::Uninitialized {
-            throw_machine_stop_str!("tried to access an uninitialized local")
-        }
-
-        l.access()
-    }
-
-    fn access_local_mut<'a>(
-        ecx: &'a mut InterpCx<'mir, 'tcx, Self>,
-        frame: usize,
-        local: Local,
-    ) -> InterpResult<'tcx, Result<&'a mut LocalValue<Self::PointerTag>, MemPlace<Self::PointerTag>>>
-    {
-        if ecx.machine.can_const_prop[local] == ConstPropMode::NoPropagation {
-            throw_machine_stop_str!("tried to write to a local that is marked as not propagatable")
-        }
-        if frame == 0 && ecx.machine.only_propagate_inside_block_locals.contains(local) {
-            trace!(
-                "mutating local {:?} which is restricted to its block. \
-                Will remove it from const-prop after block is finished.",
-                local
-            );
-            ecx.machine.written_only_inside_own_block_locals.insert(local);
-        }
-        ecx.machine.stack[frame].locals[local].access_mut()
-    }
-
     fn before_access_global(
         _memory_extra: &(),
         _alloc_id: AllocId,
@@ -307,202 +270,704 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for ConstPropMachine<'mir, 'tcx>
     }
 }
 
-/// Finds optimization opportunities on the MIR.
-struct ConstPropagator<'mir, 'tcx> {
-    ecx: InterpCx<'mir, 'tcx, ConstPropMachine<'mir, 'tcx>>,
+fn new_ecx<'mir, 'tcx>(
     tcx: TyCtxt<'tcx>,
     param_env: ParamEnv<'tcx>,
-    // FIXME(eddyb) avoid cloning these two fields more than once,
-    // by accessing them through `ecx` instead.
-    source_scopes: IndexVec<SourceScope, SourceScopeData<'tcx>>,
-    local_decls: IndexVec<Local, LocalDecl<'tcx>>,
-    // Because we have `MutVisitor` we can't obtain the `SourceInfo` from a `Location`. So we store
-    // the last known `SourceInfo` here and just keep revisiting it.
-    source_info: Option<SourceInfo>,
+    body: &Body<'tcx>,
+) -> InterpCx<'mir, 'tcx, ConstPropMachine<'mir, 'tcx>> {
+    let span = tcx.def_span(body.source.def_id());
+    InterpCx::new(tcx, span, param_env, ConstPropMachine { stack: Vec::new() }, ())
 }
 
-impl<'mir, 'tcx> LayoutOf for ConstPropagator<'mir, 'tcx> {
-    type Ty = Ty<'tcx>;
-    type TyAndLayout = Result<TyAndLayout<'tcx>, LayoutError<'tcx>>;
+/// Returns the integer of `imm`, if it is a scalar that is not a pointer.
+fn scalar_int(imm: &ImmTy<'_>) -> Option<ScalarInt> {
+    match **imm {
+        Immediate::Scalar(ScalarMaybeUninit::Scalar(Scalar::Int(int))) => Some(int),
+        _ => None,
+    }
+}
 
-    fn layout_of(&self, ty: Ty<'tcx>) -> Self::TyAndLayout {
-        self.tcx.layout_of(self.param_env.and(ty))
+/// Whether the values of type `ty` are tracked: the types whose values are
+/// integers for the interpreter.
+fn is_tracked_scalar(ty: Ty<'_>) -> bool {
+    matches!(ty.kind(), ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) | ty::Float(_))
+}
+
+rustc_index::newtype_index! {
+    /// A place tracked by `ConstAnalysis`.
+    struct PlaceIndex {
+        DEBUG_FORMAT = "pl{}"
     }
 }
 
-impl<'mir, 'tcx> HasDataLayout for ConstPropagator<'mir, 'tcx> {
-    #[inline]
-    fn data_layout(&self) -> &TargetDataLayout {
-        &self.tcx.data_layout
+rustc_index::newtype_index! {
+    /// A scalar tracked by `ConstAnalysis`, the value of a `PlaceIndex`.
+    struct ValueIndex {
+        DEBUG_FORMAT = "v{}"
     }
 }
 
-impl<'mir, 'tcx> HasTyCtxt<'tcx> for ConstPropagator<'mir, 'tcx> {
-    #[inline]
-    fn tcx(&self) -> TyCtxt<'tcx> {
-        self.tcx
+/// A projection from a tracked place to one of its tracked parts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TrackElem {
+    Field(Field),
+    /// The discriminant of an enum, which is set by `SetDiscriminant`.
+    Discriminant,
+}
+
+struct PlaceInfo<'tcx> {
+    /// The type of the place, or of the discriminant for `TrackElem::Discriminant`.
+    ty: Ty<'tcx>,
+    /// The value of the place, if it is a scalar.
+    value: Option<ValueIndex>,
+    children: Vec<(TrackElem, PlaceIndex)>,
+}
+
+/// The places tracked by `ConstAnalysis`, as a tree for each local.
+struct PlaceMap<'tcx> {
+    locals: IndexVec<Local, Option<PlaceIndex>>,
+    places: IndexVec<PlaceIndex, PlaceInfo<'tcx>>,
+    value_count: usize,
+}
+
+impl PlaceMap<'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, body: &Body<'tcx>) -> PlaceMap<'tcx> {
+        // The value of a local whose address is taken can change without an
+        // assignment to it.
+        let mut borrowed = BorrowedLocals(BitSet::new_empty(body.local_decls.len()));
+        borrowed.visit_body(body);
+
+        let mut map = PlaceMap {
+            locals: IndexVec::from_elem(None, &body.local_decls),
+            places: IndexVec::new(),
+            value_count: 0,
+        };
+        for (local, decl) in body.local_decls.iter_enumerated() {
+            if borrowed.0.contains(local) || map.value_count >= MAX_TRACKED_VALUES {
+                continue;
+            }
+            map.locals[local] = Some(map.track(tcx, param_env, decl.ty, 0));
+        }
+        map
+    }
+
+    fn track(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        ty: Ty<'tcx>,
+        depth: usize,
+    ) -> PlaceIndex {
+        let index = self.places.push(PlaceInfo { ty, value: None, children: vec![] });
+        if is_tracked_scalar(ty) {
+            self.places[index].value = Some(ValueIndex::new(self.value_count));
+            self.value_count += 1;
+            return index;
+        }
+        if depth == MAX_FIELD_DEPTH {
+            return index;
+        }
+
+        let fields: Vec<Ty<'tcx>> = match *ty.kind() {
+            ty::Tuple(..) => ty.tuple_fields().collect(),
+            ty::Adt(def, substs) if def.is_struct() && !ty.is_box() => def
+                .non_enum_variant()
+                .fields
+                .iter()
+                .map(|field| tcx.normalize_erasing_regions(param_env, field.ty(tcx, substs)))
+                .collect(),
+            ty::Closure(_, substs) => substs.as_closure().upvar_tys().collect(),
+            ty::Adt(def, _) if def.is_enum() && !def.variants.is_empty() => {
+                let discr = self.track(tcx, param_env, ty.discriminant_ty(tcx), depth + 1);
+                self.places[index].children.push((TrackElem::Discriminant, discr));
+                vec![]
+            }
+            _ => vec![],
+        };
+        for (i, field_ty) in fields.into_iter().enumerate() {
+            let field = self.track(tcx, param_env, field_ty, depth + 1);
+            self.places[index].children.push((TrackElem::Field(Field::new(i)), field));
+        }
+        index
+    }
+
+    fn child(&self, index: PlaceIndex, elem: TrackElem) -> Option<PlaceIndex> {
+        self.places[index].children.iter().find(|&&(e, _)| e == elem).map(|&(_, child)| child)
+    }
+
+    /// Returns the tracked place of `place`, if it is tracked.
+    fn find(&self, place: PlaceRef<'tcx>) -> Option<PlaceIndex> {
+        let mut index = self.locals[place.local]?;
+        for elem in place.projection {
+            match *elem {
+                ProjectionElem::Field(field, _) => {
+                    index = self.child(index, TrackElem::Field(field))?;
+                }
+                _ => return None,
+            }
+        }
+        Some(index)
+    }
+
+    /// Returns the longest tracked prefix of `place`, which contains all the
+    /// tracked places that a write to `place` can change.
+    fn find_prefix(&self, place: PlaceRef<'tcx>) -> Option<PlaceIndex> {
+        let mut index = self.locals[place.local]?;
+        for elem in place.projection {
+            let child = match *elem {
+                ProjectionElem::Field(field, _) => self.child(index, TrackElem::Field(field)),
+                _ => None,
+            };
+            match child {
+                Some(child) => index = child,
+                None => break,
+            }
+        }
+        Some(index)
     }
 }
 
-impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        use rustc_middle::mir::visit::PlaceContext::*;
+        match context {
+            NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+            | NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
+            | NonMutatingUse(NonMutatingUseContext::AddressOf)
+            | MutatingUse(MutatingUseContext::Borrow)
+            | MutatingUse(MutatingUseContext::AddressOf) => {
+                trace!("local {:?} can't be propagated because it's used: {:?}", local, context);
+                self.0.insert(local);
+            }
+            _ => {}
+        }
+    }
+}
+
+type Values = IndexVec<ValueIndex, FlatSet<ScalarInt>>;
+
+/// The dataflow state of `ConstAnalysis`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum State {
+    /// No execution reaches this point. This is the bottom of the lattice.
+    Unreachable,
+    /// The values of the tracked scalars.
+    Reachable(Values),
+}
+
+impl JoinSemiLattice for State {
+    fn join(&mut self, other: &Self) -> bool {
+        match (&mut *self, other) {
+            (_, State::Unreachable) => false,
+            (State::Unreachable, State::Reachable(_)) => {
+                *self = other.clone();
+                true
+            }
+            (State::Reachable(values), State::Reachable(other)) => values.join(other),
+        }
+    }
+}
+
+impl<C> DebugWithContext<C> for State {}
+
+/// Computes the values of the tracked places at each point of a body.
+struct ConstAnalysis<'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'mir Body<'tcx>,
+    map: PlaceMap<'tcx>,
+    ecx: InterpCx<'mir, 'tcx, ConstPropMachine<'mir, 'tcx>>,
+}
+
+impl<'mir, 'tcx> ConstAnalysis<'mir, 'tcx> {
     fn new(
-        body: &Body<'tcx>,
-        dummy_body: &'mir Body<'tcx>,
         tcx: TyCtxt<'tcx>,
-    ) -> ConstPropagator<'mir, 'tcx> {
-        let def_id = body.source.def_id();
-        let substs = &InternalSubsts::identity_for_item(tcx, def_id);
-        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        param_env: ParamEnv<'tcx>,
+        body: &'mir Body<'tcx>,
+        map: PlaceMap<'tcx>,
+    ) -> ConstAnalysis<'mir, 'tcx> {
+        ConstAnalysis { tcx, body, map, ecx: new_ecx(tcx, param_env, body) }
+    }
 
-        let span = tcx.def_span(def_id);
-        // FIXME: `CanConstProp::check` computes the layout of all locals, return those layouts
-        // so we can write them to `ecx.frame_mut().locals.layout, reducing the duplication in
-        // `layout_of` query invocations.
-        let can_const_prop = CanConstProp::check(tcx, param_env, body);
-        let mut only_propagate_inside_block_locals = BitSet::new_empty(can_const_prop.len());
-        for (l, mode) in can_const_prop.iter_enumerated() {
-            if *mode == ConstPropMode::OnlyInsideOwnBlock {
-                only_propagate_inside_block_locals.insert(l);
+    /// Returns the value, if any, of evaluating `c`. Errors are reported by
+    /// `Collector`.
+    fn eval_constant(&self, c: &Constant<'tcx>) -> Option<OpTy<'tcx>> {
+        // FIXME we need to revisit this for #67176
+        if c.needs_subst() {
+            return None;
+        }
+        self.ecx.const_to_op(c.literal, None).ok()
+    }
+
+    fn eval_place(&self, place: Place<'tcx>, values: &Values) -> Option<ImmTy<'tcx>> {
+        let index = self.map.find(place.as_ref())?;
+        self.read(index, values)
+    }
+
+    fn read(&self, index: PlaceIndex, values: &Values) -> Option<ImmTy<'tcx>> {
+        let info = &self.map.places[index];
+        match values[info.value?] {
+            FlatSet::Elem(int) => {
+                let layout = self.ecx.layout_of(info.ty).ok()?;
+                Some(ImmTy::from_scalar(Scalar::Int(int), layout))
             }
+            FlatSet::Bottom | FlatSet::Top => None,
         }
-        let mut ecx = InterpCx::new(
-            tcx,
-            span,
-            param_env,
-            ConstPropMachine::new(only_propagate_inside_block_locals, can_const_prop),
-            (),
-        );
-
-        let ret = ecx
-            .layout_of(body.return_ty().subst(tcx, substs))
-            .ok()
-            // Don't bother allocating memory for ZST types which have no values
-            // or for large values.
-            .filter(|ret_layout| {
-                !ret_layout.is_zst() && ret_layout.size < Size::from_bytes(MAX_ALLOC_LIMIT)
-            })
-            .map(|ret_layout| ecx.allocate(ret_layout, MemoryKind::Stack));
-
-        ecx.push_stack_frame(
-            Instance::new(def_id, substs),
-            dummy_body,
-            ret.map(Into::into),
-            StackPopCleanup::None { cleanup: false },
-        )
-        .expect("failed to push initial stack frame");
+    }
 
-        ConstPropagator {
-            ecx,
-            tcx,
-            param_env,
-            // FIXME(eddyb) avoid cloning these two fields more than once,
-            // by accessing them through `ecx` instead.
-            source_scopes: body.source_scopes.clone(),
-            //FIXME(wesleywiser) we can't steal this because `Visitor::super_visit_body()` needs it
-            local_decls: body.local_decls.clone(),
-            source_info: None,
+    /// Returns the value of `op`, if it is a known scalar.
+    fn eval_operand(&self, op: &Operand<'tcx>, values: &Values) -> Option<ImmTy<'tcx>> {
+        match *op {
+            Operand::Copy(place) | Operand::Move(place) => self.eval_place(place, values),
+            Operand::Constant(ref c) => {
+                let op = self.eval_constant(c)?;
+                if !is_tracked_scalar(op.layout.ty) {
+                    return None;
+                }
+                let imm = self.ecx.read_immediate(op).ok()?;
+                scalar_int(&imm).map(|_| imm)
+            }
+        }
+    }
+
+    /// Returns the result of a binary operation and whether it overflowed, if
+    /// it is known.
+    fn binary_op(
+        &self,
+        op: BinOp,
+        left: &Operand<'tcx>,
+        right: &Operand<'tcx>,
+        values: &Values,
+    ) -> Option<(ImmTy<'tcx>, bool)> {
+        match (self.eval_operand(left, values), self.eval_operand(right, values)) {
+            (Some(l), Some(r)) => {
+                let (val, overflow, ty) = self.ecx.overflowing_binary_op(op, l, r).ok()?;
+                Some((ImmTy::from_scalar(val, self.ecx.layout_of(ty).ok()?), overflow))
+            }
+            // Use algebraic identities to find the result from one operand.
+            (Some(known), None) | (None, Some(known)) => {
+                let bits = scalar_int(&known)?.assert_bits(known.layout.size);
+                let ty = known.layout.ty;
+                let absorbs = match op {
+                    BinOp::BitAnd => bits == 0,
+                    BinOp::BitOr => {
+                        bits == known.layout.size.truncate(u128::MAX) || (ty.is_bool() && bits == 1)
+                    }
+                    BinOp::Mul => ty.is_integral() && bits == 0,
+                    _ => false,
+                };
+                if absorbs {
+                    Some((known, false))
+                } else {
+                    None
+                }
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the value of `rvalue`, if it is known.
+    fn eval_rvalue(&self, rvalue: &Rvalue<'tcx>, values: &Values) -> Option<ImmTy<'tcx>> {
+        match *rvalue {
+            Rvalue::Use(ref op) => self.eval_operand(op, values),
+            Rvalue::BinaryOp(op, ref left, ref right) => {
+                self.binary_op(op, left, right, values).map(|(val, _)| val)
+            }
+            Rvalue::CheckedBinaryOp(op, ref left, ref right) => {
+                let (val, overflow) = self.binary_op(op, left, right, values)?;
+                let ty = rvalue.ty(self.body, self.tcx);
+                let pair = Immediate::ScalarPair(
+                    val.to_scalar().ok()?.into(),
+                    Scalar::from_bool(overflow).into(),
+                );
+                Some(ImmTy::from_immediate(pair, self.ecx.layout_of(ty).ok()?))
+            }
+            Rvalue::UnaryOp(op, ref arg) => {
+                let arg = self.eval_operand(arg, values)?;
+                let (val, _, ty) = self.ecx.overflowing_unary_op(op, arg).ok()?;
+                Some(ImmTy::from_scalar(val, self.ecx.layout_of(ty).ok()?))
+            }
+            Rvalue::Cast(CastKind::Misc, ref op, ty) if is_tracked_scalar(ty) => {
+                let op = self.eval_operand(op, values)?;
+                let imm = self.ecx.misc_cast(op, ty).ok()?;
+                Some(ImmTy::from_immediate(imm, self.ecx.layout_of(ty).ok()?))
+            }
+            Rvalue::Discriminant(place) => {
+                let index = self.map.find(place.as_ref())?;
+                self.read(self.map.child(index, TrackElem::Discriminant)?, values)
+            }
+            Rvalue::Len(place) => {
+                let len = match place.ty(self.body, self.tcx).ty.kind() {
+                    ty::Array(_, len) => len.try_eval_usize(self.tcx, self.ecx.param_env)?,
+                    _ => return None,
+                };
+                let usize_layout = self.ecx.layout_of(self.tcx.types.usize).ok()?;
+                Some(ImmTy::from_uint(len, usize_layout))
+            }
+            Rvalue::NullaryOp(NullOp::SizeOf, ty) => {
+                let layout = self.ecx.layout_of(ty).ok()?;
+                if layout.is_unsized() {
+                    return None;
+                }
+                let usize_layout = self.ecx.layout_of(self.tcx.types.usize).ok()?;
+                Some(ImmTy::from_uint(layout.size.bytes(), usize_layout))
+            }
+            _ => None,
+        }
+    }
+
+    fn flood(&self, place: PlaceRef<'tcx>, values: &mut Values) {
+        if let Some(index) = self.map.find_prefix(place) {
+            self.flood_index(index, values);
+        }
+    }
+
+    /// Forgets the values of `index` and of its parts.
+    fn flood_index(&self, index: PlaceIndex, values: &mut Values) {
+        let info = &self.map.places[index];
+        if let Some(value) = info.value {
+            values[value] = FlatSet::Top;
+        }
+        for &(_, child) in &info.children {
+            self.flood_index(child, values);
+        }
+    }
+
+    /// Writes the tracked parts of `op` into `index`, which must be flooded.
+    fn write(&self, index: PlaceIndex, op: OpTy<'tcx>, values: &mut Values) {
+        let info = &self.map.places[index];
+        if let Some(value) = info.value {
+            if let Some(int) = self.ecx.read_immediate(op).ok().as_ref().and_then(scalar_int) {
+                values[value] = FlatSet::Elem(int);
+            }
+        }
+        for &(elem, child) in &info.children {
+            match elem {
+                TrackElem::Field(field) => {
+                    if let Ok(field) = self.ecx.operand_field(op, field.index()) {
+                        self.write(child, field, values);
+                    }
+                }
+                TrackElem::Discriminant => {
+                    if let Ok((Scalar::Int(int), _)) = self.ecx.read_discriminant(op) {
+                        values[self.map.places[child].value.unwrap()] = FlatSet::Elem(int);
+                    }
+                }
+            }
         }
     }
 
-    fn get_const(&self, place: Place<'tcx>) -> Option<OpTy<'tcx>> {
-        let op = match self.ecx.eval_place_to_op(place, None) {
-            Ok(op) => op,
-            Err(e) => {
-                trace!("get_const failed: {}", e);
-                return None;
+    /// Copies the values of `source` into `target`, which has the same type.
+    fn copy(&self, target: PlaceIndex, source: PlaceIndex, values: &mut Values) {
+        let (target, source) = (&self.map.places[target], &self.map.places[source]);
+        if let Some(value) = target.value {
+            values[value] = match source.value {
+                Some(source) => values[source].clone(),
+                None => FlatSet::Top,
+            };
+        }
+        // The children can differ if one of the places is nested deeper.
+        for &(elem, child) in &target.children {
+            match source.children.iter().find(|&&(e, _)| e == elem) {
+                Some(&(_, source_child)) => self.copy(child, source_child, values),
+                None => self.flood_index(child, values),
             }
+        }
+    }
+
+    fn assign_operand(&self, place: Place<'tcx>, operand: &Operand<'tcx>, values: &mut Values) {
+        let target = match self.map.find(place.as_ref()) {
+            Some(target) => target,
+            None => return self.flood(place.as_ref(), values),
+        };
+        match *operand {
+            Operand::Copy(source) | Operand::Move(source) => match self.map.find(source.as_ref()) {
+                Some(source) => self.copy(target, source, values),
+                None => self.flood_index(target, values),
+            },
+            Operand::Constant(ref c) => {
+                self.flood_index(target, values);
+                if let Some(op) = self.eval_constant(c) {
+                    self.write(target, op, values);
+                }
+            }
+        }
+    }
+
+    fn assign(&self, place: Place<'tcx>, rvalue: &Rvalue<'tcx>, values: &mut Values) {
+        if let Rvalue::Use(operand) = rvalue {
+            return self.assign_operand(place, operand, values);
+        }
+        let target = match self.map.find(place.as_ref()) {
+            Some(target) => target,
+            None => return self.flood(place.as_ref(), values),
         };
+        let value = self.eval_rvalue(rvalue, values);
+        self.flood_index(target, values);
+        if let Some(value) = value {
+            self.write(target, value.into(), values);
+        }
+    }
+}
+
+impl<'tcx> AnalysisDomain<'tcx> for ConstAnalysis<'_, 'tcx> {
+    type Domain = State;
+
+    const NAME: &'static str = "ConstAnalysis";
 
-        // Try to read the local as an immediate so that if it is representable as a scalar, we can
-        // handle it as such, but otherwise, just return the value as is.
-        Some(match self.ecx.try_read_immediate(op) {
-            Ok(Ok(imm)) => imm.into(),
-            _ => op,
-        })
+    fn bottom_value(&self, _body: &Body<'tcx>) -> State {
+        State::Unreachable
     }
 
-    /// Remove `local` from the pool of `Locals`. Allows writing to them,
-    /// but not reading from them anymore.
-    fn remove_const(ecx: &mut InterpCx<'mir, 'tcx, ConstPropMachine<'mir, 'tcx>>, local: Local) {
-        ecx.frame_mut().locals[local] =
-            LocalState { value: LocalValue::Uninitialized, layout: Cell::new(None) };
+    fn initialize_start_block(&self, _body: &Body<'tcx>, state: &mut State) {
+        // The arguments are unknown, and so are the other locals until they
+        // are assigned.
+        *state = State::Reachable(IndexVec::from_elem_n(FlatSet::Top, self.map.value_count));
     }
+}
 
-    fn lint_root(&self, source_info: SourceInfo) -> Option<HirId> {
-        match &self.source_scopes[source_info.scope].local_data {
-            ClearCrossCrate::Set(data) => Some(data.lint_root),
-            ClearCrossCrate::Clear => None,
+impl<'tcx> Analysis<'tcx> for ConstAnalysis<'_, 'tcx> {
+    fn apply_statement_effect(
+        &self,
+        state: &mut State,
+        statement: &Statement<'tcx>,
+        _location: Location,
+    ) {
+        let values = match state {
+            State::Reachable(values) => values,
+            State::Unreachable => return,
+        };
+        match statement.kind {
+            StatementKind::Assign(box (place, ref rvalue)) => self.assign(place, rvalue, values),
+            StatementKind::SetDiscriminant { box place, variant_index } => {
+                let discr = self
+                    .map
+                    .find(place.as_ref())
+                    .and_then(|index| self.map.child(index, TrackElem::Discriminant));
+                let discr = match discr {
+                    Some(discr) => discr,
+                    None => return self.flood(place.as_ref(), values),
+                };
+                self.flood_index(discr, values);
+                let ty = place.ty(self.body, self.tcx).ty;
+                let size = match self.ecx.layout_of(self.map.places[discr].ty) {
+                    Ok(layout) => layout.size,
+                    Err(_) => return,
+                };
+                if let Some(int) = ty
+                    .discriminant_for_variant(self.tcx, variant_index)
+                    .and_then(|discr| ScalarInt::try_from_uint(discr.val, size))
+                {
+                    values[self.map.places[discr].value.unwrap()] = FlatSet::Elem(int);
+                }
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                self.flood(Place::from(local).as_ref(), values);
+            }
+            StatementKind::LlvmInlineAsm(ref asm) => {
+                for place in asm.outputs.iter() {
+                    self.flood(place.as_ref(), values);
+                }
+            }
+            StatementKind::FakeRead(..)
+            | StatementKind::Retag(..)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Coverage(..)
+            | StatementKind::Nop => {}
         }
     }
 
-    fn use_ecx<F, T>(&mut self, f: F) -> Option<T>
-    where
-        F: FnOnce(&mut Self) -> InterpResult<'tcx, T>,
-    {
-        match f(self) {
-            Ok(val) => Some(val),
-            Err(error) => {
-                trace!("InterpCx operation failed: {:?}", error);
-                // Some errors shouldn't come up because creating them causes
-                // an allocation, which we should avoid. When that happens,
-                // dedicated error variants should be introduced instead.
-                assert!(
-                    !error.kind.allocates(),
-                    "const-prop encountered allocating error: {}",
-                    error
-                );
-                None
+    fn apply_terminator_effect(
+        &self,
+        state: &mut State,
+        terminator: &Terminator<'tcx>,
+        _location: Location,
+    ) {
+        let values = match state {
+            State::Reachable(values) => values,
+            State::Unreachable => return,
+        };
+        match terminator.kind {
+            TerminatorKind::DropAndReplace { place, ref value, .. } => {
+                self.assign_operand(place, value, values);
             }
+            TerminatorKind::InlineAsm { ref operands, .. } => {
+                for operand in operands {
+                    match *operand {
+                        InlineAsmOperand::Out { place: Some(place), .. }
+                        | InlineAsmOperand::InOut { out_place: Some(place), .. } => {
+                            self.flood(place.as_ref(), values);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
         }
     }
 
-    /// Returns the value, if any, of evaluating `c`.
-    fn eval_constant(&mut self, c: &Constant<'tcx>, source_info: SourceInfo) -> Option<OpTy<'tcx>> {
-        // FIXME we need to revisit this for #67176
-        if c.needs_subst() {
-            return None;
+    fn apply_call_return_effect(
+        &self,
+        state: &mut State,
+        _block: BasicBlock,
+        _func: &Operand<'tcx>,
+        _args: &[Operand<'tcx>],
+        return_place: Place<'tcx>,
+    ) {
+        if let State::Reachable(values) = state {
+            self.flood(return_place.as_ref(), values);
         }
+    }
+
+    fn apply_yield_resume_effect(
+        &self,
+        state: &mut State,
+        _resume_block: BasicBlock,
+        resume_place: Place<'tcx>,
+    ) {
+        if let State::Reachable(values) = state {
+            self.flood(resume_place.as_ref(), values);
+        }
+    }
 
-        match self.ecx.const_to_op(c.literal, None) {
-            Ok(op) => Some(op),
-            Err(error) => {
-                let tcx = self.ecx.tcx.at(c.span);
-                let err = ConstEvalErr::new(&self.ecx, error, Some(c.span));
-                if let Some(lint_root) = self.lint_root(source_info) {
-                    let lint_only = match c.literal.val {
-                        // Promoteds must lint and not error as the user didn't ask for them
-                        ConstKind::Unevaluated(_, _, Some(_)) => true,
-                        // Out of backwards compatibility we cannot report hard errors in unused
-                        // generic functions using associated constants of the generic parameters.
-                        _ => c.literal.needs_subst(),
+    fn apply_switch_int_edge_effects(
+        &self,
+        block: BasicBlock,
+        discr: &Operand<'tcx>,
+        apply_edge_effects: &mut impl SwitchIntEdgeEffects<State>,
+    ) {
+        let targets = match self.body[block].terminator().kind {
+            TerminatorKind::SwitchInt { ref targets, .. } => targets,
+            _ => bug!("expected a `SwitchInt` terminator"),
+        };
+        let discr_size = match self.ecx.layout_of(discr.ty(self.body, self.tcx)) {
+            Ok(layout) => layout.size,
+            Err(_) => return,
+        };
+        let discr_value = discr
+            .place()
+            .and_then(|place| self.map.find(place.as_ref()))
+            .and_then(|index| self.map.places[index].value);
+
+        apply_edge_effects.apply(|state, target: SwitchIntTarget| {
+            let values = match state {
+                State::Reachable(values) => values,
+                State::Unreachable => return,
+            };
+            match self.eval_operand(discr, values).as_ref().and_then(scalar_int) {
+                // Only the edge of the value of the discriminant is taken.
+                Some(int) => {
+                    let bits = int.assert_bits(discr_size);
+                    let taken = match target.value {
+                        Some(value) => value == bits,
+                        None => targets.iter().all(|(value, _)| value != bits),
                     };
-                    if lint_only {
-                        // Out of backwards compatibility we cannot report hard errors in unused
-                        // generic functions using associated constants of the generic parameters.
-                        err.report_as_lint(tcx, "erroneous constant used", lint_root, Some(c.span));
-                    } else {
-                        err.report_as_error(tcx, "erroneous constant used");
+                    if !taken {
+                        *state = State::Unreachable;
                     }
-                } else {
-                    err.report_as_error(tcx, "erroneous constant used");
                 }
-                None
+                // Along the edge of a value, the discriminant has that value.
+                None => {
+                    if let (Some(value), Some(discr_value)) = (target.value, discr_value) {
+                        if let Some(int) = ScalarInt::try_from_uint(value, discr_size) {
+                            values[discr_value] = FlatSet::Elem(int);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// The changes to a body found by `Collector`.
+#[derive(Default)]
+struct Patch<'tcx> {
+    /// The known values of the rvalues of assignments.
+    assignments: FxHashMap<Location, ImmTy<'tcx>>,
+    /// The known values of the places used as operands, before the
+    /// statement or terminator that uses them.
+    operands: FxHashMap<(Location, Place<'tcx>), ImmTy<'tcx>>,
+    /// The blocks that are never reached.
+    unreachable_blocks: Vec<BasicBlock>,
+}
+
+/// Reports the statically known assertion failures and overflows, and
+/// records the known values in a `Patch`.
+struct Collector<'a, 'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'mir Body<'tcx>,
+    cursor: ResultsRefCursor<'a, 'mir, 'tcx, ConstAnalysis<'mir, 'tcx>>,
+    patch: Patch<'tcx>,
+    /// The state within the unreachable block that is visited, if any.
+    unreachable_state: Option<State>,
+}
+
+impl<'a, 'mir, 'tcx> Collector<'a, 'mir, 'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        body: &'mir Body<'tcx>,
+        results: &'a Results<'tcx, ConstAnalysis<'mir, 'tcx>>,
+    ) -> Self {
+        let cursor = ResultsRefCursor::new(body, results);
+        Collector { tcx, body, cursor, patch: Patch::default(), unreachable_state: None }
+    }
+
+    fn collect(mut self) -> Patch<'tcx> {
+        let body = self.body;
+        for (block, data) in body.basic_blocks().iter_enumerated() {
+            self.cursor.seek_to_block_start(block);
+            if let State::Unreachable = self.cursor.get() {
+                self.patch.unreachable_blocks.push(block);
+                self.collect_unreachable_block(block, data);
+                continue;
+            }
+
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block, statement_index };
+                self.cursor.seek_before_primary_effect(location);
+                self.visit_statement(statement, location);
             }
+            let location = body.terminator_loc(block);
+            self.cursor.seek_before_primary_effect(location);
+            self.visit_terminator(data.terminator(), location);
         }
+        self.patch
     }
 
-    /// Returns the value, if any, of evaluating `place`.
-    fn eval_place(&mut self, place: Place<'tcx>) -> Option<OpTy<'tcx>> {
-        trace!("eval_place(place={:?})", place);
-        self.use_ecx(|this| this.ecx.eval_place_to_op(place, None))
+    /// Lints an unreachable block as if it was entered with unknown values. Nothing
+    /// is recorded in the patch, as the block is removed.
+    fn collect_unreachable_block(&mut self, block: BasicBlock, data: &BasicBlockData<'tcx>) {
+        let value_count = self.analysis().map.value_count;
+        let mut state = State::Reachable(IndexVec::from_elem_n(FlatSet::Top, value_count));
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let location = Location { block, statement_index };
+            self.unreachable_state = Some(state);
+            self.visit_statement(statement, location);
+            state = self.unreachable_state.take().unwrap();
+            self.analysis().apply_statement_effect(&mut state, statement, location);
+        }
+        self.unreachable_state = Some(state);
+        self.visit_terminator(data.terminator(), self.body.terminator_loc(block));
+        self.unreachable_state = None;
     }
 
-    /// Returns the value, if any, of evaluating `op`. Calls upon `eval_constant`
-    /// or `eval_place`, depending on the variant of `Operand` used.
-    fn eval_operand(&mut self, op: &Operand<'tcx>, source_info: SourceInfo) -> Option<OpTy<'tcx>> {
-        match *op {
-            Operand::Constant(ref c) => self.eval_constant(c, source_info),
-            Operand::Move(place) | Operand::Copy(place) => self.eval_place(place),
+    fn analysis(&self) -> &ConstAnalysis<'mir, 'tcx> {
+        self.cursor.analysis()
+    }
+
+    fn values(&self) -> &Values {
+        match self.unreachable_state.as_ref().unwrap_or_else(|| self.cursor.get()) {
+            State::Reachable(values) => values,
+            State::Unreachable => bug!("unreachable code is visited without a state"),
+        }
+    }
+
+    fn lint_root(&self, source_info: SourceInfo) -> Option<HirId> {
+        match &self.body.source_scopes[source_info.scope].local_data {
+            ClearCrossCrate::Set(data) => Some(data.lint_root),
+            ClearCrossCrate::Clear => None,
         }
     }
 
@@ -512,27 +977,23 @@ impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
         source_info: SourceInfo,
         message: &'static str,
         panic: AssertKind<impl std::fmt::Debug>,
-    ) -> Option<()> {
-        let lint_root = self.lint_root(source_info)?;
-        self.tcx.struct_span_lint_hir(lint, lint_root, source_info.span, |lint| {
-            let mut err = lint.build(message);
-            err.span_label(source_info.span, format!("{:?}", panic));
-            err.emit()
-        });
-        None
+    ) {
+        if let Some(lint_root) = self.lint_root(source_info) {
+            self.tcx.struct_span_lint_hir(lint, lint_root, source_info.span, |lint| {
+                let mut err = lint.build(message);
+                err.span_label(source_info.span, format!("{:?}", panic));
+                err.emit()
+            });
+        }
     }
 
-    fn check_unary_op(
-        &mut self,
-        op: UnOp,
-        arg: &Operand<'tcx>,
-        source_info: SourceInfo,
-    ) -> Option<()> {
-        if let (val, true) = self.use_ecx(|this| {
-            let val = this.ecx.read_immediate(this.ecx.eval_operand(arg, None)?)?;
-            let (_res, overflow, _ty) = this.ecx.overflowing_unary_op(op, val)?;
-            Ok((val, overflow))
-        })? {
+    fn check_unary_op(&self, op: UnOp, arg: &Operand<'tcx>, source_info: SourceInfo) {
+        let analysis = self.analysis();
+        let val = match analysis.eval_operand(arg, self.values()) {
+            Some(val) => val,
+            None => return,
+        };
+        if let Ok((_res, true, _ty)) = analysis.ecx.overflowing_unary_op(op, val) {
             // `AssertKind` only has an `OverflowNeg` variant, so make sure that is
             // appropriate to use.
             assert_eq!(op, UnOp::Neg, "Neg is the only UnOp that can overflow");
@@ -541,32 +1002,34 @@ impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
                 source_info,
                 "this arithmetic operation will overflow",
                 AssertKind::OverflowNeg(val.to_const_int()),
-            )?;
+            );
         }
-
-        Some(())
     }
 
     fn check_binary_op(
-        &mut self,
+        &self,
         op: BinOp,
         left: &Operand<'tcx>,
         right: &Operand<'tcx>,
         source_info: SourceInfo,
-    ) -> Option<()> {
-        let r = self.use_ecx(|this| this.ecx.read_immediate(this.ecx.eval_operand(right, None)?));
-        let l = self.use_ecx(|this| this.ecx.read_immediate(this.ecx.eval_operand(left, None)?));
+    ) {
+        let analysis = self.analysis();
+        let r = analysis.eval_operand(right, self.values());
+        let l = analysis.eval_operand(left, self.values());
         // Check for exceeding shifts *even if* we cannot evaluate the LHS.
         if op == BinOp::Shr || op == BinOp::Shl {
-            let r = r?;
-            // We need the type of the LHS. We cannot use `place_layout` as that is the type
-            // of the result, which for checked binops is not the same!
-            let left_ty = left.ty(&self.local_decls, self.tcx);
-            let left_size = self.ecx.layout_of(left_ty).ok()?.size;
-            let right_size = r.layout.size;
-            let r_bits = r.to_scalar().ok();
-            // This is basically `force_bits`.
-            let r_bits = r_bits.and_then(|r| r.to_bits_or_ptr(right_size, &self.tcx).ok());
+            let r = match r {
+                Some(r) => r,
+                None => return,
+            };
+            // We need the type of the LHS. We cannot use the type of the result, which for
+            // checked binops is not the same!
+            let left_ty = left.ty(self.body, self.tcx);
+            let left_size = match analysis.ecx.layout_of(left_ty) {
+                Ok(layout) => layout.size,
+                Err(_) => return,
+            };
+            let r_bits = scalar_int(&r).map(|r| r.assert_bits(r.size()));
             if r_bits.map_or(false, |b| b >= left_size.bits() as u128) {
                 debug!("check_binary_op: reporting assert for {:?}", source_info);
                 self.report_assert_as_lint(
@@ -586,449 +1049,259 @@ impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
                         },
                         r.to_const_int(),
                     ),
-                )?;
+                );
+                return;
             }
         }
 
+        // The remaining operators are handled through `overflowing_binary_op`.
         if let (Some(l), Some(r)) = (l, r) {
-            // The remaining operators are handled through `overflowing_binary_op`.
-            if self.use_ecx(|this| {
-                let (_res, overflow, _ty) = this.ecx.overflowing_binary_op(op, l, r)?;
-                Ok(overflow)
-            })? {
+            if let Ok((_res, true, _ty)) = analysis.ecx.overflowing_binary_op(op, l, r) {
                 self.report_assert_as_lint(
                     lint::builtin::ARITHMETIC_OVERFLOW,
                     source_info,
                     "this arithmetic operation will overflow",
                     AssertKind::Overflow(op, l.to_const_int(), r.to_const_int()),
-                )?;
+                );
             }
         }
-        Some(())
     }
 
-    fn propagate_operand(&mut self, operand: &mut Operand<'tcx>) {
-        match *operand {
-            Operand::Copy(l) | Operand::Move(l) => {
-                if let Some(value) = self.get_const(l) {
-                    if self.should_const_prop(value) {
-                        // FIXME(felix91gr): this code only handles `Scalar` cases.
-                        // For now, we're not handling `ScalarPair` cases because
-                        // doing so here would require a lot of code duplication.
-                        // We should hopefully generalize `Operand` handling into a fn,
-                        // and use it to do const-prop here and everywhere else
-                        // where it makes sense.
-                        if let interpret::Operand::Immediate(interpret::Immediate::Scalar(
-                            ScalarMaybeUninit::Scalar(scalar),
-                        )) = *value
-                        {
-                            *operand = self.operand_from_scalar(
-                                scalar,
-                                value.layout.ty,
-                                self.source_info.unwrap().span,
-                            );
-                        }
-                    }
-                }
-            }
-            Operand::Constant(_) => (),
+    fn check_assert(
+        &self,
+        cond: &Operand<'tcx>,
+        expected: bool,
+        msg: &AssertKind<Operand<'tcx>>,
+        source_info: SourceInfo,
+    ) {
+        let analysis = self.analysis();
+        let value = match analysis.eval_operand(cond, self.values()).as_ref().and_then(scalar_int) {
+            Some(value) => value,
+            None => return,
+        };
+        trace!("assertion on {:?} should be {:?}", value, expected);
+        if Scalar::Int(value) == Scalar::from_bool(expected) {
+            return;
         }
-    }
 
-    fn const_prop(
-        &mut self,
-        rvalue: &Rvalue<'tcx>,
-        source_info: SourceInfo,
-        place: Place<'tcx>,
-    ) -> Option<()> {
-        // Perform any special handling for specific Rvalue types.
-        // Generally, checks here fall into one of two categories:
-        //   1. Additional checking to provide useful lints to the user
-        //        - In this case, we will do some validation and then fall through to the
-        //          end of the function which evals the assignment.
-        //   2. Working around bugs in other parts of the compiler
-        //        - In this case, we'll return `None` from this function to stop evaluation.
-        match rvalue {
-            // Additional checking: give lints to the user if an overflow would occur.
-            // We do this here and not in the `Assert` terminator as that terminator is
-            // only sometimes emitted (overflow checks can be disabled), but we want to always
-            // lint.
-            Rvalue::UnaryOp(op, arg) => {
-                trace!("checking UnaryOp(op = {:?}, arg = {:?})", op, arg);
-                self.check_unary_op(*op, arg, source_info)?;
-            }
-            Rvalue::BinaryOp(op, left, right) => {
-                trace!("checking BinaryOp(op = {:?}, left = {:?}, right = {:?})", op, left, right);
-                self.check_binary_op(*op, left, right, source_info)?;
-            }
-            Rvalue::CheckedBinaryOp(op, left, right) => {
-                trace!(
-                    "checking CheckedBinaryOp(op = {:?}, left = {:?}, right = {:?})",
-                    op,
-                    left,
-                    right
-                );
-                self.check_binary_op(*op, left, right, source_info)?;
+        enum DbgVal<T> {
+            Val(T),
+            Underscore,
+        }
+        impl<T: std::fmt::Debug> std::fmt::Debug for DbgVal<T> {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Self::Val(val) => val.fmt(fmt),
+                    Self::Underscore => fmt.write_str("_"),
+                }
             }
-
-            // Do not try creating references (#67862)
-            Rvalue::AddressOf(_, place) | Rvalue::Ref(_, _, place) => {
-                trace!("skipping AddressOf | Ref for {:?}", place);
-
-                // This may be creating mutable references or immutable references to cells.
-                // If that happens, the pointed to value could be mutated via that reference.
-                // Since we aren't tracking references, the const propagator loses track of what
-                // value the local has right now.
-                // Thus, all locals that have their reference taken
-                // must not take part in propagation.
-                Self::remove_const(&mut self.ecx, place.local);
-
-                return None;
+        }
+        let eval_to_int = |op| {
+            // This can be `None` if the lhs wasn't const propagated and we just
+            // triggered the assert on the value of the rhs.
+            match analysis.eval_operand(op, self.values()) {
+                Some(op) => DbgVal::Val(op.to_const_int()),
+                None => DbgVal::Underscore,
             }
-            Rvalue::ThreadLocalRef(def_id) => {
-                trace!("skipping ThreadLocalRef({:?})", def_id);
-
-                return None;
+        };
+        let msg = match msg {
+            AssertKind::DivisionByZero(op) => Some(AssertKind::DivisionByZero(eval_to_int(op))),
+            AssertKind::RemainderByZero(op) => Some(AssertKind::RemainderByZero(eval_to_int(op))),
+            AssertKind::BoundsCheck { ref len, ref index } => {
+                let len = eval_to_int(len);
+                let index = eval_to_int(index);
+                Some(AssertKind::BoundsCheck { len, index })
             }
-
-            // There's no other checking to do at this time.
-            Rvalue::Aggregate(..)
-            | Rvalue::Use(..)
-            | Rvalue::Repeat(..)
-            | Rvalue::Len(..)
-            | Rvalue::Cast(..)
-            | Rvalue::Discriminant(..)
-            | Rvalue::NullaryOp(..) => {}
-        }
-
-        // FIXME we need to revisit this for #67176
-        if rvalue.needs_subst() {
-            return None;
-        }
-
-        if self.tcx.sess.opts.debugging_opts.mir_opt_level >= 3 {
-            self.eval_rvalue_with_identities(rvalue, place)
-        } else {
-            self.use_ecx(|this| this.ecx.eval_rvalue_into_place(rvalue, place))
+            // Overflow is are already covered by checks on the binary operators.
+            AssertKind::Overflow(..) | AssertKind::OverflowNeg(_) => None,
+            // Need proper const propagator for these.
+            _ => None,
+        };
+        if let Some(msg) = msg {
+            self.report_assert_as_lint(
+                lint::builtin::UNCONDITIONAL_PANIC,
+                source_info,
+                "this operation will panic at runtime",
+                msg,
+            );
         }
     }
+}
 
-    // Attempt to use albegraic identities to eliminate constant expressions
-    fn eval_rvalue_with_identities(
-        &mut self,
-        rvalue: &Rvalue<'tcx>,
-        place: Place<'tcx>,
-    ) -> Option<()> {
-        self.use_ecx(|this| {
-            match rvalue {
-                Rvalue::BinaryOp(op, left, right) | Rvalue::CheckedBinaryOp(op, left, right) => {
-                    let l = this.ecx.eval_operand(left, None);
-                    let r = this.ecx.eval_operand(right, None);
-
-                    let const_arg = match (l, r) {
-                        (Ok(x), Err(_)) | (Err(_), Ok(x)) => this.ecx.read_immediate(x)?,
-                        (Err(e), Err(_)) => return Err(e),
-                        (Ok(_), Ok(_)) => {
-                            this.ecx.eval_rvalue_into_place(rvalue, place)?;
-                            return Ok(());
-                        }
-                    };
-
-                    let arg_value =
-                        this.ecx.force_bits(const_arg.to_scalar()?, const_arg.layout.size)?;
-                    let dest = this.ecx.eval_place(place)?;
-
-                    match op {
-                        BinOp::BitAnd => {
-                            if arg_value == 0 {
-                                this.ecx.write_immediate(*const_arg, dest)?;
-                            }
-                        }
-                        BinOp::BitOr => {
-                            if arg_value == const_arg.layout.size.truncate(u128::MAX)
-                                || (const_arg.layout.ty.is_bool() && arg_value == 1)
-                            {
-                                this.ecx.write_immediate(*const_arg, dest)?;
-                            }
-                        }
-                        BinOp::Mul => {
-                            if const_arg.layout.ty.is_integral() && arg_value == 0 {
-                                if let Rvalue::CheckedBinaryOp(_, _, _) = rvalue {
-                                    let val = Immediate::ScalarPair(
-                                        const_arg.to_scalar()?.into(),
-                                        Scalar::from_bool(false).into(),
-                                    );
-                                    this.ecx.write_immediate(val, dest)?;
-                                } else {
-                                    this.ecx.write_immediate(*const_arg, dest)?;
-                                }
-                            }
-                        }
-                        _ => {
-                            this.ecx.eval_rvalue_into_place(rvalue, place)?;
-                        }
-                    }
+impl<'tcx> Visitor<'tcx> for Collector<'_, '_, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        if let StatementKind::Assign(box (_, ref rvalue)) = statement.kind {
+            // Give lints to the user if an overflow would occur. We do this here and not in
+            // the `Assert` terminator as that terminator is only sometimes emitted (overflow
+            // checks can be disabled), but we want to always lint.
+            match *rvalue {
+                Rvalue::UnaryOp(op, ref arg) => {
+                    self.check_unary_op(op, arg, statement.source_info);
                 }
-                _ => {
-                    this.ecx.eval_rvalue_into_place(rvalue, place)?;
+                Rvalue::BinaryOp(op, ref left, ref right)
+                | Rvalue::CheckedBinaryOp(op, ref left, ref right) => {
+                    self.check_binary_op(op, left, right, statement.source_info);
                 }
+                _ => {}
             }
 
-            Ok(())
-        })
-    }
-
-    /// Creates a new `Operand::Constant` from a `Scalar` value
-    fn operand_from_scalar(&self, scalar: Scalar, ty: Ty<'tcx>, span: Span) -> Operand<'tcx> {
-        Operand::Constant(Box::new(Constant {
-            span,
-            user_ty: None,
-            literal: ty::Const::from_scalar(self.tcx, scalar, ty),
-        }))
-    }
-
-    fn replace_with_const(
-        &mut self,
-        rval: &mut Rvalue<'tcx>,
-        value: OpTy<'tcx>,
-        source_info: SourceInfo,
-    ) {
-        if let Rvalue::Use(Operand::Constant(c)) = rval {
-            if !matches!(c.literal.val, ConstKind::Unevaluated(..)) {
-                trace!("skipping replace of Rvalue::Use({:?} because it is already a const", c);
-                return;
+            if self.unreachable_state.is_none() {
+                if let Some(value) = self.analysis().eval_rvalue(rvalue, self.values()) {
+                    self.patch.assignments.insert(location, value);
+                }
             }
         }
+        self.super_statement(statement, location);
+    }
 
-        trace!("attepting to replace {:?} with {:?}", rval, value);
-        if let Err(e) = self.ecx.const_validate_operand(
-            value,
-            vec![],
-            // FIXME: is ref tracking too expensive?
-            // FIXME: what is the point of ref tracking if we do not even check the tracked refs?
-            &mut interpret::RefTracking::empty(),
-            CtfeValidationMode::Regular,
-        ) {
-            trace!("validation error, attempt failed: {:?}", e);
-            return;
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Assert { ref cond, expected, ref msg, .. } = terminator.kind {
+            self.check_assert(cond, expected, msg, terminator.source_info);
         }
+        self.super_terminator(terminator, location);
+    }
 
-        // FIXME> figure out what to do when try_read_immediate fails
-        let imm = self.use_ecx(|this| this.ecx.try_read_immediate(value));
-
-        if let Some(Ok(imm)) = imm {
-            match *imm {
-                interpret::Immediate::Scalar(ScalarMaybeUninit::Scalar(scalar)) => {
-                    *rval = Rvalue::Use(self.operand_from_scalar(
-                        scalar,
-                        value.layout.ty,
-                        source_info.span,
-                    ));
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+        match *operand {
+            Operand::Copy(place) | Operand::Move(place) => {
+                if self.unreachable_state.is_some() {
+                    return;
                 }
-                Immediate::ScalarPair(
-                    ScalarMaybeUninit::Scalar(_),
-                    ScalarMaybeUninit::Scalar(_),
-                ) => {
-                    // Found a value represented as a pair. For now only do const-prop if the type
-                    // of `rvalue` is also a tuple with two scalars.
-                    // FIXME: enable the general case stated above ^.
-                    let ty = &value.layout.ty;
-                    // Only do it for tuples
-                    if let ty::Tuple(substs) = ty.kind() {
-                        // Only do it if tuple is also a pair with two scalars
-                        if substs.len() == 2 {
-                            let alloc = self.use_ecx(|this| {
-                                let ty1 = substs[0].expect_ty();
-                                let ty2 = substs[1].expect_ty();
-                                let ty_is_scalar = |ty| {
-                                    this.ecx.layout_of(ty).ok().map(|layout| layout.abi.is_scalar())
-                                        == Some(true)
-                                };
-                                if ty_is_scalar(ty1) && ty_is_scalar(ty2) {
-                                    let alloc = this
-                                        .ecx
-                                        .intern_with_temp_alloc(value.layout, |ecx, dest| {
-                                            ecx.write_immediate_to_mplace(*imm, dest)
-                                        })
-                                        .unwrap();
-                                    Ok(Some(alloc))
-                                } else {
-                                    Ok(None)
-                                }
-                            });
-
-                            if let Some(Some(alloc)) = alloc {
-                                // Assign entire constant in a single statement.
-                                // We can't use aggregates, as we run after the aggregate-lowering `MirPhase`.
-                                *rval = Rvalue::Use(Operand::Constant(Box::new(Constant {
-                                    span: source_info.span,
-                                    user_ty: None,
-                                    literal: self.ecx.tcx.mk_const(ty::Const {
-                                        ty,
-                                        val: ty::ConstKind::Value(ConstValue::ByRef {
-                                            alloc,
-                                            offset: Size::ZERO,
-                                        }),
-                                    }),
-                                })));
-                            }
-                        }
-                    }
+                if let Some(value) = self.analysis().eval_place(place, self.values()) {
+                    self.patch.operands.insert((location, place), value);
                 }
-                // Scalars or scalar pairs that contain undef values are assumed to not have
-                // successfully evaluated and are thus not propagated.
-                _ => {}
+            }
+            Operand::Constant(ref constant) => {
+                ErroneousConstants { tcx: self.tcx, body: self.body, ecx: &self.analysis().ecx }
+                    .visit_constant(constant, location);
             }
         }
     }
+}
 
-    /// Returns `true` if and only if this `op` should be const-propagated into.
-    fn should_const_prop(&mut self, op: OpTy<'tcx>) -> bool {
-        let mir_opt_level = self.tcx.sess.opts.debugging_opts.mir_opt_level;
+/// Reports the constants whose evaluation fails.
+struct ErroneousConstants<'a, 'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'mir Body<'tcx>,
+    ecx: &'a InterpCx<'mir, 'tcx, ConstPropMachine<'mir, 'tcx>>,
+}
 
-        if mir_opt_level == 0 {
-            return false;
+impl<'tcx> Visitor<'tcx> for ErroneousConstants<'_, '_, 'tcx> {
+    fn visit_constant(&mut self, c: &Constant<'tcx>, location: Location) {
+        trace!("visit_constant: {:?}", c);
+        // FIXME we need to revisit this for #67176
+        if c.needs_subst() {
+            return;
         }
 
-        match *op {
-            interpret::Operand::Immediate(Immediate::Scalar(ScalarMaybeUninit::Scalar(s))) => {
-                s.is_bits()
+        if let Err(error) = self.ecx.const_to_op(c.literal, None) {
+            let tcx = self.tcx.at(c.span);
+            let err = ConstEvalErr::new(self.ecx, error, Some(c.span));
+            let source_info = self.body.source_info(location);
+            let lint_root = match &self.body.source_scopes[source_info.scope].local_data {
+                ClearCrossCrate::Set(data) => Some(data.lint_root),
+                ClearCrossCrate::Clear => None,
+            };
+            if let Some(lint_root) = lint_root {
+                let lint_only = match c.literal.val {
+                    // Promoteds must lint and not error as the user didn't ask for them
+                    ConstKind::Unevaluated(_, _, Some(_)) => true,
+                    // Out of backwards compatibility we cannot report hard errors in unused
+                    // generic functions using associated constants of the generic parameters.
+                    _ => c.literal.needs_subst(),
+                };
+                if lint_only {
+                    // Out of backwards compatibility we cannot report hard errors in unused
+                    // generic functions using associated constants of the generic parameters.
+                    err.report_as_lint(tcx, "erroneous constant used", lint_root, Some(c.span));
+                } else {
+                    err.report_as_error(tcx, "erroneous constant used");
+                }
+            } else {
+                err.report_as_error(tcx, "erroneous constant used");
             }
-            interpret::Operand::Immediate(Immediate::ScalarPair(
-                ScalarMaybeUninit::Scalar(l),
-                ScalarMaybeUninit::Scalar(r),
-            )) => l.is_bits() && r.is_bits(),
-            _ => false,
         }
     }
 }
 
-/// The mode that `ConstProp` is allowed to run in for a given `Local`.
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum ConstPropMode {
-    /// The `Local` can be propagated into and reads of this `Local` can also be propagated.
-    FullConstProp,
-    /// The `Local` can only be propagated into and from its own block.
-    OnlyInsideOwnBlock,
-    /// The `Local` can be propagated into but reads cannot be propagated.
-    OnlyPropagateInto,
-    /// The `Local` cannot be part of propagation at all. Any statement
-    /// referencing it either for reading or writing will not get propagated.
-    NoPropagation,
-}
-
-struct CanConstProp {
-    can_const_prop: IndexVec<Local, ConstPropMode>,
-    // False at the beginning. Once set, no more assignments are allowed to that local.
-    found_assignment: BitSet<Local>,
-    // Cache of locals' information
-    local_kinds: IndexVec<Local, LocalKind>,
+/// Replaces the known values of a `Patch` with constants.
+struct ConstPropagator<'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    ecx: InterpCx<'mir, 'tcx, ConstPropMachine<'mir, 'tcx>>,
+    patch: Patch<'tcx>,
+    /// Whether to replace all the operands whose value is known, rather than
+    /// only the conditions of `Assert` and `SwitchInt` terminators.
+    propagate_operands: bool,
+    // Because we have `MutVisitor` we can't obtain the `SourceInfo` from a `Location`. So we store
+    // the last known `SourceInfo` here and just keep revisiting it.
+    source_info: Option<SourceInfo>,
 }
 
-impl CanConstProp {
-    /// Returns true if `local` can be propagated
-    fn check(
+impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
+    fn new(
         tcx: TyCtxt<'tcx>,
         param_env: ParamEnv<'tcx>,
         body: &Body<'tcx>,
-    ) -> IndexVec<Local, ConstPropMode> {
-        let mut cpv = CanConstProp {
-            can_const_prop: IndexVec::from_elem(ConstPropMode::FullConstProp, &body.local_decls),
-            found_assignment: BitSet::new_empty(body.local_decls.len()),
-            local_kinds: IndexVec::from_fn_n(
-                |local| body.local_kind(local),
-                body.local_decls.len(),
-            ),
-        };
-        for (local, val) in cpv.can_const_prop.iter_enumerated_mut() {
-            let ty = body.local_decls[local].ty;
-            match tcx.layout_of(param_env.and(ty)) {
-                Ok(layout) if layout.size < Size::from_bytes(MAX_ALLOC_LIMIT) => {}
-                // Either the layout fails to compute, then we can't use this local anyway
-                // or the local is too large, then we don't want to.
-                _ => {
-                    *val = ConstPropMode::NoPropagation;
-                    continue;
-                }
-            }
-            // Cannot use args at all
-            // Cannot use locals because if x < y { y - x } else { x - y } would
-            //        lint for x != y
-            // FIXME(oli-obk): lint variables until they are used in a condition
-            // FIXME(oli-obk): lint if return value is constant
-            if cpv.local_kinds[local] == LocalKind::Arg {
-                *val = ConstPropMode::OnlyPropagateInto;
-                trace!(
-                    "local {:?} can't be const propagated because it's a function argument",
-                    local
-                );
-            } else if cpv.local_kinds[local] == LocalKind::Var {
-                *val = ConstPropMode::OnlyInsideOwnBlock;
-                trace!(
-                    "local {:?} will only be propagated inside its block, because it's a user variable",
-                    local
-                );
-            }
+        patch: Patch<'tcx>,
+    ) -> ConstPropagator<'mir, 'tcx> {
+        ConstPropagator {
+            tcx,
+            ecx: new_ecx(tcx, param_env, body),
+            patch,
+            // Only const prop copies and moves on `mir_opt_level=2` as doing so
+            // currently slightly increases compile time in some cases.
+            propagate_operands: tcx.sess.opts.debugging_opts.mir_opt_level >= 2,
+            source_info: None,
         }
-        cpv.visit_body(&body);
-        cpv.can_const_prop
     }
-}
 
-impl<'tcx> Visitor<'tcx> for CanConstProp {
-    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
-        use rustc_middle::mir::visit::PlaceContext::*;
-        match context {
-            // Projections are fine, because `&mut foo.x` will be caught by
-            // `MutatingUseContext::Borrow` elsewhere.
-            MutatingUse(MutatingUseContext::Projection)
-            // These are just stores, where the storing is not propagatable, but there may be later
-            // mutations of the same local via `Store`
-            | MutatingUse(MutatingUseContext::Call)
-            // Actual store that can possibly even propagate a value
-            | MutatingUse(MutatingUseContext::Store) => {
-                if !self.found_assignment.insert(local) {
-                    match &mut self.can_const_prop[local] {
-                        // If the local can only get propagated in its own block, then we don't have
-                        // to worry about multiple assignments, as we'll nuke the const state at the
-                        // end of the block anyway, and inside the block we overwrite previous
-                        // states as applicable.
-                        ConstPropMode::OnlyInsideOwnBlock => {}
-                        ConstPropMode::NoPropagation => {}
-                        ConstPropMode::OnlyPropagateInto => {}
-                        other @ ConstPropMode::FullConstProp => {
-                            trace!(
-                                "local {:?} can't be propagated because of multiple assignments. Previous state: {:?}",
-                                local, other,
-                            );
-                            *other = ConstPropMode::OnlyInsideOwnBlock;
-                        }
-                    }
+    /// Creates a constant of `value`, if it is a scalar or a pair of scalars.
+    fn constant(&mut self, value: ImmTy<'tcx>, span: Span) -> Option<Operand<'tcx>> {
+        let ty = value.layout.ty;
+        let literal = match *value {
+            Immediate::Scalar(ScalarMaybeUninit::Scalar(scalar)) => {
+                ty::Const::from_scalar(self.tcx, scalar, ty)
+            }
+            Immediate::ScalarPair(ScalarMaybeUninit::Scalar(_), ScalarMaybeUninit::Scalar(_)) => {
+                // Found a value represented as a pair. For now only do const-prop if the type
+                // of `rvalue` is also a tuple with two scalars.
+                // FIXME: enable the general case stated above ^.
+                let substs = match ty.kind() {
+                    ty::Tuple(substs) if substs.len() == 2 => substs,
+                    _ => return None,
+                };
+                let ecx = &mut self.ecx;
+                let is_scalar =
+                    |ty| ecx.layout_of(ty).ok().map(|l| l.abi.is_scalar()) == Some(true);
+                if !is_scalar(substs[0].expect_ty()) || !is_scalar(substs[1].expect_ty()) {
+                    return None;
                 }
+                let alloc = ecx
+                    .intern_with_temp_alloc(value.layout, |ecx, dest| {
+                        ecx.write_immediate_to_mplace(*value, dest)
+                    })
+                    .ok()?;
+                // Assign entire constant in a single statement.
+                // We can't use aggregates, as we run after the aggregate-lowering `MirPhase`.
+                self.tcx.mk_const(ty::Const {
+                    ty,
+                    val: ty::ConstKind::Value(ConstValue::ByRef { alloc, offset: Size::ZERO }),
+                })
             }
-            // Reading constants is allowed an arbitrary number of times
-            NonMutatingUse(NonMutatingUseContext::Copy)
-            | NonMutatingUse(NonMutatingUseContext::Move)
-            | NonMutatingUse(NonMutatingUseContext::Inspect)
-            | NonMutatingUse(NonMutatingUseContext::Projection)
-            | NonUse(_) => {}
-
-            // These could be propagated with a smarter analysis or just some careful thinking about
-            // whether they'd be fine right now.
-            MutatingUse(MutatingUseContext::AsmOutput)
-            | MutatingUse(MutatingUseContext::Yield)
-            | MutatingUse(MutatingUseContext::Drop)
-            | MutatingUse(MutatingUseContext::Retag)
-            // These can't ever be propagated under any scheme, as we can't reason about indirect
-            // mutation.
-            | NonMutatingUse(NonMutatingUseContext::SharedBorrow)
-            | NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
-            | NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
-            | NonMutatingUse(NonMutatingUseContext::AddressOf)
-            | MutatingUse(MutatingUseContext::Borrow)
-            | MutatingUse(MutatingUseContext::AddressOf) => {
-                trace!("local {:?} can't be propagaged because it's used: {:?}", local, context);
-                self.can_const_prop[local] = ConstPropMode::NoPropagation;
+            // Scalars or scalar pairs that contain undef values are assumed to not have
+            // successfully evaluated and are thus not propagated.
+            _ => return None,
+        };
+        Some(Operand::Constant(box Constant { span, user_ty: None, literal }))
+    }
+
+    fn propagate_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Copy(place) | Operand::Move(place) = *operand {
+            if let Some(&value) = self.patch.operands.get(&(location, place)) {
+                let span = self.source_info.unwrap().span;
+                if let Some(constant) = self.constant(value, span) {
+                    *operand = constant;
+                }
             }
         }
     }
@@ -1039,240 +1312,44 @@ impl<'mir, 'tcx> MutVisitor<'tcx> for ConstPropagator<'mir, 'tcx> {
         self.tcx
     }
 
-    fn visit_body(&mut self, body: &mut Body<'tcx>) {
-        for (bb, data) in body.basic_blocks_mut().iter_enumerated_mut() {
-            self.visit_basic_block_data(bb, data);
-        }
-    }
-
-    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
-        self.super_operand(operand, location);
-
-        // Only const prop copies and moves on `mir_opt_level=2` as doing so
-        // currently slightly increases compile time in some cases.
-        if self.tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
-            self.propagate_operand(operand)
-        }
-    }
-
-    fn visit_constant(&mut self, constant: &mut Constant<'tcx>, location: Location) {
-        trace!("visit_constant: {:?}", constant);
-        self.super_constant(constant, location);
-        self.eval_constant(constant, self.source_info.unwrap());
-    }
-
     fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
-        trace!("visit_statement: {:?}", statement);
         let source_info = statement.source_info;
         self.source_info = Some(source_info);
-        if let StatementKind::Assign(box (place, ref mut rval)) = statement.kind {
-            let can_const_prop = self.ecx.machine.can_const_prop[place.local];
-            if let Some(()) = self.const_prop(rval, source_info, place) {
-                // This will return None if the above `const_prop` invocation only "wrote" a
-                // type whose creation requires no write. E.g. a generator whose initial state
-                // consists solely of uninitialized memory (so it doesn't capture any locals).
-                if let Some(value) = self.get_const(place) {
-                    if self.should_const_prop(value) {
-                        trace!("replacing {:?} with {:?}", rval, value);
-                        self.replace_with_const(rval, value, source_info);
-                        if can_const_prop == ConstPropMode::FullConstProp
-                            || can_const_prop == ConstPropMode::OnlyInsideOwnBlock
-                        {
-                            trace!("propagated into {:?}", place);
-                        }
-                    }
+        if let StatementKind::Assign(box (_, ref mut rvalue)) = statement.kind {
+            let is_constant = match rvalue {
+                Rvalue::Use(Operand::Constant(c)) => {
+                    !matches!(c.literal.val, ConstKind::Unevaluated(..))
                 }
-                match can_const_prop {
-                    ConstPropMode::OnlyInsideOwnBlock => {
-                        trace!(
-                            "found local restricted to its block. \
-                                Will remove it from const-prop after block is finished. Local: {:?}",
-                            place.local
-                        );
-                    }
-                    ConstPropMode::OnlyPropagateInto | ConstPropMode::NoPropagation => {
-                        trace!("can't propagate into {:?}", place);
-                        if place.local != RETURN_PLACE {
-                            Self::remove_const(&mut self.ecx, place.local);
-                        }
-                    }
-                    ConstPropMode::FullConstProp => {}
+                _ => false,
+            };
+            if let (Some(&value), false) = (self.patch.assignments.get(&location), is_constant) {
+                if let Some(constant) = self.constant(value, source_info.span) {
+                    trace!("replacing {:?} with {:?}", rvalue, constant);
+                    *rvalue = Rvalue::Use(constant);
+                    return;
                 }
-            } else {
-                // Const prop failed, so erase the destination, ensuring that whatever happens
-                // from here on, does not know about the previous value.
-                // This is important in case we have
-                // ```rust
-                // let mut x = 42;
-                // x = SOME_MUTABLE_STATIC;
-                // // x must now be uninit
-                // ```
-                // FIXME: we overzealously erase the entire local, because that's easier to
-                // implement.
-                trace!(
-                    "propagation into {:?} failed.
-                        Nuking the entire site from orbit, it's the only way to be sure",
-                    place,
-                );
-                Self::remove_const(&mut self.ecx, place.local);
-            }
-        } else {
-            match statement.kind {
-                StatementKind::SetDiscriminant { ref place, .. } => {
-                    match self.ecx.machine.can_const_prop[place.local] {
-                        ConstPropMode::FullConstProp | ConstPropMode::OnlyInsideOwnBlock => {
-                            if self.use_ecx(|this| this.ecx.statement(statement)).is_some() {
-                                trace!("propped discriminant into {:?}", place);
-                            } else {
-                                Self::remove_const(&mut self.ecx, place.local);
-                            }
-                        }
-                        ConstPropMode::OnlyPropagateInto | ConstPropMode::NoPropagation => {
-                            Self::remove_const(&mut self.ecx, place.local);
-                        }
-                    }
-                }
-                StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
-                    let frame = self.ecx.frame_mut();
-                    frame.locals[local].value =
-                        if let StatementKind::StorageLive(_) = statement.kind {
-                            LocalValue::Uninitialized
-                        } else {
-                            LocalValue::Dead
-                        };
-                }
-                _ => {}
             }
         }
-
         self.super_statement(statement, location);
     }
 
     fn visit_terminator(&mut self, terminator: &mut Terminator<'tcx>, location: Location) {
-        let source_info = terminator.source_info;
-        self.source_info = Some(source_info);
-        self.super_terminator(terminator, location);
-        match &mut terminator.kind {
-            TerminatorKind::Assert { expected, ref msg, ref mut cond, .. } => {
-                if let Some(value) = self.eval_operand(&cond, source_info) {
-                    trace!("assertion on {:?} should be {:?}", value, expected);
-                    let expected = ScalarMaybeUninit::from(Scalar::from_bool(*expected));
-                    let value_const = self.ecx.read_scalar(value).unwrap();
-                    if expected != value_const {
-                        enum DbgVal<T> {
-                            Val(T),
-                            Underscore,
-                        }
-                        impl<T: std::fmt::Debug> std::fmt::Debug for DbgVal<T> {
-                            fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                                match self {
-                                    Self::Val(val) => val.fmt(fmt),
-                                    Self::Underscore => fmt.write_str("_"),
-                                }
-                            }
-                        }
-                        let mut eval_to_int = |op| {
-                            // This can be `None` if the lhs wasn't const propagated and we just
-                            // triggered the assert on the value of the rhs.
-                            match self.eval_operand(op, source_info) {
-                                Some(op) => {
-                                    DbgVal::Val(self.ecx.read_immediate(op).unwrap().to_const_int())
-                                }
-                                None => DbgVal::Underscore,
-                            }
-                        };
-                        let msg = match msg {
-                            AssertKind::DivisionByZero(op) => {
-                                Some(AssertKind::DivisionByZero(eval_to_int(op)))
-                            }
-                            AssertKind::RemainderByZero(op) => {
-                                Some(AssertKind::RemainderByZero(eval_to_int(op)))
-                            }
-                            AssertKind::BoundsCheck { ref len, ref index } => {
-                                let len = eval_to_int(len);
-                                let index = eval_to_int(index);
-                                Some(AssertKind::BoundsCheck { len, index })
-                            }
-                            // Overflow is are already covered by checks on the binary operators.
-                            AssertKind::Overflow(..) | AssertKind::OverflowNeg(_) => None,
-                            // Need proper const propagator for these.
-                            _ => None,
-                        };
-                        // Poison all places this operand references so that further code
-                        // doesn't use the invalid value
-                        match cond {
-                            Operand::Move(ref place) | Operand::Copy(ref place) => {
-                                Self::remove_const(&mut self.ecx, place.local);
-                            }
-                            Operand::Constant(_) => {}
-                        }
-                        if let Some(msg) = msg {
-                            self.report_assert_as_lint(
-                                lint::builtin::UNCONDITIONAL_PANIC,
-                                source_info,
-                                "this operation will panic at runtime",
-                                msg,
-                            );
-                        }
-                    } else {
-                        if self.should_const_prop(value) {
-                            if let ScalarMaybeUninit::Scalar(scalar) = value_const {
-                                *cond = self.operand_from_scalar(
-                                    scalar,
-                                    self.tcx.types.bool,
-                                    source_info.span,
-                                );
-                            }
-                        }
-                    }
-                }
+        self.source_info = Some(terminator.source_info);
+        match terminator.kind {
+            // A known discriminant lets `SimplifyBranches` remove the edges that
+            // are not taken.
+            TerminatorKind::Assert { ref mut cond, .. }
+            | TerminatorKind::SwitchInt { discr: ref mut cond, .. } => {
+                self.propagate_operand(cond, location);
             }
-            TerminatorKind::SwitchInt { ref mut discr, .. } => {
-                // FIXME: This is currently redundant with `visit_operand`, but sadly
-                // always visiting operands currently causes a perf regression in LLVM codegen, so
-                // `visit_operand` currently only runs for propagates places for `mir_opt_level=3`.
-                self.propagate_operand(discr)
-            }
-            // None of these have Operands to const-propagate.
-            TerminatorKind::Goto { .. }
-            | TerminatorKind::Resume
-            | TerminatorKind::Abort
-            | TerminatorKind::Return
-            | TerminatorKind::Unreachable
-            | TerminatorKind::Drop { .. }
-            | TerminatorKind::DropAndReplace { .. }
-            | TerminatorKind::Yield { .. }
-            | TerminatorKind::GeneratorDrop
-            | TerminatorKind::FalseEdge { .. }
-            | TerminatorKind::FalseUnwind { .. }
-            | TerminatorKind::InlineAsm { .. } => {}
-            // Every argument in our function calls have already been propagated in `visit_operand`.
-            //
-            // NOTE: because LLVM codegen gives slight performance regressions with it, so this is
-            // gated on `mir_opt_level=2`.
-            TerminatorKind::Call { .. } => {}
+            _ => {}
         }
+        self.super_terminator(terminator, location);
+    }
 
-        // We remove all Locals which are restricted in propagation to their containing blocks and
-        // which were modified in the current block.
-        // Take it out of the ecx so we can get a mutable reference to the ecx for `remove_const`.
-        let mut locals = std::mem::take(&mut self.ecx.machine.written_only_inside_own_block_locals);
-        for &local in locals.iter() {
-            Self::remove_const(&mut self.ecx, local);
-        }
-        locals.clear();
-        // Put it back so we reuse the heap of the storage
-        self.ecx.machine.written_only_inside_own_block_locals = locals;
-        if cfg!(debug_assertions) {
-            // Ensure we are correctly erasing locals with the non-debug-assert logic.
-            for local in self.ecx.machine.only_propagate_inside_block_locals.iter() {
-                assert!(
-                    self.get_const(local.into()).is_none()
-                        || self
-                            .layout_of(self.local_decls[local].ty)
-                            .map_or(true, |layout| layout.is_zst())
-                )
-            }
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if self.propagate_operands {
+            self.propagate_operand(operand, location);
         }
     }
 }
@@ -0,0 +1,361 @@
+//! Global value numbering, which removes redundant computations.
+//!
+//! The pass numbers the values of the locals that are assigned at most once
+//! and whose address is never taken (the "SSA locals"), so that two rvalues
+//! with the same number compute the same value. When an rvalue computes a
+//! value already held by an SSA local whose assignment dominates it, the
+//! rvalue is replaced with a copy of that local:
+//!
+//! ```text
+//! _4 = Len((*_1));            _4 = Len((*_1));
+//! _5 = Lt(_2, _4);            _5 = Lt(_2, _4);
+//! ...                   =>    ...
+//! _8 = Len((*_1));            _8 = _4;
+//! _9 = Lt(_2, _8);            _9 = _5;
+//! ```
+//!
+//! The values are pure rvalues of SSA locals and constants: arithmetic,
+//! casts, comparisons, field projections, discriminants and the length of
+//! slices, which is part of the pointer to them. Reading memory through a
+//! pointer is not numbered, since the memory can change.
+//!
+//! Likewise, an `Assert` is removed when its condition was already asserted
+//! by an `Assert` that dominates it, which removes repeated bounds and
+//! overflow checks.
+//!
+//! A local that is copied by a replacement must be live wherever it is
+//! copied, so its `StorageLive` and `StorageDead` statements are removed, and
+//! its moves are turned into copies.
+
+use crate::transform::MirPass;
+use crate::util::ssa::SsaLocals;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::MutVisitor;
+use rustc_middle::mir::{
+    traversal, BasicBlock, BinOp, Body, CastKind, Field, Local, Location, NullOp, Operand, Place,
+    PlaceElem, ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind, UnOp,
+};
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+
+pub struct GVN;
+
+impl<'tcx> MirPass<'tcx> for GVN {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 2 {
+            return;
+        }
+
+        let def_id = body.source.def_id();
+        trace!("running GVN on {:?}", def_id);
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        let ssa = SsaLocals::new(body);
+        let replacements = ValueNumbering::new(tcx, param_env, body, &ssa).run();
+        if replacements.rvalues.is_empty() && replacements.asserts.is_empty() {
+            return;
+        }
+
+        Replacer { tcx, replacements }.visit_body(body);
+    }
+}
+
+rustc_index::newtype_index! {
+    struct VnIndex {
+        DEBUG_FORMAT = "vn{}"
+    }
+}
+
+/// A value computed by a body. Two rvalues that compute the same `Value`
+/// evaluate to the same result.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Value<'tcx> {
+    /// The value of an SSA local that is not computed by a pure rvalue, e.g.
+    /// an argument or the result of a call. It is only equal to itself.
+    Opaque(Local),
+    Constant(&'tcx ty::Const<'tcx>),
+    Field(VnIndex, Field),
+    /// The length of the slice that the pointer with the value points to.
+    Len(VnIndex),
+    Discriminant(VnIndex),
+    SizeOf(Ty<'tcx>),
+    UnaryOp(UnOp, VnIndex),
+    BinaryOp(BinOp, VnIndex, VnIndex),
+    CheckedBinaryOp(BinOp, VnIndex, VnIndex),
+    Cast(VnIndex, Ty<'tcx>),
+}
+
+/// The changes to a body found by `ValueNumbering`.
+struct Replacements {
+    /// The rvalues to replace with a copy of a local.
+    rvalues: FxHashMap<Location, Local>,
+    /// The blocks whose `Assert` terminator always succeeds.
+    asserts: FxHashSet<BasicBlock>,
+    /// The locals copied by the replacements.
+    reused: BitSet<Local>,
+}
+
+struct ValueNumbering<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    ssa: &'a SsaLocals,
+    dominators: Dominators<BasicBlock>,
+    values: FxHashMap<Value<'tcx>, VnIndex>,
+    /// The values of the SSA locals that have been assigned so far.
+    locals: IndexVec<Local, Option<VnIndex>>,
+    /// The SSA locals that hold each value, with the location of their
+    /// assignment.
+    holders: FxHashMap<VnIndex, Vec<(Local, Location)>>,
+    /// The blocks that are only reached once an `Assert` checked that a value
+    /// is `true` or `false`.
+    asserted: FxHashMap<(VnIndex, bool), Vec<BasicBlock>>,
+    replacements: Replacements,
+}
+
+impl<'a, 'tcx> ValueNumbering<'a, 'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        body: &'a Body<'tcx>,
+        ssa: &'a SsaLocals,
+    ) -> Self {
+        ValueNumbering {
+            tcx,
+            param_env,
+            body,
+            ssa,
+            dominators: body.dominators(),
+            values: FxHashMap::default(),
+            locals: IndexVec::from_elem(None, &body.local_decls),
+            holders: FxHashMap::default(),
+            asserted: FxHashMap::default(),
+            replacements: Replacements {
+                rvalues: FxHashMap::default(),
+                asserts: FxHashSet::default(),
+                reused: BitSet::new_empty(body.local_decls.len()),
+            },
+        }
+    }
+
+    fn run(mut self) -> Replacements {
+        let body = self.body;
+        for arg in body.args_iter() {
+            if self.ssa.is_ssa(arg) {
+                let value = self.insert(Value::Opaque(arg));
+                self.define(arg, value, Location::START);
+            }
+        }
+
+        // The assignment of an SSA local dominates its uses, so it is visited
+        // before them.
+        for (block, data) in traversal::reverse_postorder(body) {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                if let StatementKind::Assign(box (place, ref rvalue)) = statement.kind {
+                    self.visit_assign(place, rvalue, Location { block, statement_index });
+                }
+            }
+            self.visit_terminator(block, data.terminator());
+        }
+        self.replacements
+    }
+
+    fn insert(&mut self, value: Value<'tcx>) -> VnIndex {
+        let next = VnIndex::new(self.values.len());
+        *self.values.entry(value).or_insert(next)
+    }
+
+    fn define(&mut self, local: Local, value: VnIndex, location: Location) {
+        self.locals[local] = Some(value);
+        self.holders.entry(value).or_default().push((local, location));
+    }
+
+    fn eval_projection(&mut self, local: Local, projection: &[PlaceElem<'tcx>]) -> Option<VnIndex> {
+        let mut value = self.locals[local]?;
+        for elem in projection {
+            match *elem {
+                ProjectionElem::Field(field, _) => value = self.insert(Value::Field(value, field)),
+                _ => return None,
+            }
+        }
+        Some(value)
+    }
+
+    fn eval_operand(&mut self, operand: &Operand<'tcx>) -> Option<VnIndex> {
+        match *operand {
+            Operand::Copy(place) | Operand::Move(place) => {
+                self.eval_projection(place.local, place.projection)
+            }
+            Operand::Constant(ref constant) => Some(self.insert(Value::Constant(constant.literal))),
+        }
+    }
+
+    fn eval_rvalue(&mut self, rvalue: &Rvalue<'tcx>) -> Option<VnIndex> {
+        let value = match *rvalue {
+            Rvalue::Use(ref operand) => return self.eval_operand(operand),
+            // The length of an array is a constant, see `InstCombine`.
+            Rvalue::Len(place) => match place.projection.split_last() {
+                Some((ProjectionElem::Deref, base)) => {
+                    Value::Len(self.eval_projection(place.local, base)?)
+                }
+                _ => return None,
+            },
+            Rvalue::Discriminant(place) => {
+                Value::Discriminant(self.eval_projection(place.local, place.projection)?)
+            }
+            Rvalue::NullaryOp(NullOp::SizeOf, ty) => Value::SizeOf(ty),
+            Rvalue::UnaryOp(op, ref arg) => Value::UnaryOp(op, self.eval_operand(arg)?),
+            Rvalue::BinaryOp(op, ref left, ref right) => {
+                let left = self.eval_operand(left)?;
+                let right = self.eval_operand(right)?;
+                let (left, right) = match op {
+                    BinOp::Add
+                    | BinOp::Mul
+                    | BinOp::BitAnd
+                    | BinOp::BitOr
+                    | BinOp::BitXor
+                    | BinOp::Eq
+                    | BinOp::Ne
+                        if right < left =>
+                    {
+                        (right, left)
+                    }
+                    _ => (left, right),
+                };
+                Value::BinaryOp(op, left, right)
+            }
+            Rvalue::CheckedBinaryOp(op, ref left, ref right) => {
+                Value::CheckedBinaryOp(op, self.eval_operand(left)?, self.eval_operand(right)?)
+            }
+            Rvalue::Cast(CastKind::Misc, ref operand, ty) => {
+                Value::Cast(self.eval_operand(operand)?, ty)
+            }
+            _ => return None,
+        };
+        Some(self.insert(value))
+    }
+
+    /// Returns an SSA local holding `value` that can be copied at `location`.
+    fn find_holder(&self, value: VnIndex, ty: Ty<'tcx>, location: Location) -> Option<Local> {
+        let span = self.body.source_info(location).span;
+        self.holders.get(&value)?.iter().find_map(|&(local, assigned)| {
+            let usable = assigned != location
+                && assigned.dominates(location, &self.dominators)
+                && self.body.local_decls[local].ty == ty
+                && ty.is_copy_modulo_regions(self.tcx.at(span), self.param_env);
+            usable.then_some(local)
+        })
+    }
+
+    fn visit_assign(&mut self, place: Place<'tcx>, rvalue: &Rvalue<'tcx>, location: Location) {
+        let value = self.eval_rvalue(rvalue);
+
+        // Copying a local or a constant is already as cheap as it gets.
+        let computes = match *rvalue {
+            Rvalue::Use(Operand::Copy(source) | Operand::Move(source)) => {
+                !source.projection.is_empty()
+            }
+            Rvalue::Use(Operand::Constant(_)) => false,
+            _ => true,
+        };
+        if let (Some(value), true) = (value, computes) {
+            let ty = rvalue.ty(self.body, self.tcx);
+            if let Some(holder) = self.find_holder(value, ty, location) {
+                debug!("replacing {:?} at {:?} with {:?}", rvalue, location, holder);
+                self.replacements.rvalues.insert(location, holder);
+                self.replacements.reused.insert(holder);
+            }
+        }
+
+        if let Some(local) = place.as_local() {
+            if self.ssa.is_ssa(local) {
+                let value = match value {
+                    Some(value) => value,
+                    None => self.insert(Value::Opaque(local)),
+                };
+                self.define(local, value, location);
+            }
+        }
+    }
+
+    fn visit_terminator(&mut self, block: BasicBlock, terminator: &Terminator<'tcx>) {
+        match terminator.kind {
+            TerminatorKind::Call { destination: Some((place, _)), .. } => {
+                if let Some(local) = place.as_local() {
+                    if self.ssa.is_ssa(local) {
+                        let value = self.insert(Value::Opaque(local));
+                        self.define(local, value, self.body.terminator_loc(block));
+                    }
+                }
+            }
+            TerminatorKind::Assert { ref cond, expected, target, .. } => {
+                let cond = match self.eval_operand(cond) {
+                    Some(cond) => cond,
+                    None => return,
+                };
+                let dominators = &self.dominators;
+                let checked = self.asserted.get(&(cond, expected)).map_or(false, |blocks| {
+                    blocks.iter().any(|&checked| dominators.is_dominated_by(block, checked))
+                });
+                if checked {
+                    debug!("removing the assert of {:?}, which is already checked", block);
+                    self.replacements.asserts.insert(block);
+                }
+                // The condition holds in the blocks dominated by `target` if
+                // `target` is only reached from the assert.
+                if self.body.predecessors()[target].len() == 1 {
+                    self.asserted.entry((cond, expected)).or_default().push(target);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct Replacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    replacements: Replacements,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::Assign(box (_, ref mut rvalue)) => {
+                if let Some(&holder) = self.replacements.rvalues.get(&location) {
+                    *rvalue = Rvalue::Use(Operand::Copy(Place::from(holder)));
+                }
+            }
+            // The reused locals are live wherever they are copied.
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if self.replacements.reused.contains(local) =>
+            {
+                statement.make_nop();
+            }
+            _ => {}
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mut Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Assert { target, .. } = terminator.kind {
+            if self.replacements.asserts.contains(&location.block) {
+                terminator.kind = TerminatorKind::Goto { target };
+            }
+        }
+        self.super_terminator(terminator, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, _: Location) {
+        // A reused local may be copied after it is moved.
+        if let Operand::Move(place) = *operand {
+            if !place.is_indirect() && self.replacements.reused.contains(place.local) {
+                *operand = Operand::Copy(place);
+            }
+        }
+    }
+}
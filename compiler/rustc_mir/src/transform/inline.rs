@@ -8,6 +8,7 @@ use rustc_middle::mir::visit::*;
 use rustc_middle::mir::*;
 use rustc_middle::ty::subst::Subst;
 use rustc_middle::ty::{self, ConstKind, Instance, InstanceDef, ParamEnv, Ty, TyCtxt};
+use rustc_session::config::OptLevel;
 use rustc_span::{hygiene::ExpnKind, ExpnData, Span};
 use rustc_target::spec::abi::Abi;
 
@@ -17,6 +18,8 @@ use std::collections::VecDeque;
 use std::iter;
 use std::ops::RangeFrom;
 
+crate mod cycle;
+
 const DEFAULT_THRESHOLD: usize = 50;
 const HINT_THRESHOLD: usize = 100;
 
@@ -36,22 +39,42 @@ struct CallSite<'tcx> {
     source_info: SourceInfo,
 }
 
+/// Returns whether the MIR inliner runs: with `-Z inline-mir` if it is given,
+/// and otherwise in optimized non-incremental builds, or at
+/// `-Z mir-opt-level=2` and above.
+crate fn is_enabled(tcx: TyCtxt<'_>) -> bool {
+    if let Some(enabled) = tcx.sess.opts.debugging_opts.inline_mir {
+        return enabled;
+    }
+
+    match tcx.sess.opts.debugging_opts.mir_opt_level {
+        0 => false,
+        1 => {
+            matches!(tcx.sess.opts.optimize, OptLevel::Default | OptLevel::Aggressive)
+                && tcx.sess.opts.incremental.is_none()
+        }
+        _ => true,
+    }
+}
+
 impl<'tcx> MirPass<'tcx> for Inline {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
-            if tcx.sess.opts.debugging_opts.instrument_coverage {
-                // The current implementation of source code coverage injects code region counters
-                // into the MIR, and assumes a 1-to-1 correspondence between MIR and source-code-
-                // based function.
-                debug!("function inlining is disabled when compiling with `instrument_coverage`");
-            } else {
-                Inliner {
-                    tcx,
-                    param_env: tcx.param_env_reveal_all_normalized(body.source.def_id()),
-                    codegen_fn_attrs: tcx.codegen_fn_attrs(body.source.def_id()),
-                }
-                .run_pass(body);
+        if !is_enabled(tcx) {
+            return;
+        }
+
+        if tcx.sess.opts.debugging_opts.instrument_coverage {
+            // The current implementation of source code coverage injects code region counters
+            // into the MIR, and assumes a 1-to-1 correspondence between MIR and source-code-
+            // based function.
+            debug!("function inlining is disabled when compiling with `instrument_coverage`");
+        } else {
+            Inliner {
+                tcx,
+                param_env: tcx.param_env_reveal_all_normalized(body.source.def_id()),
+                codegen_fn_attrs: tcx.codegen_fn_attrs(body.source.def_id()),
             }
+            .run_pass(body);
         }
     }
 }
@@ -64,12 +87,10 @@ struct Inliner<'tcx> {
 
 impl Inliner<'tcx> {
     fn run_pass(&self, caller_body: &mut Body<'tcx>) {
-        // Keep a queue of callsites to try inlining on. We take
-        // advantage of the fact that queries detect cycles here to
-        // allow us to try and fetch the fully optimized MIR of a
-        // call; if it succeeds, we can inline it and we know that
-        // they do not call us.  Otherwise, we just don't try to
-        // inline.
+        // Keep a queue of callsites to try inlining on. Fetching the
+        // fully optimized MIR of a local callee would be a query cycle
+        // if the callee (transitively) calls us, which is checked with
+        // `mir_callgraph_reachable` before inlining it.
         //
         // We use a queue so that we inline "broadly" before we inline
         // in depth. It is unclear if this is the best heuristic,
@@ -78,6 +99,11 @@ impl Inliner<'tcx> {
 
         let mut callsites = VecDeque::new();
 
+        // The functions that the callsites in the queue were inlined from,
+        // as a list of `(callee, index of the callee it was inlined from)`,
+        // so that a recursive function is not inlined into itself forever.
+        let mut history: Vec<(Instance<'tcx>, Option<usize>)> = Vec::new();
+
         let def_id = caller_body.source.def_id();
 
         // Only do inlining into fn bodies.
@@ -87,7 +113,7 @@ impl Inliner<'tcx> {
         {
             for (bb, bb_data) in caller_body.basic_blocks().iter_enumerated() {
                 if let Some(callsite) = self.get_valid_function_call(bb, bb_data, caller_body) {
-                    callsites.push_back(callsite);
+                    callsites.push_back((callsite, None));
                 }
             }
         } else {
@@ -95,7 +121,7 @@ impl Inliner<'tcx> {
         }
 
         let mut changed = false;
-        while let Some(callsite) = callsites.pop_front() {
+        while let Some((callsite, inlined_from)) = callsites.pop_front() {
             debug!("checking whether to inline callsite {:?}", callsite);
 
             if let InstanceDef::Item(_) = callsite.callee.def {
@@ -105,30 +131,33 @@ impl Inliner<'tcx> {
                 }
             }
 
-            let callee_body = if let Some(callee_def_id) = callsite.callee.def_id().as_local() {
-                let callee_hir_id = self.tcx.hir().local_def_id_to_hir_id(callee_def_id);
-                // Avoid a cycle here by only using `instance_mir` only if we have
-                // a lower `HirId` than the callee. This ensures that the callee will
-                // not inline us. This trick only works without incremental compilation.
-                // So don't do it if that is enabled. Also avoid inlining into generators,
-                // since their `optimized_mir` is used for layout computation, which can
-                // create a cycle, even when no attempt is made to inline the function
-                // in the other direction.
-                if !self.tcx.dep_graph.is_fully_enabled()
-                    && self_hir_id < callee_hir_id
-                    && caller_body.generator_kind.is_none()
-                {
-                    self.tcx.instance_mir(callsite.callee.def)
-                } else {
+            if let Some(callee_def_id) = callsite.callee.def_id().as_local() {
+                // Avoid inlining into generators, since their `optimized_mir` is used
+                // for layout computation, which can create a cycle, even when no attempt
+                // is made to inline the function in the other direction.
+                if caller_body.generator_kind.is_some() {
+                    debug!("checking whether to inline callsite {:?} - generator", callsite);
                     continue;
                 }
-            } else {
-                // This cannot result in a cycle since the callee MIR is from another crate
-                // and is already optimized.
-                self.tcx.instance_mir(callsite.callee.def)
-            };
 
-            let callee_body: &Body<'tcx> = &*callee_body;
+                // The optimized MIR of shims and constructors is built without
+                // inlining into it, so only items can call us back.
+                if let InstanceDef::Item(_) = callsite.callee.def {
+                    if !self.tcx.is_constructor(callee_def_id.to_def_id()) {
+                        let caller_def_id = def_id.expect_local();
+                        if callee_def_id == caller_def_id
+                            || self.tcx.mir_callgraph_reachable((callsite.callee, caller_def_id))
+                        {
+                            debug!("checking whether to inline callsite {:?} - cycle", callsite);
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            // The MIR of callees from other crates comes from their metadata and
+            // is already optimized, so it cannot result in a cycle.
+            let callee_body = self.tcx.instance_mir(callsite.callee.def);
 
             let callee_body = if self.consider_optimizing(callsite, callee_body) {
                 self.tcx.subst_and_normalize_erasing_regions(
@@ -149,11 +178,14 @@ impl Inliner<'tcx> {
             debug!("attempting to inline callsite {:?} - success", callsite);
 
             // Add callsites from inlined function
+            history.push((callsite.callee, inlined_from));
+            let inlined_from = Some(history.len() - 1);
             for (bb, bb_data) in caller_body.basic_blocks().iter_enumerated().skip(start) {
                 if let Some(new_callsite) = self.get_valid_function_call(bb, bb_data, caller_body) {
-                    // Don't inline the same function multiple times.
-                    if callsite.callee != new_callsite.callee {
-                        callsites.push_back(new_callsite);
+                    // Don't inline a function into code that was inlined from it.
+                    let mut ancestors = iter::successors(inlined_from, |&i| history[i].1);
+                    if !ancestors.any(|i| history[i].0 == new_callsite.callee) {
+                        callsites.push_back((new_callsite, inlined_from));
                     }
                 }
             }
@@ -262,7 +294,12 @@ impl Inliner<'tcx> {
             }
         }
 
-        let mut threshold = if hinted { HINT_THRESHOLD } else { DEFAULT_THRESHOLD };
+        let opts = &tcx.sess.opts.debugging_opts;
+        let mut threshold = if hinted {
+            opts.inline_mir_hint_threshold.unwrap_or(HINT_THRESHOLD)
+        } else {
+            opts.inline_mir_threshold.unwrap_or(DEFAULT_THRESHOLD)
+        };
 
         // Significantly lower the threshold for inlining cold functions
         if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
@@ -0,0 +1,153 @@
+//! Cycle detection for the MIR inliner.
+//!
+//! Inlining a local function requires its `optimized_mir`, which itself runs
+//! the inliner. If the callee (transitively) calls the caller, that would
+//! be a query cycle, so before inlining a local function the inliner asks
+//! `mir_callgraph_reachable` whether the caller is reachable from it in the
+//! call graph. The call graph is built from the calls of each function
+//! before optimizations, as returned by `mir_inliner_callees`.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::mir::TerminatorKind;
+use rustc_middle::ty::TypeFoldable;
+use rustc_middle::ty::{self, subst::SubstsRef, InstanceDef, TyCtxt};
+
+/// Returns whether `target` is reachable from `root` in the call graph,
+/// i.e. whether optimizing `root` could require the optimized MIR of
+/// `target`. This is conservative: `true` is returned when the call graph is
+/// too deep to be explored.
+crate fn mir_callgraph_reachable(
+    tcx: TyCtxt<'tcx>,
+    (root, target): (ty::Instance<'tcx>, LocalDefId),
+) -> bool {
+    trace!("mir_callgraph_reachable({:?}, {:?})", root, target);
+    let param_env = tcx.param_env_reveal_all_normalized(target);
+    assert_ne!(
+        root.def_id().expect_local(),
+        target,
+        "you should not call `mir_callgraph_reachable` on immediate self recursion"
+    );
+    assert!(
+        matches!(root.def, InstanceDef::Item(_)),
+        "you should not call `mir_callgraph_reachable` on shims"
+    );
+    assert!(
+        !tcx.is_constructor(root.def_id()),
+        "you should not call `mir_callgraph_reachable` on enum/struct constructor functions"
+    );
+
+    fn process(
+        tcx: TyCtxt<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        caller: ty::Instance<'tcx>,
+        target: LocalDefId,
+        seen: &mut FxHashSet<ty::Instance<'tcx>>,
+        recursion_limiter: &mut FxHashMap<DefId, usize>,
+    ) -> bool {
+        trace!("process({:?})", caller);
+        for &(callee, substs) in tcx.mir_inliner_callees(caller.def) {
+            let substs = tcx.subst_and_normalize_erasing_regions(caller.substs, param_env, &substs);
+            let callee = match ty::Instance::resolve(tcx, param_env, callee, substs) {
+                Ok(Some(callee)) => callee,
+                _ => {
+                    trace!("cannot resolve {:?}, skipping", callee);
+                    continue;
+                }
+            };
+
+            // Found a path.
+            if callee.def_id() == target.to_def_id() {
+                return true;
+            }
+
+            // Constructors always have MIR, built without calling anything.
+            if tcx.is_constructor(callee.def_id()) {
+                continue;
+            }
+
+            match callee.def {
+                InstanceDef::Item(_) => {
+                    // Functions without MIR, e.g. because they were not encoded
+                    // in the metadata or are `extern` functions, are never
+                    // inlined, and so cannot cause a cycle.
+                    if !tcx.is_mir_available(callee.def_id()) {
+                        continue;
+                    }
+                }
+                // These have no MIR of their own.
+                InstanceDef::Intrinsic(_) | InstanceDef::Virtual(..) => continue,
+                // These have MIR, and once it is inlined and substituted, the
+                // functions that it calls can be inlined in turn.
+                InstanceDef::VtableShim(_)
+                | InstanceDef::ReifyShim(_)
+                | InstanceDef::FnPtrShim(..)
+                | InstanceDef::ClosureOnceShim { .. }
+                | InstanceDef::CloneShim(..) => {}
+                InstanceDef::DropGlue(..) => {
+                    // The MIR of drop glue that is not fully substituted cannot
+                    // be built.
+                    if callee.needs_subst() {
+                        continue;
+                    }
+                }
+            }
+
+            if seen.insert(callee) {
+                let recursion = recursion_limiter.entry(callee.def_id()).or_default();
+                if tcx.sess.recursion_limit().value_within_limit(*recursion) {
+                    *recursion += 1;
+                    let found_recursion = ensure_sufficient_stack(|| {
+                        process(tcx, param_env, callee, target, seen, recursion_limiter)
+                    });
+                    if found_recursion {
+                        return true;
+                    }
+                } else {
+                    // Pessimistically assume that there could be recursion.
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    process(tcx, param_env, root, target, &mut FxHashSet::default(), &mut FxHashMap::default())
+}
+
+/// Returns the functions called by `instance`, with their substitutions, as
+/// they appear in its MIR before optimizations.
+crate fn mir_inliner_callees<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: InstanceDef<'tcx>,
+) -> &'tcx [(DefId, SubstsRef<'tcx>)] {
+    let steal;
+    let guard;
+    let body = match (instance, instance.def_id().as_local()) {
+        (InstanceDef::Item(_), Some(def_id)) => {
+            // The optimized MIR may not be built yet, and building it here
+            // could be a cycle. `mir_drops_elaborated_and_const_checked`
+            // calls this query before it steals the promoted MIR.
+            steal = tcx.mir_promoted(ty::WithOptConstParam::unknown(def_id)).0;
+            guard = steal.borrow();
+            &*guard
+        }
+        // Functions from other crates, and MIR shims.
+        _ => tcx.instance_mir(instance),
+    };
+    let mut calls = Vec::new();
+    for bb_data in body.basic_blocks() {
+        let terminator = bb_data.terminator();
+        if let TerminatorKind::Call { func, .. } = &terminator.kind {
+            let call = match *func.ty(&body.local_decls, tcx).kind() {
+                ty::FnDef(def_id, substs) => (def_id, substs),
+                _ => continue,
+            };
+            if !calls.contains(&call) {
+                calls.push(call);
+            }
+        }
+    }
+    tcx.arena.alloc_slice(&calls)
+}
@@ -0,0 +1,241 @@
+//! Loop-invariant code motion: moves the computations of a loop that produce
+//! the same value in every iteration to the preheader of the loop, the block
+//! through which the loop is entered.
+//!
+//! ```text
+//! bb1: {                              bb0: {
+//!     _5 = Len((*_1));                    _5 = Len((*_1));
+//!     _6 = Lt(_4, _5);          =>        goto -> bb1;
+//!     ...                             }
+//! }                                   bb1: {
+//!                                         _6 = Lt(_4, _5);
+//!                                         ...
+//! ```
+//!
+//! An assignment is moved when its local is an SSA local and its rvalue only
+//! reads constants and SSA locals assigned outside the loop or by assignments
+//! that are moved as well. The rvalue is then also computed when the loop
+//! body is not executed, so it must be pure and unable to fail: the length of
+//! a slice behind a reference, a discriminant, a shared reborrow, a cast and
+//! arithmetic without its overflow checks. The local is then live from the
+//! preheader on, so its `StorageLive` and `StorageDead` statements are
+//! removed.
+//!
+//! Loops are handled from the innermost, so a computation can be moved out
+//! of several loops.
+
+use crate::transform::MirPass;
+use crate::util::loops::{find_loops, Loop};
+use crate::util::ssa::SsaLocals;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::{
+    traversal, BasicBlock, BasicBlockData, BinOp, Body, BorrowKind, CastKind, Local, Location,
+    Mutability, NullOp, Operand, PlaceRef, ProjectionElem, Rvalue, StatementKind, Terminator,
+    TerminatorKind, START_BLOCK,
+};
+use rustc_middle::ty::{self, TyCtxt};
+
+pub struct LoopInvariantCodeMotion;
+
+impl<'tcx> MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return;
+        }
+
+        trace!("running LoopInvariantCodeMotion on {:?}", body.source);
+        // Each round moves the invariant assignments of one loop, and changes
+        // the loops and the dominators.
+        loop {
+            let dominators = body.dominators();
+            let ssa = SsaLocals::new(body);
+            let loops = find_loops(body, &dominators);
+            let invariant = loops.into_iter().find_map(|l| {
+                let assignments = find_invariant_assignments(body, &dominators, &ssa, &l);
+                (!assignments.is_empty()).then_some((l, assignments))
+            });
+            match invariant {
+                Some((l, assignments)) => hoist(body, &l, &assignments),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Finds the assignments of `l` that can be moved to its preheader, in the
+/// order in which they must be executed.
+fn find_invariant_assignments(
+    body: &Body<'_>,
+    dominators: &Dominators<BasicBlock>,
+    ssa: &SsaLocals,
+    l: &Loop,
+) -> Vec<Location> {
+    if l.header == START_BLOCK {
+        // There is no way to add a preheader.
+        return Vec::new();
+    }
+
+    let mut hoisted = BitSet::new_empty(body.local_decls.len());
+    let mut assignments = Vec::new();
+    // A block comes before the blocks it dominates, so the assignments of the
+    // locals come before their uses.
+    for (block, data) in traversal::reverse_postorder(body) {
+        if !l.blocks.contains(block) || data.is_cleanup {
+            continue;
+        }
+
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                let local = match place.as_local() {
+                    Some(local) if ssa.assignment(local).is_some() => local,
+                    _ => continue,
+                };
+                // The local must also be initialized when the loop is not
+                // entered, so its assignment must dominate the loop.
+                let is_invariant = |local: Local| {
+                    ssa.is_ssa(local)
+                        && (hoisted.contains(local)
+                            || ssa.assignment(local).map_or(true, |a| {
+                                !l.blocks.contains(a.block)
+                                    && dominators.is_dominated_by(l.header, a.block)
+                            }))
+                };
+                if is_invariant_rvalue(body, rvalue, is_invariant) {
+                    hoisted.insert(local);
+                    assignments.push(Location { block, statement_index });
+                }
+            }
+        }
+    }
+    assignments
+}
+
+/// Whether `rvalue` is pure, cannot fail and only reads constants and the
+/// locals for which `is_invariant` holds.
+fn is_invariant_rvalue<'tcx>(
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    is_invariant: impl Fn(Local) -> bool,
+) -> bool {
+    let is_invariant_operand = |operand: &Operand<'_>| match operand {
+        Operand::Copy(place) | Operand::Move(place) => {
+            place.projection.is_empty() && is_invariant(place.local)
+        }
+        Operand::Constant(_) => true,
+    };
+    match *rvalue {
+        // The length of a slice is part of the pointer to it.
+        Rvalue::Len(place) => match place.as_ref() {
+            PlaceRef { local, projection: [ProjectionElem::Deref] } => {
+                body.local_decls[local].ty.is_region_ptr() && is_invariant(local)
+            }
+            _ => false,
+        },
+        Rvalue::Discriminant(place) => {
+            place.projection.iter().all(|elem| matches!(elem, ProjectionElem::Field(..)))
+                && is_invariant(place.local)
+        }
+        Rvalue::Ref(_, BorrowKind::Shared, place) => match place.as_ref() {
+            PlaceRef { local, projection: [ProjectionElem::Deref] } => {
+                matches!(body.local_decls[local].ty.kind(), ty::Ref(_, _, Mutability::Not))
+                    && is_invariant(local)
+            }
+            _ => false,
+        },
+        Rvalue::NullaryOp(NullOp::SizeOf, _) => true,
+        Rvalue::Cast(CastKind::Misc, ref operand, _) | Rvalue::UnaryOp(_, ref operand) => {
+            is_invariant_operand(operand)
+        }
+        // Division and shifts can be undefined.
+        Rvalue::BinaryOp(
+            BinOp::Add
+            | BinOp::Sub
+            | BinOp::Mul
+            | BinOp::BitXor
+            | BinOp::BitAnd
+            | BinOp::BitOr
+            | BinOp::Eq
+            | BinOp::Lt
+            | BinOp::Le
+            | BinOp::Ne
+            | BinOp::Ge
+            | BinOp::Gt,
+            ref lhs,
+            ref rhs,
+        )
+        | Rvalue::CheckedBinaryOp(BinOp::Add | BinOp::Sub | BinOp::Mul, ref lhs, ref rhs) => {
+            is_invariant_operand(lhs) && is_invariant_operand(rhs)
+        }
+        _ => false,
+    }
+}
+
+/// Moves `assignments` to the end of the preheader of `l`.
+fn hoist(body: &mut Body<'_>, l: &Loop, assignments: &[Location]) {
+    let preheader = find_or_create_preheader(body, l);
+    debug!("moving {:?} to {:?}", assignments, preheader);
+
+    let mut locals = BitSet::new_empty(body.local_decls.len());
+    let basic_blocks = body.basic_blocks_mut();
+    let mut statements = Vec::with_capacity(assignments.len());
+    for location in assignments {
+        let statement =
+            basic_blocks[location.block].statements[location.statement_index].replace_nop();
+        if let StatementKind::Assign(box (place, _)) = &statement.kind {
+            locals.insert(place.local);
+        }
+        statements.push(statement);
+    }
+    basic_blocks[preheader].statements.extend(statements);
+
+    for data in basic_blocks.iter_mut() {
+        for statement in &mut data.statements {
+            match statement.kind {
+                StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                    if locals.contains(local) =>
+                {
+                    statement.make_nop()
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Returns the block through which `l` is entered, which is created if the
+/// header of the loop has several predecessors outside of it.
+fn find_or_create_preheader(body: &mut Body<'_>, l: &Loop) -> BasicBlock {
+    let outside: Vec<_> = body.predecessors()[l.header]
+        .iter()
+        .copied()
+        .filter(|&pred| !l.blocks.contains(pred))
+        .collect();
+    if let [pred] = outside[..] {
+        let data = &body.basic_blocks()[pred];
+        if let TerminatorKind::Goto { .. } = data.terminator().kind {
+            if !data.is_cleanup {
+                return pred;
+            }
+        }
+    }
+
+    let source_info = body.basic_blocks()[l.header].terminator().source_info;
+    let basic_blocks = body.basic_blocks_mut();
+    let preheader = basic_blocks.push(BasicBlockData {
+        statements: vec![],
+        terminator: Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Goto { target: l.header },
+        }),
+        is_cleanup: false,
+    });
+    for pred in outside {
+        for target in basic_blocks[pred].terminator_mut().successors_mut() {
+            if *target == l.header {
+                *target = preheader;
+            }
+        }
+    }
+    preheader
+}
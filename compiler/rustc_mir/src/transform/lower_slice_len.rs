@@ -0,0 +1,51 @@
+//! Replaces the calls to `<[T]>::len` with the `Len` rvalue, so that the
+//! length of a slice is a value that the later passes understand, just like
+//! the length computed by a bounds check.
+//!
+//! ```text
+//! _3 = core::slice::<impl [T]>::len(move _4) -> bb1;    =>    _3 = Len((*_4));
+//!                                                             goto -> bb1;
+//! ```
+
+use crate::transform::MirPass;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, TyCtxt};
+
+pub struct LowerSliceLenCalls;
+
+impl<'tcx> MirPass<'tcx> for LowerSliceLenCalls {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let slice_len_fn = match tcx.lang_items().slice_len_fn() {
+            Some(slice_len_fn) => slice_len_fn,
+            None => return,
+        };
+
+        trace!("running LowerSliceLenCalls on {:?}", body.source);
+        for block in body.basic_blocks_mut() {
+            let terminator = block.terminator();
+            let (place, destination, target) = match &terminator.kind {
+                TerminatorKind::Call {
+                    func: Operand::Constant(func),
+                    args,
+                    destination: Some((destination, target)),
+                    ..
+                } => match (func.literal.ty.kind(), &args[..]) {
+                    (&ty::FnDef(def_id, _), [Operand::Move(place) | Operand::Copy(place)])
+                        if def_id == slice_len_fn =>
+                    {
+                        (*place, *destination, *target)
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            debug!("replacing `{:?}` with `Len`", terminator.kind);
+            let source_info = terminator.source_info;
+            let len = Rvalue::Len(tcx.mk_place_deref(place));
+            let kind = StatementKind::Assign(box (destination, len));
+            block.statements.push(Statement { source_info, kind });
+            block.terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
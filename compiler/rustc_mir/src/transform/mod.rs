@@ -30,13 +30,17 @@ pub mod early_otherwise_branch;
 pub mod elaborate_drops;
 pub mod function_item_references;
 pub mod generator;
+pub mod gvn;
 pub mod inline;
 pub mod instcombine;
+pub mod licm;
+pub mod lower_slice_len;
 pub mod match_branches;
 pub mod multiple_return_terminators;
 pub mod no_landing_pads;
 pub mod nrvo;
 pub mod promote_consts;
+pub mod remove_bounds_checks;
 pub mod remove_noop_landing_pads;
 pub mod remove_unneeded_drops;
 pub mod required_consts;
@@ -45,6 +49,7 @@ pub mod simplify;
 pub mod simplify_branches;
 pub mod simplify_comparison_integral;
 pub mod simplify_try;
+pub mod sroa;
 pub mod uninhabited_enum_branching;
 pub mod unreachable_prop;
 pub mod validate;
@@ -71,6 +76,8 @@ pub(crate) fn provide(providers: &mut Providers) {
         mir_drops_elaborated_and_const_checked,
         optimized_mir,
         optimized_mir_of_const_arg,
+        mir_callgraph_reachable: inline::cycle::mir_callgraph_reachable,
+        mir_inliner_callees: inline::cycle::mir_inliner_callees,
         is_mir_available,
         promoted_mir: |tcx, def_id| {
             let def_id = def_id.expect_local();
@@ -333,6 +340,16 @@ fn mir_drops_elaborated_and_const_checked<'tcx>(
         tcx.ensure().mir_borrowck(def.did);
     }
 
+    // The inliner builds the call graph from the promoted MIR, before it is
+    // stolen here. Only do it if the call graph is going to be used. This
+    // must not use `ensure`, which does not run the query if its dep node is
+    // green, so that a later call would read the stolen MIR.
+    let hir_id = tcx.hir().local_def_id_to_hir_id(def.did);
+    if tcx.hir().body_owner_kind(hir_id).is_fn_or_closure() && inline::is_enabled(tcx) {
+        let instance = ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def.did.to_def_id()));
+        let _ = tcx.mir_inliner_callees(instance);
+    }
+
     let (body, _) = tcx.mir_promoted(def);
     let mut body = body.steal();
 
@@ -380,6 +397,7 @@ fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
     let optimizations_with_generators: &[&dyn MirPass<'tcx>] = &[
         &unreachable_prop::UnreachablePropagation,
         &uninhabited_enum_branching::UninhabitedEnumBranching,
+        &lower_slice_len::LowerSliceLenCalls,
         &simplify::SimplifyCfg::new("after-uninhabited-enum-branching"),
         &inline::Inline,
         &generator::StateTransform,
@@ -394,9 +412,13 @@ fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         &match_branches::MatchBranchSimplification,
         // inst combine is after MatchBranchSimplification to clean up Ne(_1, false)
         &multiple_return_terminators::MultipleReturnTerminators,
+        &sroa::ScalarReplacementOfAggregates,
         &instcombine::InstCombine,
         &const_prop::ConstProp,
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
+        &licm::LoopInvariantCodeMotion,
+        &remove_bounds_checks::RemoveRedundantBoundsChecks,
+        &gvn::GVN,
         &early_otherwise_branch::EarlyOtherwiseBranch,
         &simplify_comparison_integral::SimplifyComparisonIntegral,
         &simplify_try::SimplifyArmIdentity,
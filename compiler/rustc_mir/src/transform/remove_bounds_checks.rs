@@ -0,0 +1,368 @@
+//! Removes the bounds checks whose index is known to be in bounds, because a
+//! condition that dominates them already compared the index with the length.
+//!
+//! ```text
+//! bb1: {
+//!     _4 = _2;
+//!     _5 = Len((*_1));
+//!     _3 = Lt(move _4, move _5);
+//!     switchInt(move _3) -> [false: bb4, otherwise: bb2];
+//! }
+//! bb2: {
+//!     _7 = _2;
+//!     _8 = Len((*_1));
+//!     _9 = Lt(_7, _8);
+//!     assert(move _9, ...) -> bb3;          =>      goto -> bb3;
+//! }
+//! ```
+//!
+//! The conditions are the comparisons on which a `switchInt` branches, which
+//! hold in the targets of the branches, and the conditions of `Assert`s,
+//! which hold in their targets. The compared values are constants, SSA
+//! locals, the lengths of arrays and of slices behind SSA pointers, and the
+//! reads of locals whose address is never taken, like the index `_2` above.
+//! Two reads of such a local give the same value if the local is not written
+//! on any path from the first read to the second one.
+
+use crate::transform::MirPass;
+use crate::util::ssa::SsaLocals;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{
+    AssertKind, BasicBlock, BinOp, Body, Local, Location, Operand, Place, PlaceRef, ProjectionElem,
+    Rvalue, StatementKind, TerminatorKind, START_BLOCK,
+};
+use rustc_middle::ty::{self, TyCtxt};
+
+pub struct RemoveRedundantBoundsChecks;
+
+impl<'tcx> MirPass<'tcx> for RemoveRedundantBoundsChecks {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return;
+        }
+
+        trace!("running RemoveRedundantBoundsChecks on {:?}", body.source);
+        let redundant = BoundsChecks::new(body).find_redundant();
+        for block in redundant {
+            let terminator = body.basic_blocks_mut()[block].terminator_mut();
+            if let TerminatorKind::Assert { target, .. } = terminator.kind {
+                debug!("removing the bounds check of {:?}", block);
+                terminator.kind = TerminatorKind::Goto { target };
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Value<'tcx> {
+    Constant(&'tcx ty::Const<'tcx>),
+    /// The value of an SSA local.
+    Local(Local),
+    /// The length of an array local, or of the slice behind an SSA pointer.
+    Len(Local),
+    /// The value of a local whose address is never taken, read at a location.
+    Read(Local, Location),
+}
+
+/// The condition `lhs < rhs`.
+#[derive(Copy, Clone, Debug)]
+struct Fact<'tcx> {
+    lhs: Value<'tcx>,
+    rhs: Value<'tcx>,
+}
+
+struct BoundsChecks<'a, 'tcx> {
+    body: &'a Body<'tcx>,
+    ssa: SsaLocals,
+    dominators: Dominators<BasicBlock>,
+    /// The writes of each local whose address is never taken, and `None` for
+    /// the other locals.
+    writes: IndexVec<Local, Option<Vec<Location>>>,
+}
+
+impl<'a, 'tcx> BoundsChecks<'a, 'tcx> {
+    fn new(body: &'a Body<'tcx>) -> Self {
+        let mut writes = Writes { writes: IndexVec::from_elem(Some(vec![]), &body.local_decls) };
+        writes.visit_body(body);
+        BoundsChecks {
+            body,
+            ssa: SsaLocals::new(body),
+            dominators: body.dominators(),
+            writes: writes.writes,
+        }
+    }
+
+    /// Finds the blocks whose bounds check always succeeds.
+    fn find_redundant(&self) -> Vec<BasicBlock> {
+        let basic_blocks = self.body.basic_blocks();
+        // The facts that hold on entry to each block, with the block whose
+        // terminator established them.
+        let mut facts = IndexVec::from_elem(vec![], basic_blocks);
+        for (block, data) in basic_blocks.iter_enumerated() {
+            if !self.dominators.is_reachable(block) {
+                continue;
+            }
+
+            match &data.terminator().kind {
+                TerminatorKind::SwitchInt {
+                    discr: Operand::Copy(place) | Operand::Move(place),
+                    switch_ty,
+                    targets,
+                } if switch_ty.is_bool() => {
+                    for (value, target) in targets.iter() {
+                        self.add_fact(&mut facts, block, target, *place, value != 0);
+                    }
+                    // The otherwise branch is taken for the other boolean.
+                    if targets.iter().len() == 1 {
+                        let (value, _) = targets.iter().next().unwrap();
+                        self.add_fact(&mut facts, block, targets.otherwise(), *place, value == 0);
+                    }
+                }
+                TerminatorKind::Assert {
+                    cond: Operand::Copy(place) | Operand::Move(place),
+                    expected,
+                    target,
+                    ..
+                } => self.add_fact(&mut facts, block, *target, *place, *expected),
+                _ => {}
+            }
+        }
+
+        let mut redundant = vec![];
+        for (block, data) in basic_blocks.iter_enumerated() {
+            if !self.dominators.is_reachable(block) {
+                continue;
+            }
+
+            let check = match &data.terminator().kind {
+                TerminatorKind::Assert {
+                    cond: Operand::Copy(place) | Operand::Move(place),
+                    expected: true,
+                    msg: AssertKind::BoundsCheck { .. },
+                    ..
+                } => match self.condition(*place, true) {
+                    Some(check) => check,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let is_redundant = self.dominators.dominators(block).any(|entry| {
+                facts[entry].iter().any(|&(fact, from)| {
+                    self.is_same(fact.lhs, check.lhs, from, entry)
+                        && self.is_same(fact.rhs, check.rhs, from, entry)
+                })
+            });
+            if is_redundant {
+                redundant.push(block);
+            }
+        }
+        redundant
+    }
+
+    /// Records that the condition `place == holds` holds on entry to `target`,
+    /// if `from` is its only predecessor. The start block is also entered from
+    /// the caller, so nothing holds on entry to it.
+    fn add_fact(
+        &self,
+        facts: &mut IndexVec<BasicBlock, Vec<(Fact<'tcx>, BasicBlock)>>,
+        from: BasicBlock,
+        target: BasicBlock,
+        place: Place<'tcx>,
+        holds: bool,
+    ) {
+        if target == START_BLOCK || self.body.predecessors()[target].len() != 1 {
+            return;
+        }
+        if let Some(fact) = self.condition(place, holds) {
+            debug!("{:?} holds in {:?}", fact, target);
+            facts[target].push((fact, from));
+        }
+    }
+
+    /// The comparison of the values that `place` holds the result of, if that
+    /// result is `holds`.
+    fn condition(&self, place: Place<'tcx>, holds: bool) -> Option<Fact<'tcx>> {
+        let location = self.ssa.assignment(place.as_local()?)?;
+        match self.rvalue(location)? {
+            Rvalue::BinaryOp(op, lhs, rhs) => {
+                let (lhs, rhs) = match (*op, holds) {
+                    (BinOp::Lt, true) | (BinOp::Ge, false) => (lhs, rhs),
+                    (BinOp::Gt, true) | (BinOp::Le, false) => (rhs, lhs),
+                    _ => return None,
+                };
+                Some(Fact { lhs: self.value(lhs, location)?, rhs: self.value(rhs, location)? })
+            }
+            _ => None,
+        }
+    }
+
+    /// The rvalue assigned by the statement at `location`.
+    fn rvalue(&self, location: Location) -> Option<&'a Rvalue<'tcx>> {
+        let statement =
+            self.body.basic_blocks()[location.block].statements.get(location.statement_index)?;
+        match &statement.kind {
+            StatementKind::Assign(box (_, rvalue)) => Some(rvalue),
+            _ => None,
+        }
+    }
+
+    /// The value of `operand` when read at `location`.
+    fn value(&self, operand: &Operand<'tcx>, location: Location) -> Option<Value<'tcx>> {
+        let local = match operand {
+            Operand::Constant(constant) => return Some(Value::Constant(constant.literal)),
+            Operand::Copy(place) | Operand::Move(place) => place.as_local()?,
+        };
+        if !self.ssa.is_ssa(local) {
+            return self.writes[local].is_some().then_some(Value::Read(local, location));
+        }
+
+        // Look through the copies and the lengths.
+        let assignment = match self.ssa.assignment(local) {
+            Some(assignment) => assignment,
+            None => return Some(Value::Local(local)),
+        };
+        match self.rvalue(assignment) {
+            Some(Rvalue::Use(operand)) => self.value(operand, assignment),
+            Some(Rvalue::Len(place)) => self.len(*place),
+            _ => Some(Value::Local(local)),
+        }
+    }
+
+    fn len(&self, place: Place<'tcx>) -> Option<Value<'tcx>> {
+        match place.as_ref() {
+            PlaceRef { local, projection: [] }
+                if matches!(self.body.local_decls[local].ty.kind(), ty::Array(..)) =>
+            {
+                Some(Value::Len(local))
+            }
+            PlaceRef { local, projection: [ProjectionElem::Deref] } if self.ssa.is_ssa(local) => {
+                Some(Value::Len(self.pointer(local)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The SSA pointer that the SSA pointer `local` copies or reborrows.
+    fn pointer(&self, local: Local) -> Local {
+        let pointer = match self.ssa.assignment(local).and_then(|a| self.rvalue(a)) {
+            Some(Rvalue::Use(Operand::Copy(place) | Operand::Move(place))) => place.as_local(),
+            Some(Rvalue::Ref(_, _, place)) => match place.as_ref() {
+                PlaceRef { local, projection: [ProjectionElem::Deref] } => Some(local),
+                _ => None,
+            },
+            _ => None,
+        };
+        match pointer {
+            Some(pointer) if self.ssa.is_ssa(pointer) => self.pointer(pointer),
+            _ => local,
+        }
+    }
+
+    /// Whether the value `fact` of a fact established by the terminator of
+    /// `from` on entry to `entry` is equal to `value`, which is read in a block
+    /// dominated by `entry`.
+    fn is_same(
+        &self,
+        fact: Value<'tcx>,
+        value: Value<'tcx>,
+        from: BasicBlock,
+        entry: BasicBlock,
+    ) -> bool {
+        match (fact, value) {
+            (Value::Read(local, read), Value::Read(other, later)) if local == other => {
+                read == later || read.block == from && self.is_unchanged(local, read, entry, later)
+            }
+            _ => fact == value,
+        }
+    }
+
+    /// Whether `local` is never written after `read`, which is in the only
+    /// predecessor of `entry`, and before `later`.
+    fn is_unchanged(
+        &self,
+        local: Local,
+        read: Location,
+        entry: BasicBlock,
+        later: Location,
+    ) -> bool {
+        if !self.dominators.is_dominated_by(later.block, entry) {
+            return false;
+        }
+
+        // The blocks on the paths from `entry` to `later`, which all go
+        // through `entry` since it dominates `later`.
+        let predecessors = self.body.predecessors();
+        let mut blocks = BitSet::new_empty(self.body.basic_blocks().len());
+        let mut is_cyclic = false;
+        let mut stack = vec![later.block];
+        while let Some(block) = stack.pop() {
+            if !blocks.insert(block) {
+                is_cyclic |= block == later.block;
+                continue;
+            }
+            if block != entry {
+                let reachable = |&pred: &BasicBlock| self.dominators.is_reachable(pred);
+                stack.extend(predecessors[block].iter().copied().filter(reachable));
+            }
+        }
+
+        let writes = match &self.writes[local] {
+            Some(writes) => writes,
+            None => return false,
+        };
+        writes.iter().all(|write| {
+            if write.block == read.block {
+                write.statement_index < read.statement_index
+            } else if write.block == later.block && !is_cyclic {
+                write.statement_index >= later.statement_index
+            } else {
+                !blocks.contains(write.block)
+            }
+        })
+    }
+}
+
+/// Finds the writes of the locals whose address is never taken.
+struct Writes {
+    writes: IndexVec<Local, Option<Vec<Location>>>,
+}
+
+impl<'tcx> Visitor<'tcx> for Writes {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Writing or borrowing through a pointer does not change the pointer.
+        if place.is_indirect() {
+            return;
+        }
+
+        let writes = &mut self.writes[place.local];
+        match context {
+            PlaceContext::MutatingUse(
+                MutatingUseContext::Borrow | MutatingUseContext::AddressOf,
+            )
+            | PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::SharedBorrow
+                | NonMutatingUseContext::ShallowBorrow
+                | NonMutatingUseContext::UniqueBorrow
+                | NonMutatingUseContext::AddressOf,
+            ) => *writes = None,
+            PlaceContext::MutatingUse(_) => {
+                if let Some(writes) = writes {
+                    writes.push(location);
+                }
+            }
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, location: Location) {
+        // The storage statements make the local uninitialized.
+        if context.is_storage_marker() {
+            if let Some(writes) = &mut self.writes[local] {
+                writes.push(location);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Scalar replacement of aggregates: splits the locals of a struct, tuple or
+//! closure type that are only accessed field by field into a local for each
+//! field.
+//!
+//! ```text
+//! _3.0 = _1;                      _5 = _1;
+//! _3.1 = const 2_u32;       =>    _6 = const 2_u32;
+//! _4 = Add(_3.0, _3.1);           _4 = Add(_5, _6);
+//! ```
+//!
+//! Aggregates are assigned field by field since the `Deaggregator`, so this
+//! applies to most of the locals that are not used as a whole, i.e. moved,
+//! copied, borrowed or dropped. The field locals are split in turn, and a
+//! field that is never accessed gets no local. Codegen then allocates
+//! separate and often no stack slots for the fields, and the later passes
+//! see the scalars as locals.
+//!
+//! Arguments and the return place have a fixed layout and are not split. The
+//! debuginfo of a user variable refers to the whole local, so a user variable
+//! is not split either, unless only its fields are in the debuginfo.
+
+use crate::transform::MirPass;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::{
+    Body, Local, LocalDecl, Location, Place, ProjectionElem, Statement, StatementKind,
+};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+
+pub struct ScalarReplacementOfAggregates;
+
+impl<'tcx> MirPass<'tcx> for ScalarReplacementOfAggregates {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return;
+        }
+
+        trace!("running ScalarReplacementOfAggregates on {:?}", body.source);
+        // Each round splits the fields created by the previous one.
+        loop {
+            let fields = find_fields(tcx, body);
+            if fields.iter().all(|fields| fields.is_empty()) {
+                break;
+            }
+            let fragments = create_fragments(body, fields);
+            replace_fields(tcx, body, fragments);
+        }
+    }
+}
+
+/// Whether a local of type `ty` can be split into its fields.
+fn is_splittable(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    match ty.kind() {
+        ty::Tuple(..) | ty::Closure(..) => true,
+        ty::Adt(def, _) => {
+            def.is_struct() && !def.has_dtor(tcx) && !def.repr.simd() && !ty.is_box()
+        }
+        _ => false,
+    }
+}
+
+/// Returns the type of each accessed field of the locals that can be split,
+/// and nothing for the others.
+fn find_fields(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> IndexVec<Local, Vec<Option<Ty<'tcx>>>> {
+    let mut candidates = BitSet::new_empty(body.local_decls.len());
+    for (local, decl) in body.local_decls.iter_enumerated().skip(1 + body.arg_count) {
+        if is_splittable(tcx, decl.ty) {
+            candidates.insert(local);
+        }
+    }
+
+    let mut finder =
+        FieldFinder { candidates, fields: IndexVec::from_elem(vec![], &body.local_decls) };
+    finder.visit_body(body);
+
+    let FieldFinder { candidates, mut fields } = finder;
+    for (local, fields) in fields.iter_enumerated_mut() {
+        if !candidates.contains(local) {
+            fields.clear();
+        }
+    }
+    fields
+}
+
+struct FieldFinder<'tcx> {
+    candidates: BitSet<Local>,
+    fields: IndexVec<Local, Vec<Option<Ty<'tcx>>>>,
+}
+
+impl<'tcx> Visitor<'tcx> for FieldFinder<'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, _: PlaceContext, _: Location) {
+        if !self.candidates.contains(place.local) {
+            return;
+        }
+        match place.projection.first() {
+            Some(&ProjectionElem::Field(field, ty)) => {
+                let fields = &mut self.fields[place.local];
+                if fields.len() <= field.index() {
+                    fields.resize(field.index() + 1, None);
+                }
+                fields[field.index()] = Some(ty);
+            }
+            // The local is used as a whole.
+            _ => {
+                trace!("{:?} can't be split, it is used as {:?}", place.local, place);
+                self.candidates.remove(place.local);
+            }
+        }
+    }
+}
+
+/// Creates the locals of the accessed fields, and returns the local of each
+/// field of the locals that are split.
+fn create_fragments(
+    body: &mut Body<'tcx>,
+    fields: IndexVec<Local, Vec<Option<Ty<'tcx>>>>,
+) -> IndexVec<Local, Vec<Option<Local>>> {
+    fields
+        .into_iter_enumerated()
+        .map(|(local, fields)| {
+            let span = body.local_decls[local].source_info.span;
+            let internal = body.local_decls[local].internal;
+            fields
+                .into_iter()
+                .map(|ty| {
+                    let mut decl = LocalDecl::new(ty?, span);
+                    decl.internal = internal;
+                    Some(body.local_decls.push(decl))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Replaces the fields of the split locals with their fragments.
+fn replace_fields(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    fragments: IndexVec<Local, Vec<Option<Local>>>,
+) {
+    let mut replacer = FieldReplacer { tcx, fragments };
+    replacer.visit_body(body);
+    let fragments = replacer.fragments;
+
+    // The storage of a split local is the storage of its fragments.
+    for data in body.basic_blocks_mut() {
+        data.expand_statements(|statement| {
+            let (local, make_kind): (_, fn(Local) -> StatementKind<'tcx>) = match statement.kind {
+                StatementKind::StorageLive(local) => (local, StatementKind::StorageLive),
+                StatementKind::StorageDead(local) => (local, StatementKind::StorageDead),
+                _ => return None,
+            };
+            if fragments.get(local).map_or(true, |fragments| fragments.is_empty()) {
+                return None;
+            }
+            let source_info = statement.source_info;
+            let statements: Vec<_> = fragments[local]
+                .iter()
+                .flatten()
+                .map(|&fragment| Statement { source_info, kind: make_kind(fragment) })
+                .collect();
+            Some(statements.into_iter())
+        });
+    }
+}
+
+struct FieldReplacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    fragments: IndexVec<Local, Vec<Option<Local>>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for FieldReplacer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, _: PlaceContext, _: Location) {
+        // The locals created for the fields have no fragments.
+        let fragments = match self.fragments.get(place.local) {
+            Some(fragments) if !fragments.is_empty() => fragments,
+            _ => return,
+        };
+        let (field, rest) = match place.projection.split_first() {
+            Some((&ProjectionElem::Field(field, _), rest)) => (field, rest),
+            _ => bug!("split local {:?} is used as {:?}", place.local, place),
+        };
+        *place = Place {
+            local: fragments[field.index()].unwrap(),
+            projection: self.tcx.intern_place_elems(rest),
+        };
+    }
+}
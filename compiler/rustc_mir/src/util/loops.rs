@@ -0,0 +1,57 @@
+//! Finds the natural loops of a body.
+
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::{BasicBlock, Body};
+
+/// A natural loop: a header and the blocks from which a back edge to the
+/// header is reachable without going through the header. The header
+/// dominates the blocks of the loop, so the loop is only entered through the
+/// header.
+#[derive(Clone, Debug)]
+pub struct Loop {
+    pub header: BasicBlock,
+    /// The blocks of the loop, including the header.
+    pub blocks: BitSet<BasicBlock>,
+}
+
+/// Finds the natural loops of the reachable blocks of `body`. The loops that
+/// share a header are merged, and a loop comes after the loops nested in it.
+pub fn find_loops(body: &Body<'_>, dominators: &Dominators<BasicBlock>) -> Vec<Loop> {
+    let predecessors = body.predecessors();
+    let mut loops: Vec<Loop> = Vec::new();
+    for (source, data) in body.basic_blocks().iter_enumerated() {
+        if !dominators.is_reachable(source) {
+            continue;
+        }
+
+        for &header in data.terminator().successors() {
+            // An edge to a block that dominates its source is a back edge.
+            if !dominators.is_dominated_by(source, header) {
+                continue;
+            }
+
+            let index = match loops.iter().position(|l| l.header == header) {
+                Some(index) => index,
+                None => {
+                    let mut blocks = BitSet::new_empty(body.basic_blocks().len());
+                    blocks.insert(header);
+                    loops.push(Loop { header, blocks });
+                    loops.len() - 1
+                }
+            };
+            let blocks = &mut loops[index].blocks;
+            let mut stack = vec![source];
+            while let Some(block) = stack.pop() {
+                if blocks.insert(block) {
+                    let reachable = |&pred: &BasicBlock| dominators.is_reachable(pred);
+                    stack.extend(predecessors[block].iter().copied().filter(reachable));
+                }
+            }
+        }
+    }
+
+    // A loop nested in another one has fewer blocks.
+    loops.sort_by_key(|l| l.blocks.count());
+    loops
+}
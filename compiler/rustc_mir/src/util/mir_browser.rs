@@ -0,0 +1,386 @@
+//! The MIR browser of `-Z dump-mir-browser`: in addition to the `.mir` files,
+//! `-Z dump-mir` writes a single `.html` file for each body, with a tab for
+//! its MIR after each pass. Each tab highlights the lines that changed since
+//! the previous tab, and each statement or terminator links to its source
+//! range in a spanview of the body and shows the locals live before it.
+//!
+//! The dumps of a compilation are kept in its `TyCtxt`, and the files are
+//! written once, when it is dropped at the end of the compilation. This also
+//! happens when the compilation fails, so that the browsers show the passes
+//! that ran before the failure.
+
+use std::cell::Cell;
+use std::cmp;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use super::pretty::{body_file_basename, create_dump_file_at, dump_path, write_mir_fn, PassWhere};
+use super::spanview::{escape_attr, escape_html, source_range_no_file, write_mir_fn_spanview};
+use crate::dataflow::impls::MaybeLiveLocals;
+use crate::dataflow::Analysis;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lock;
+use rustc_middle::mir::{Body, Location};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::config::MirSpanview;
+
+/// The largest number of pairs of lines compared to diff two dumps. Larger
+/// dumps are shown as entirely replaced.
+const MAX_DIFF_SIZE: usize = 1 << 22;
+
+/// The MIR dumps of a compilation, kept in `GlobalCtxt::mir_browser`. The
+/// browsers are written when it is dropped.
+#[derive(Default)]
+pub struct MirBrowser {
+    /// The dumps recorded so far, by the path of the browser of their body.
+    bodies: Lock<FxHashMap<PathBuf, MirBodyDumps>>,
+}
+
+impl Drop for MirBrowser {
+    fn drop(&mut self) {
+        for (path, dumps) in self.bodies.get_mut() {
+            // As for the other dump files, an error is ignored.
+            let _: io::Result<()> = try {
+                let mut file = create_dump_file_at(path)?;
+                write_mir_browser(dumps, &mut file)?;
+                file.flush()?;
+            };
+        }
+    }
+}
+
+/// A line of a MIR dump, with the source range of the statement or
+/// terminator on it and the locals live before it, if there is one.
+#[derive(PartialEq, Eq)]
+struct MirDumpLine {
+    text: String,
+    span: Option<String>,
+    live: Option<String>,
+}
+
+/// The MIR of a body before or after a pass.
+struct MirPassDump {
+    pass: String,
+    lines: Vec<MirDumpLine>,
+}
+
+/// The MIR dumps of a body, in the order of the passes.
+struct MirBodyDumps {
+    title: String,
+    /// The spanview of the source of the body, if it is local.
+    source: Option<String>,
+    passes: Vec<MirPassDump>,
+}
+
+const STYLE_SECTION: &str = r#"<style>
+    body {
+        font-family: sans-serif;
+        margin: 0;
+        display: flex;
+        height: 100vh;
+    }
+    #mir {
+        flex: 1;
+        overflow: auto;
+        padding: 0 1em;
+    }
+    #source {
+        flex: 1;
+        border: none;
+        border-left: 1px solid #888888;
+    }
+    .tabs button {
+        font-family: Menlo, Monaco, monospace;
+        margin: 2px;
+    }
+    .tabs button.unchanged {
+        color: #888888;
+    }
+    .tabs button.selected {
+        font-weight: bold;
+    }
+    .pass {
+        display: none;
+    }
+    .pass.selected {
+        display: block;
+    }
+    table {
+        border-collapse: collapse;
+        font-family: Menlo, Monaco, monospace;
+        white-space: pre;
+    }
+    td.range {
+        color: #4444ff;
+        cursor: pointer;
+        padding-left: 2em;
+    }
+    td.live {
+        color: #888888;
+        padding-left: 2em;
+    }
+    tr.added {
+        background-color: #ccffcc;
+    }
+    tr.removed {
+        background-color: #ffcccc;
+        text-decoration: line-through;
+    }
+    body.hide-removed tr.removed {
+        display: none;
+    }
+</style>"#;
+
+const SCRIPT_SECTION: &str = r#"<script>
+    function show(index) {
+        document.querySelectorAll(".selected").forEach(e => e.classList.remove("selected"));
+        document.getElementById("tab-" + index).classList.add("selected");
+        document.getElementById("pass-" + index).classList.add("selected");
+    }
+    function showSource(range) {
+        const source = document.getElementById("source");
+        if (!source) {
+            return;
+        }
+        const code = source.contentDocument.querySelector(".code");
+        const first = parseInt(code.style.counterReset.split(" ")[1]) + 1;
+        const line = code.querySelectorAll(".line")[parseInt(range) - first];
+        if (line) {
+            line.scrollIntoView({ block: "center" });
+            line.style.outline = "2px solid #ffff00";
+            setTimeout(() => line.style.outline = "", 1000);
+        }
+    }
+</script>"#;
+
+const HIDE_REMOVED_CHECKBOX: &str = r#"<label>
+    <input type="checkbox"
+        onchange="document.body.classList.toggle('hide-removed', this.checked)">
+    hide removed lines
+</label>"#;
+
+/// Records the MIR of `body` after a pass, for the browser of the body.
+pub(super) fn record_mir_dump<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pass_num: Option<&dyn Display>,
+    pass_name: &str,
+    disambiguator: &dyn Display,
+    body: &Body<'tcx>,
+) -> io::Result<()> {
+    let path = dump_path(tcx, &body_file_basename(tcx, body.source), "html");
+    let def_path = ty::print::with_forced_impl_filename_line(|| {
+        // see notes on #41697 in `pretty`
+        tcx.def_path_str(body.source.def_id())
+    });
+    let title = match body.source.promoted {
+        None => format!("MIR for `{}`", def_path),
+        Some(promoted) => format!("MIR for `{}::{:?}`", def_path, promoted),
+    };
+    let pass = match pass_num {
+        None => format!("{} {}", pass_name, disambiguator),
+        Some(pass_num) => format!("{} {} {}", pass_num, pass_name, disambiguator),
+    };
+    let lines = mir_lines(tcx, body)?;
+
+    let mir_browser = tcx.mir_browser.downcast_ref::<MirBrowser>().unwrap();
+    let mut bodies = mir_browser.bodies.lock();
+    let dumps = bodies.entry(path).or_insert_with(|| {
+        let source = if body.source.def_id().is_local() {
+            let mut source = Vec::new();
+            write_mir_fn_spanview(tcx, body, MirSpanview::Statement, &title, &mut source)
+                .ok()
+                .map(|()| String::from_utf8_lossy(&source).into_owned())
+        } else {
+            None
+        };
+        MirBodyDumps { title, source, passes: Vec::new() }
+    });
+    dumps.passes.push(MirPassDump { pass, lines });
+    Ok(())
+}
+
+/// Returns the lines of the MIR of `body`, as written in the `.mir` files,
+/// with the source range and the live locals of the statements and
+/// terminators.
+fn mir_lines<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> io::Result<Vec<MirDumpLine>> {
+    let line_count = Cell::new(0);
+    let mut text = LineCounter { buf: Vec::new(), line_count: &line_count };
+    let mut locations = Vec::new();
+    write_mir_fn(
+        tcx,
+        body,
+        &mut |pass_where, _| {
+            if let PassWhere::BeforeLocation(location) = pass_where {
+                locations.push((line_count.get(), location));
+            }
+            Ok(())
+        },
+        &mut text,
+    )?;
+
+    let mut lines: Vec<_> = String::from_utf8_lossy(&text.buf)
+        .lines()
+        .map(|text| MirDumpLine { text: text.to_owned(), span: None, live: None })
+        .collect();
+    let mut live_locals =
+        MaybeLiveLocals.into_engine(tcx, body).iterate_to_fixpoint().into_results_cursor(body);
+    for (index, location) in locations {
+        let line = match lines.get_mut(index) {
+            Some(line) => line,
+            None => continue,
+        };
+        line.span = Some(source_range_no_file(tcx, &body.source_info(location).span));
+        // Liveness is a backward analysis, so the state after the primary
+        // effect of a location is the state before it in the program.
+        live_locals.seek_after_primary_effect(location);
+        let live: Vec<_> = live_locals.get().iter().map(|local| format!("{:?}", local)).collect();
+        line.live = Some(live.join(", "));
+    }
+    Ok(lines)
+}
+
+/// A buffer that counts the lines written to it, so that the lines of the
+/// locations can be recorded while the MIR is written.
+struct LineCounter<'a> {
+    buf: Vec<u8>,
+    line_count: &'a Cell<usize>,
+}
+
+impl Write for LineCounter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let lines = buf.iter().filter(|&&byte| byte == b'\n').count();
+        self.line_count.set(self.line_count.get() + lines);
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn write_mir_browser(dumps: &MirBodyDumps, w: &mut dyn Write) -> io::Result<()> {
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, "<title>{}</title>", escape_html(&dumps.title))?;
+    writeln!(w, "{}", STYLE_SECTION)?;
+    writeln!(w, "{}", SCRIPT_SECTION)?;
+    writeln!(w, "</head>")?;
+    writeln!(w, r#"<body onload="show({})">"#, dumps.passes.len().saturating_sub(1))?;
+    writeln!(w, r#"<div id="mir">"#)?;
+    writeln!(w, "<h3>{}</h3>", escape_html(&dumps.title))?;
+    writeln!(w, "{}", HIDE_REMOVED_CHECKBOX)?;
+
+    writeln!(w, r#"<div class="tabs">"#)?;
+    let mut previous: &[MirDumpLine] = &[];
+    for (index, dump) in dumps.passes.iter().enumerate() {
+        let class = if index > 0 && dump.lines == previous { "unchanged" } else { "" };
+        writeln!(
+            w,
+            r#"<button id="tab-{}" class="{}" onclick="show({})">{}</button>"#,
+            index,
+            class,
+            index,
+            escape_html(&dump.pass),
+        )?;
+        previous = &dump.lines;
+    }
+    writeln!(w, "</div>")?;
+
+    let mut previous: &[MirDumpLine] = &[];
+    for (index, dump) in dumps.passes.iter().enumerate() {
+        writeln!(w, r#"<div class="pass" id="pass-{}">"#, index)?;
+        writeln!(w, "<table>")?;
+        if index == 0 {
+            for line in &dump.lines {
+                write_line(line, "", w)?;
+            }
+        } else {
+            for change in diff(previous, &dump.lines) {
+                match change {
+                    Change::Same(line) => write_line(line, "", w)?,
+                    Change::Added(line) => write_line(line, "added", w)?,
+                    Change::Removed(line) => write_line(line, "removed", w)?,
+                }
+            }
+        }
+        writeln!(w, "</table>")?;
+        writeln!(w, "</div>")?;
+        previous = &dump.lines;
+    }
+    writeln!(w, "</div>")?;
+
+    if let Some(source) = &dumps.source {
+        writeln!(w, r#"<iframe id="source" srcdoc="{}"></iframe>"#, escape_attr(source))?;
+    }
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")?;
+    Ok(())
+}
+
+fn write_line(line: &MirDumpLine, class: &str, w: &mut dyn Write) -> io::Result<()> {
+    write!(w, r#"<tr class="{}"><td>{}</td>"#, class, escape_html(&line.text))?;
+    match &line.span {
+        Some(range) => write!(
+            w,
+            r#"<td class="range" onclick="showSource('{}')">{}</td>"#,
+            escape_attr(range),
+            escape_html(range),
+        )?,
+        None => write!(w, "<td></td>")?,
+    }
+    match &line.live {
+        Some(live) => writeln!(w, r#"<td class="live">live: {}</td></tr>"#, escape_html(live)),
+        None => writeln!(w, "<td></td></tr>"),
+    }
+}
+
+enum Change<'a> {
+    Same(&'a MirDumpLine),
+    Added(&'a MirDumpLine),
+    Removed(&'a MirDumpLine),
+}
+
+/// Computes the changes from the lines `old` to the lines `new`, from their
+/// longest common subsequence. The lines that are the same keep the source
+/// range and the live locals of `new`.
+fn diff<'a>(old: &'a [MirDumpLine], new: &'a [MirDumpLine]) -> Vec<Change<'a>> {
+    let (n, m) = (old.len(), new.len());
+    if n.saturating_mul(m) > MAX_DIFF_SIZE {
+        return old.iter().map(Change::Removed).chain(new.iter().map(Change::Added)).collect();
+    }
+
+    // `lcs[i][j]` is the length of the longest common subsequence of
+    // `old[i..]` and `new[j..]`.
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i].text == new[j].text {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::with_capacity(cmp::max(n, m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i].text == new[j].text {
+            changes.push(Change::Same(&new[j]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            changes.push(Change::Removed(&old[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(&new[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old[i..].iter().map(Change::Removed));
+    changes.extend(new[j..].iter().map(Change::Added));
+    changes
+}
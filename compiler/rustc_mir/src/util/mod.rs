@@ -1,7 +1,9 @@
 pub mod aggregate;
 pub mod borrowck_errors;
 pub mod elaborate_drops;
+pub mod loops;
 pub mod patch;
+pub mod ssa;
 pub mod storage;
 
 mod alignment;
@@ -9,6 +11,7 @@ pub mod collect_writes;
 mod find_self_call;
 pub(crate) mod generic_graphviz;
 mod graphviz;
+mod mir_browser;
 pub(crate) mod pretty;
 pub(crate) mod spanview;
 
@@ -17,4 +20,5 @@ pub use self::alignment::is_disaligned;
 pub use self::find_self_call::find_self_call;
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
 pub use self::graphviz::{graphviz_safe_def_name, write_mir_graphviz};
+pub use self::mir_browser::MirBrowser;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
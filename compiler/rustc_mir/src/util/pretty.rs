@@ -6,6 +6,7 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
 use super::graphviz::write_mir_fn_graphviz;
+use super::mir_browser::record_mir_dump;
 use super::spanview::write_mir_fn_spanview;
 use crate::transform::MirSource;
 use either::Either;
@@ -159,6 +160,10 @@ fn dump_matched_mir_node<'tcx, F>(
             }
         };
     }
+
+    if tcx.sess.opts.debugging_opts.dump_mir_browser {
+        let _: io::Result<()> = record_mir_dump(tcx, pass_num, pass_name, disambiguator, body);
+    }
 }
 
 /// Returns the file basename portion (without extension) of a filename path
@@ -170,11 +175,6 @@ fn dump_file_basename(
     disambiguator: &dyn Display,
     source: MirSource<'tcx>,
 ) -> String {
-    let promotion_id = match source.promoted {
-        Some(id) => format!("-{:?}", id),
-        None => String::new(),
-    };
-
     let pass_num = if tcx.sess.opts.debugging_opts.dump_mir_exclude_pass_number {
         String::new()
     } else {
@@ -184,6 +184,17 @@ fn dump_file_basename(
         }
     };
 
+    format!("{}{}.{}.{}", body_file_basename(tcx, source), pass_num, pass_name, disambiguator)
+}
+
+/// Returns the part of the file basenames of the MIR dumps of a body that
+/// identifies the body, without the pass.
+pub(crate) fn body_file_basename(tcx: TyCtxt<'_>, source: MirSource<'tcx>) -> String {
+    let promotion_id = match source.promoted {
+        Some(id) => format!("-{:?}", id),
+        None => String::new(),
+    };
+
     let crate_name = tcx.crate_name(source.def_id().krate);
     let item_name = tcx.def_path(source.def_id()).to_filename_friendly_no_crate();
     // All drop shims have the same DefId, so we have to add the type
@@ -203,16 +214,13 @@ fn dump_file_basename(
         _ => String::new(),
     };
 
-    format!(
-        "{}.{}{}{}{}.{}.{}",
-        crate_name, item_name, shim_disambiguator, promotion_id, pass_num, pass_name, disambiguator,
-    )
+    format!("{}.{}{}{}", crate_name, item_name, shim_disambiguator, promotion_id)
 }
 
 /// Returns the path to the filename where we should dump a given MIR.
 /// Also used by other bits of code (e.g., NLL inference) that dump
 /// graphviz data or other things.
-fn dump_path(tcx: TyCtxt<'_>, basename: &str, extension: &str) -> PathBuf {
+pub(crate) fn dump_path(tcx: TyCtxt<'_>, basename: &str, extension: &str) -> PathBuf {
     let mut file_path = PathBuf::new();
     file_path.push(Path::new(&tcx.sess.opts.debugging_opts.dump_mir_dir));
 
@@ -224,12 +232,16 @@ fn dump_path(tcx: TyCtxt<'_>, basename: &str, extension: &str) -> PathBuf {
 }
 
 /// Attempts to open the MIR dump file with the given name and extension.
-fn create_dump_file_with_basename(
+pub(crate) fn create_dump_file_with_basename(
     tcx: TyCtxt<'_>,
     file_basename: &str,
     extension: &str,
 ) -> io::Result<io::BufWriter<fs::File>> {
-    let file_path = dump_path(tcx, file_basename, extension);
+    create_dump_file_at(&dump_path(tcx, file_basename, extension))
+}
+
+/// Attempts to open the MIR dump file at `file_path`, a path from `dump_path`.
+pub(crate) fn create_dump_file_at(file_path: &Path) -> io::Result<io::BufWriter<fs::File>> {
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent).map_err(|e| {
             io::Error::new(
@@ -238,7 +250,7 @@ fn create_dump_file_with_basename(
             )
         })?;
     }
-    Ok(io::BufWriter::new(fs::File::create(&file_path).map_err(|e| {
+    Ok(io::BufWriter::new(fs::File::create(file_path).map_err(|e| {
         io::Error::new(e.kind(), format!("IO error creating MIR dump file: {:?}; {}", file_path, e))
     })?))
 }
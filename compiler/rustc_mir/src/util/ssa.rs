@@ -0,0 +1,77 @@
+//! Finds the locals of a body that are assigned at most once.
+
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{Body, Local, Location, Place, RETURN_PLACE};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Assignments {
+    None,
+    /// An argument, or a local assigned at the given location.
+    Once(Option<Location>),
+    Many,
+}
+
+/// The SSA locals of a body: the arguments that are never assigned, and the
+/// other locals that are assigned once, by a statement or by a call. The
+/// address of an SSA local is never taken.
+pub struct SsaLocals {
+    assignments: IndexVec<Local, Assignments>,
+}
+
+impl SsaLocals {
+    pub fn new(body: &Body<'_>) -> SsaLocals {
+        let mut assignments = IndexVec::from_elem(Assignments::None, &body.local_decls);
+        for arg in body.args_iter() {
+            assignments[arg] = Assignments::Once(None);
+        }
+        // The return place is read by the caller.
+        assignments[RETURN_PLACE] = Assignments::Many;
+        let mut ssa = SsaLocals { assignments };
+        ssa.visit_body(body);
+        ssa
+    }
+
+    pub fn is_ssa(&self, local: Local) -> bool {
+        matches!(self.assignments[local], Assignments::Once(_))
+    }
+
+    /// The location of the assignment of an SSA local, or `None` for the
+    /// arguments and the locals that are not SSA locals. The value of a local
+    /// assigned by a call is only available in the return block of the call.
+    pub fn assignment(&self, local: Local) -> Option<Location> {
+        match self.assignments[local] {
+            Assignments::Once(location) => location,
+            Assignments::None | Assignments::Many => None,
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for SsaLocals {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Writing or borrowing through a pointer does not change the pointer.
+        if place.is_indirect() {
+            return;
+        }
+
+        let assignments = &mut self.assignments[place.local];
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store | MutatingUseContext::Call)
+                if place.projection.is_empty() =>
+            {
+                *assignments = match *assignments {
+                    Assignments::None => Assignments::Once(Some(location)),
+                    Assignments::Once(_) | Assignments::Many => Assignments::Many,
+                };
+            }
+            PlaceContext::MutatingUse(_)
+            | PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::SharedBorrow
+                | NonMutatingUseContext::ShallowBorrow
+                | NonMutatingUseContext::UniqueBorrow
+                | NonMutatingUseContext::AddressOf,
+            ) => *assignments = Assignments::Many,
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
+    }
+}
@@ -37,8 +37,16 @@ fn should_explore(tcx: TyCtxt<'_>, hir_id: hir::HirId) -> bool {
     }
 }
 
+/// Whether an item was added to the worklist only because it is annotated with
+/// `#[expect(dead_code)]`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ComesFromAllowExpect {
+    Yes,
+    No,
+}
+
 struct MarkSymbolVisitor<'tcx> {
-    worklist: Vec<hir::HirId>,
+    worklist: Vec<(hir::HirId, ComesFromAllowExpect)>,
     tcx: TyCtxt<'tcx>,
     maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
     live_symbols: FxHashSet<hir::HirId>,
@@ -64,7 +72,7 @@ impl<'tcx> MarkSymbolVisitor<'tcx> {
         if let Some(def_id) = def_id.as_local() {
             let hir_id = self.tcx.hir().local_def_id_to_hir_id(def_id);
             if should_explore(self.tcx, hir_id) || self.struct_constructors.contains_key(&hir_id) {
-                self.worklist.push(hir_id);
+                self.worklist.push((hir_id, ComesFromAllowExpect::No));
             }
             self.live_symbols.insert(hir_id);
         }
@@ -155,8 +163,10 @@ impl<'tcx> MarkSymbolVisitor<'tcx> {
 
     fn mark_live_symbols(&mut self) {
         let mut scanned = FxHashSet::default();
-        while let Some(id) = self.worklist.pop() {
-            if !scanned.insert(id) {
+        while let Some((id, comes_from_allow_expect)) = self.worklist.pop() {
+            // An item seeded because of `#[expect(dead_code)]` is scanned again if it turns out
+            // to be live.
+            if !scanned.insert((id, comes_from_allow_expect)) {
                 continue;
             }
 
@@ -165,7 +175,11 @@ impl<'tcx> MarkSymbolVisitor<'tcx> {
             let id = self.struct_constructors.get(&id).cloned().unwrap_or(id);
 
             if let Some(node) = self.tcx.hir().find(id) {
-                self.live_symbols.insert(id);
+                // An item with `#[expect(dead_code)]` keeps what it uses alive, but is not
+                // live itself, so that the lint it expects is still emitted.
+                if comes_from_allow_expect == ComesFromAllowExpect::No {
+                    self.live_symbols.insert(id);
+                }
                 self.visit_node(node);
             }
         }
@@ -330,19 +344,19 @@ fn has_allow_dead_code_or_lang_attr(
     tcx: TyCtxt<'_>,
     id: hir::HirId,
     attrs: &[ast::Attribute],
-) -> bool {
+) -> Option<ComesFromAllowExpect> {
     if tcx.sess.contains_name(attrs, sym::lang) {
-        return true;
+        return Some(ComesFromAllowExpect::No);
     }
 
     // Stable attribute for #[lang = "panic_impl"]
     if tcx.sess.contains_name(attrs, sym::panic_handler) {
-        return true;
+        return Some(ComesFromAllowExpect::No);
     }
 
     // (To be) stable attribute for #[lang = "oom"]
     if tcx.sess.contains_name(attrs, sym::alloc_error_handler) {
-        return true;
+        return Some(ComesFromAllowExpect::No);
     }
 
     let def_id = tcx.hir().local_def_id(id);
@@ -351,10 +365,14 @@ fn has_allow_dead_code_or_lang_attr(
     // #[used], #[no_mangle], #[export_name], etc also keeps the item alive
     // forcefully, e.g., for placing it in a specific section.
     if cg_attrs.contains_extern_indicator() || cg_attrs.flags.contains(CodegenFnAttrFlags::USED) {
-        return true;
+        return Some(ComesFromAllowExpect::No);
     }
 
-    tcx.lint_level_at_node(lint::builtin::DEAD_CODE, id).0 == lint::Allow
+    match tcx.lint_level_at_node(lint::builtin::DEAD_CODE, id).0 {
+        lint::Expect => Some(ComesFromAllowExpect::Yes),
+        level if level.is_allow_like() => Some(ComesFromAllowExpect::No),
+        _ => None,
+    }
 }
 
 // This visitor seeds items that
@@ -365,13 +383,16 @@ fn has_allow_dead_code_or_lang_attr(
 //           For example, if both `f` and `g` are dead and `f` calls `g`,
 //           then annotating `f` with `#[allow(dead_code)]` will suppress
 //           warning for both `f` and `g`.
+//     * Item annotated with #[expect(dead_code)]
+//         - Like #[allow(dead_code)], except that the item itself is not
+//           marked as live, so that the expected lint is still emitted.
 //     * Item annotated with #[lang=".."]
 //         - This is because lang items are always callable from elsewhere.
 //   or
 //   2) We are not sure to be live or not
 //     * Implementations of traits and trait methods
 struct LifeSeeder<'k, 'tcx> {
-    worklist: Vec<hir::HirId>,
+    worklist: Vec<(hir::HirId, ComesFromAllowExpect)>,
     krate: &'k hir::Crate<'k>,
     tcx: TyCtxt<'tcx>,
     // see `MarkSymbolVisitor::struct_constructors`
@@ -381,13 +402,18 @@ struct LifeSeeder<'k, 'tcx> {
 impl<'v, 'k, 'tcx> ItemLikeVisitor<'v> for LifeSeeder<'k, 'tcx> {
     fn visit_item(&mut self, item: &hir::Item<'_>) {
         let allow_dead_code = has_allow_dead_code_or_lang_attr(self.tcx, item.hir_id, &item.attrs);
-        if allow_dead_code {
-            self.worklist.push(item.hir_id);
+        if let Some(comes_from_allow_expect) = allow_dead_code {
+            self.worklist.push((item.hir_id, comes_from_allow_expect));
         }
         match item.kind {
             hir::ItemKind::Enum(ref enum_def, _) => {
-                if allow_dead_code {
-                    self.worklist.extend(enum_def.variants.iter().map(|variant| variant.id));
+                if let Some(comes_from_allow_expect) = allow_dead_code {
+                    self.worklist.extend(
+                        enum_def
+                            .variants
+                            .iter()
+                            .map(|variant| (variant.id, comes_from_allow_expect)),
+                    );
                 }
 
                 for variant in enum_def.variants {
@@ -402,12 +428,12 @@ impl<'v, 'k, 'tcx> ItemLikeVisitor<'v> for LifeSeeder<'k, 'tcx> {
                     match trait_item.kind {
                         hir::TraitItemKind::Const(_, Some(_))
                         | hir::TraitItemKind::Fn(_, hir::TraitFn::Provided(_)) => {
-                            if has_allow_dead_code_or_lang_attr(
+                            if let Some(comes_from_allow_expect) = has_allow_dead_code_or_lang_attr(
                                 self.tcx,
                                 trait_item.hir_id,
                                 &trait_item.attrs,
                             ) {
-                                self.worklist.push(trait_item.hir_id);
+                                self.worklist.push((trait_item.hir_id, comes_from_allow_expect));
                             }
                         }
                         _ => {}
@@ -416,18 +442,18 @@ impl<'v, 'k, 'tcx> ItemLikeVisitor<'v> for LifeSeeder<'k, 'tcx> {
             }
             hir::ItemKind::Impl { ref of_trait, items, .. } => {
                 if of_trait.is_some() {
-                    self.worklist.push(item.hir_id);
+                    self.worklist.push((item.hir_id, ComesFromAllowExpect::No));
                 }
                 for impl_item_ref in items {
                     let impl_item = self.krate.impl_item(impl_item_ref.id);
-                    if of_trait.is_some()
-                        || has_allow_dead_code_or_lang_attr(
-                            self.tcx,
-                            impl_item.hir_id,
-                            &impl_item.attrs,
-                        )
-                    {
-                        self.worklist.push(impl_item_ref.id.hir_id);
+                    if of_trait.is_some() {
+                        self.worklist.push((impl_item_ref.id.hir_id, ComesFromAllowExpect::No));
+                    } else if let Some(comes_from_allow_expect) = has_allow_dead_code_or_lang_attr(
+                        self.tcx,
+                        impl_item.hir_id,
+                        &impl_item.attrs,
+                    ) {
+                        self.worklist.push((impl_item_ref.id.hir_id, comes_from_allow_expect));
                     }
                 }
             }
@@ -453,7 +479,7 @@ fn create_and_seed_worklist<'tcx>(
     tcx: TyCtxt<'tcx>,
     access_levels: &privacy::AccessLevels,
     krate: &hir::Crate<'_>,
-) -> (Vec<hir::HirId>, FxHashMap<hir::HirId, hir::HirId>) {
+) -> (Vec<(hir::HirId, ComesFromAllowExpect)>, FxHashMap<hir::HirId, hir::HirId>) {
     let worklist = access_levels
         .map
         .iter()
@@ -466,6 +492,7 @@ fn create_and_seed_worklist<'tcx>(
             // Seed entry point
             tcx.entry_fn(LOCAL_CRATE).map(|(def_id, _)| tcx.hir().local_def_id_to_hir_id(def_id)),
         )
+        .map(|id| (id, ComesFromAllowExpect::No))
         .collect::<Vec<_>>();
 
     // Seed implemented trait items
@@ -522,17 +549,20 @@ impl DeadVisitor<'tcx> {
         !field.is_positional()
             && !self.symbol_is_live(field.hir_id)
             && !field_type.is_phantom_data()
-            && !has_allow_dead_code_or_lang_attr(self.tcx, field.hir_id, &field.attrs)
+            && has_allow_dead_code_or_lang_attr(self.tcx, field.hir_id, &field.attrs)
+                != Some(ComesFromAllowExpect::No)
     }
 
     fn should_warn_about_variant(&mut self, variant: &hir::Variant<'_>) -> bool {
         !self.symbol_is_live(variant.id)
-            && !has_allow_dead_code_or_lang_attr(self.tcx, variant.id, &variant.attrs)
+            && has_allow_dead_code_or_lang_attr(self.tcx, variant.id, &variant.attrs)
+                != Some(ComesFromAllowExpect::No)
     }
 
     fn should_warn_about_foreign_item(&mut self, fi: &hir::ForeignItem<'_>) -> bool {
         !self.symbol_is_live(fi.hir_id)
-            && !has_allow_dead_code_or_lang_attr(self.tcx, fi.hir_id, &fi.attrs)
+            && has_allow_dead_code_or_lang_attr(self.tcx, fi.hir_id, &fi.attrs)
+                != Some(ComesFromAllowExpect::No)
     }
 
     // id := HIR id of an item's definition.
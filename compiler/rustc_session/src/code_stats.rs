@@ -2,6 +2,7 @@ use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::sync::Lock;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -45,9 +46,30 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+/// The cost of evaluating a constant or static, or of the code of a function
+/// executed during such an evaluation.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ConstEvalCost {
+    /// The number of terminators executed, as counted by the `const_eval_limit`.
+    pub steps: u64,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+    pub time: Duration,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConstEvalInfo {
+    pub description: String,
+    pub cost: ConstEvalCost,
+    /// The cost of each function executed by the evaluation, excluding the
+    /// cost of its callees.
+    pub functions: Vec<(String, ConstEvalCost)>,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    const_evals: Lock<Vec<ConstEvalInfo>>,
 }
 
 impl CodeStats {
@@ -77,6 +99,49 @@ impl CodeStats {
         self.type_sizes.borrow_mut().insert(info);
     }
 
+    pub fn record_const_eval<S: ToString>(
+        &self,
+        description: S,
+        cost: ConstEvalCost,
+        mut functions: Vec<(String, ConstEvalCost)>,
+    ) {
+        // Sort functions so the ones that executed the most are shown first.
+        functions.sort_by(|(name1, cost1), (name2, cost2)| match cost2.steps.cmp(&cost1.steps) {
+            Ordering::Equal => name1.cmp(name2),
+            other => other,
+        });
+        let info = ConstEvalInfo { description: description.to_string(), cost, functions };
+        self.const_evals.borrow_mut().push(info);
+    }
+
+    pub fn print_const_eval_profile(&self) {
+        let const_evals = self.const_evals.borrow();
+        let mut sorted: Vec<_> = const_evals.iter().collect();
+
+        // Primary sort: most steps first.
+        // Secondary sort: description (dictionary order)
+        sorted.sort_by(|info1, info2| match info2.cost.steps.cmp(&info1.cost.steps) {
+            Ordering::Equal => info1.description.cmp(&info2.description),
+            other => other,
+        });
+
+        let describe = |cost: &ConstEvalCost| {
+            format!(
+                "{} steps, {} allocations ({} bytes), {:.3}ms",
+                cost.steps,
+                cost.allocations,
+                cost.allocated_bytes,
+                cost.time.as_secs_f64() * 1000.0
+            )
+        };
+        for info in &sorted {
+            println!("const-eval-profile `{}`: {}", info.description, describe(&info.cost));
+            for (name, cost) in &info.functions {
+                println!("const-eval-profile     in `{}`: {}", name, describe(cost));
+            }
+        }
+    }
+
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
         let mut sorted: Vec<_> = type_sizes.iter().collect();
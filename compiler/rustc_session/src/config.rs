@@ -316,6 +316,12 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF log, written at the end of the session, for consumption
+    /// by code-scanning tools.
+    Sarif {
+        /// Render the log in a human readable way (with indents and newlines).
+        pretty: bool,
+    },
 }
 
 impl Default for ErrorOutputType {
@@ -615,6 +621,7 @@ impl Default for Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_rules: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -690,6 +697,8 @@ impl DebuggingOptions {
             report_delayed_bugs: self.report_delayed_bugs,
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
+            apply_suggestions: self.apply_suggestions,
+            group_diagnostics: self.group_diagnostics,
         }
     }
 }
@@ -1049,11 +1058,24 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
         ),
         opt::opt_s("", "sysroot", "Override the system root", "PATH"),
         opt::multi("Z", "", "Set internal debugging options", "FLAG"),
+        opt::multi(
+            "",
+            "force-warn",
+            "Set lint to warn, overriding any other level set in the source \
+             code or by `--cap-lints`",
+            "LINT",
+        ),
+        opt::opt(
+            "",
+            "lint-rules",
+            "Define lints that report the uses of items, from a lint rules file",
+            "PATH",
+        ),
         opt::opt_s(
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|sarif|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1091,7 +1113,7 @@ pub fn get_cmd_lint_options(
     let mut lint_opts_with_position = vec![];
     let mut describe_lints = false;
 
-    for &level in &[lint::Allow, lint::Warn, lint::Deny, lint::Forbid] {
+    for &level in &[lint::Allow, lint::Warn, lint::ForceWarn, lint::Deny, lint::Forbid] {
         for (passed_arg_pos, lint_name) in matches.opt_strs_pos(level.as_str()) {
             let arg_pos = if let lint::Forbid = level {
                 // HACK: forbid is always specified last, so it can't be overridden.
@@ -1117,7 +1139,10 @@ pub fn get_cmd_lint_options(
         .collect();
 
     let lint_cap = matches.opt_str("cap-lints").map(|cap| {
+        // `expect` only makes sense for attributes, which name the lints
+        // that are expected to fire, and `force-warn` only for single lints.
         lint::Level::from_str(&cap)
+            .filter(|&level| !matches!(level, lint::Level::Expect | lint::Level::ForceWarn))
             .unwrap_or_else(|| early_error(error_format, &format!("unknown lint level: `{}`", cap)))
     });
     (lint_opts, describe_lints, lint_cap)
@@ -1196,14 +1221,20 @@ pub fn parse_error_format(
             Some("human-annotate-rs") => {
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::AnnotateSnippet(color))
             }
+            Some("human-diff") => {
+                ErrorOutputType::HumanReadable(HumanReadableErrorType::Diff(color))
+            }
+            Some("html") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Html),
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif { pretty: false },
+            Some("pretty-sarif") => ErrorOutputType::Sarif { pretty: true },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
+                    "argument for `--error-format` must be `human`, `json`, `sarif` or \
                      `short` (instead was `{}`)",
                     arg
                 ),
@@ -1281,6 +1312,28 @@ fn check_debug_option_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::HumanReadable(HumanReadableErrorType::Diff(_)) = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=human-diff` is unstable",
+            );
+        }
+        if let ErrorOutputType::HumanReadable(HumanReadableErrorType::Html) = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=html` is unstable",
+            );
+        }
+        if let ErrorOutputType::Sarif { pretty } = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                if pretty {
+                    "`--error-format=pretty-sarif` is unstable"
+                } else {
+                    "`--error-format=sarif` is unstable"
+                },
+            );
+        }
     }
 }
 
@@ -1706,6 +1759,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         .unwrap_or_else(|e| early_error(error_format, &e[..]));
 
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_rules = matches.opt_str("lint-rules").map(PathBuf::from);
 
     let mut debugging_opts = build_debugging_options(matches, error_format);
     check_debug_option_stability(&debugging_opts, error_format, json_rendered);
@@ -1820,6 +1874,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_rules,
         describe_lints,
         output_types,
         search_paths,
@@ -8,6 +8,7 @@ use crate::utils::NativeLibKind;
 use rustc_target::spec::{CodeModel, LinkerFlavor, MergeFunctions, PanicStrategy};
 use rustc_target::spec::{RelocModel, RelroLevel, TargetTriple, TlsModel};
 
+use rustc_errors::Applicability;
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::Edition;
 use rustc_span::SourceFileHashAlgorithm;
@@ -90,6 +91,7 @@ top_level_options!(
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED],
         lint_cap: Option<lint::Level> [TRACKED],
+        lint_rules: Option<PathBuf> [TRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -261,6 +263,7 @@ macro_rules! options {
         pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
         pub const parse_unpretty: &str = "`string` or `string=string`";
         pub const parse_treat_err_as_bug: &str = "either no value or a number bigger than 0";
+        pub const parse_apply_suggestions: &str = "either `machine-applicable` or `maybe-incorrect`";
         pub const parse_lto: &str =
             "either a boolean (`yes`, `no`, `on`, `off`, etc), `thin`, `fat`, or omitted";
         pub const parse_linker_plugin_lto: &str =
@@ -592,6 +595,15 @@ macro_rules! options {
             }
         }
 
+        fn parse_apply_suggestions(slot: &mut Option<Applicability>, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("machine-applicable") => Some(Applicability::MachineApplicable),
+                Some("maybe-incorrect") => Some(Applicability::MaybeIncorrect),
+                _ => return false,
+            };
+            true
+        }
+
         fn parse_lto(slot: &mut LtoCli, v: Option<&str>) -> bool {
             if v.is_some() {
                 let mut bool_arg = None;
@@ -831,6 +843,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "only allow the listed language features to be enabled in code (space separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<Applicability> = (None, parse_apply_suggestions, [UNTRACKED],
+        "apply the suggestions of all diagnostics to the source files: only those that are \
+        certainly correct (`machine-applicable`), or also those that may be incorrect \
+        (`maybe-incorrect`)"),
     asm_comments: bool = (false, parse_bool, [TRACKED],
         "generate comments into the assembly (may change behavior) (default: no)"),
     ast_json: bool = (false, parse_bool, [UNTRACKED],
@@ -852,6 +868,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    const_eval_profile: bool = (false, parse_bool, [UNTRACKED],
+        "print the steps, allocations and time spent evaluating each constant and static, \
+        and in each function they call (default: no)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_macros: bool = (false, parse_bool, [TRACKED],
@@ -879,6 +898,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         `foo` matches all passes for functions whose name contains 'foo',
         `foo & ConstProp` only the 'ConstProp' pass for function names containing 'foo',
         `foo | bar` all passes for function names containing 'foo' or 'bar'."),
+    dump_mir_browser: bool = (false, parse_bool, [UNTRACKED],
+        "in addition to `.mir` files, create an `.html` file for each function with \
+        its MIR after each pass, the differences between consecutive passes, the \
+        source spans of the statements and the live locals (default: no)"),
     dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files with dataflow results \
         (default: no)"),
@@ -916,6 +939,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     graphviz_font: String = ("Courier, monospace".to_string(), parse_string, [UNTRACKED],
         "use the given `fontname` in graphviz output; can be overridden by setting \
         environment variable `RUSTC_GRAPHVIZ_FONT` (default: `Courier, monospace`)"),
+    group_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+        "report errors and warnings with the same code and origin once, with the locations \
+        of the others, at the end of the compilation (default: no)"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR (default: no)"),
     human_readable_cgu_names: bool = (false, parse_bool, [TRACKED],
@@ -931,6 +957,13 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "control whether `#[inline]` functions are in all CGUs"),
+    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED],
+        "enable MIR inlining (default: yes at `-O` without incremental compilation, and at \
+        `-Z mir-opt-level=2` and above)"),
+    inline_mir_hint_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "the cost threshold for inlining `#[inline]` functions in MIR (default: 100)"),
+    inline_mir_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "the cost threshold for inlining functions in MIR (default: 50)"),
     input_stats: bool = (false, parse_bool, [UNTRACKED],
         "gather statistics about the input (default: no)"),
     insert_sideeffect: bool = (false, parse_bool, [TRACKED],
@@ -952,6 +985,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    lint_config: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "set lint levels per crate, module and directory from a configuration file"),
     llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
         "generate JSON tracing data file from LLVM data (default: no)"),
     ls: bool = (false, parse_bool, [UNTRACKED],
@@ -1109,6 +1144,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
     trace_macros: bool = (false, parse_bool, [UNTRACKED],
         "for every macro invocation, print its name and arguments (default: no)"),
+    translation_locale: Option<String> = (None, parse_opt_string, [TRACKED],
+        "translate the diagnostics that have a catalog key to the given locale, using the \
+        catalog in the `share/locale` directory of the sysroot (default: English)"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
         "treat error number `val` that occurs as bug"),
     trim_diagnostic_paths: bool = (true, parse_bool, [UNTRACKED],
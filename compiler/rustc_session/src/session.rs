@@ -1,8 +1,10 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+pub use crate::code_stats::{ConstEvalCost, DataTypeKind, FieldInfo, SizeKind, VariantInfo};
 use crate::config::{self, CrateType, OutputType, PrintRequest, SanitizerSet, SwitchWithOptPath};
 use crate::filesearch;
+use crate::lint::config::LintConfig;
+use crate::lint::rules::LintRules;
 use crate::lint::{self, LintId};
 use crate::parse::ParseSess;
 use crate::search_paths::{PathKind, SearchPath};
@@ -19,8 +21,10 @@ use rustc_data_structures::sync::{
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
+use rustc_errors::html::HtmlEmitter;
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, DiagnosticId, ErrorReported};
 use rustc_lint_defs::FutureBreakage;
 use rustc_span::edition::Edition;
@@ -181,6 +185,12 @@ pub struct Session {
     /// Cap lint level specified by a driver specifically.
     pub driver_lint_caps: FxHashMap<lint::LintId, lint::Level>,
 
+    /// The lint configuration file given with `-Z lint-config`.
+    pub lint_config: Option<LintConfig>,
+
+    /// The lints defined by the lint rules file given with `--lint-rules`.
+    pub lint_rules: Option<LintRules>,
+
     /// `Span`s of trait methods that weren't found to avoid emitting object safety errors
     pub trait_methods_not_found: Lock<FxHashSet<Span>>,
 
@@ -303,6 +313,7 @@ impl Session {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.diagnostic().apply_suggestions(self.source_map());
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
@@ -362,6 +373,7 @@ impl Session {
     pub fn struct_span_warn<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_span_warn(sp, msg)
     }
+    #[track_caller]
     pub fn struct_span_warn_with_code<S: Into<MultiSpan>>(
         &self,
         sp: S,
@@ -373,15 +385,37 @@ impl Session {
     pub fn struct_warn(&self, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_warn(msg)
     }
+    pub fn struct_span_force_warn<S: Into<MultiSpan>>(
+        &self,
+        sp: S,
+        msg: &str,
+    ) -> DiagnosticBuilder<'_> {
+        self.diagnostic().struct_span_force_warn(sp, msg)
+    }
+    pub fn struct_force_warn(&self, msg: &str) -> DiagnosticBuilder<'_> {
+        self.diagnostic().struct_force_warn(msg)
+    }
     pub fn struct_span_allow<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_span_allow(sp, msg)
     }
     pub fn struct_allow(&self, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_allow(msg)
     }
+    pub fn struct_span_expect<S: Into<MultiSpan>>(
+        &self,
+        sp: S,
+        msg: &str,
+        id: lint::LintExpectationId,
+    ) -> DiagnosticBuilder<'_> {
+        self.diagnostic().struct_span_expect(sp, msg, id)
+    }
+    pub fn struct_expect(&self, msg: &str, id: lint::LintExpectationId) -> DiagnosticBuilder<'_> {
+        self.diagnostic().struct_expect(msg, id)
+    }
     pub fn struct_span_err<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_span_err(sp, msg)
     }
+    #[track_caller]
     pub fn struct_span_err_with_code<S: Into<MultiSpan>>(
         &self,
         sp: S,
@@ -394,12 +428,14 @@ impl Session {
     pub fn struct_err(&self, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_err(msg)
     }
+    #[track_caller]
     pub fn struct_err_with_code(&self, msg: &str, code: DiagnosticId) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_err_with_code(msg, code)
     }
     pub fn struct_span_fatal<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_span_fatal(sp, msg)
     }
+    #[track_caller]
     pub fn struct_span_fatal_with_code<S: Into<MultiSpan>>(
         &self,
         sp: S,
@@ -415,6 +451,7 @@ impl Session {
     pub fn span_fatal<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> ! {
         self.diagnostic().span_fatal(sp, msg).raise()
     }
+    #[track_caller]
     pub fn span_fatal_with_code<S: Into<MultiSpan>>(
         &self,
         sp: S,
@@ -436,6 +473,7 @@ impl Session {
     pub fn span_err<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
         self.diagnostic().span_err(sp, msg)
     }
+    #[track_caller]
     pub fn span_err_with_code<S: Into<MultiSpan>>(&self, sp: S, msg: &str, code: DiagnosticId) {
         self.diagnostic().span_err_with_code(sp, &msg, code)
     }
@@ -478,6 +516,7 @@ impl Session {
     pub fn span_warn<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
         self.diagnostic().span_warn(sp, msg)
     }
+    #[track_caller]
     pub fn span_warn_with_code<S: Into<MultiSpan>>(&self, sp: S, msg: &str, code: DiagnosticId) {
         self.diagnostic().span_warn_with_code(sp, msg, code)
     }
@@ -1218,6 +1257,12 @@ fn default_emitter(
                 let emitter =
                     AnnotateSnippetEmitterWriter::new(Some(source_map), short, macro_backtrace);
                 Box::new(emitter.ui_testing(sopts.debugging_opts.ui_testing))
+            } else if let HumanReadableErrorType::Html = kind {
+                let emitter = match dst {
+                    None => HtmlEmitter::stderr(Some(source_map), macro_backtrace),
+                    Some(dst) => HtmlEmitter::new(dst, Some(source_map), macro_backtrace),
+                };
+                Box::new(emitter.ui_testing(sopts.debugging_opts.ui_testing))
             } else {
                 let emitter = match dst {
                     None => EmitterWriter::stderr(
@@ -1238,7 +1283,11 @@ fn default_emitter(
                         macro_backtrace,
                     ),
                 };
-                Box::new(emitter.ui_testing(sopts.debugging_opts.ui_testing))
+                Box::new(
+                    emitter
+                        .diff_suggestions(matches!(kind, HumanReadableErrorType::Diff(_)))
+                        .ui_testing(sopts.debugging_opts.ui_testing),
+                )
             }
         }
         (config::ErrorOutputType::Json { pretty, json_rendered }, None) => Box::new(
@@ -1264,6 +1313,14 @@ fn default_emitter(
             )
             .ui_testing(sopts.debugging_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif { pretty }, None) => Box::new(
+            SarifEmitter::stderr(Some(registry), source_map, pretty)
+                .ui_testing(sopts.debugging_opts.ui_testing),
+        ),
+        (config::ErrorOutputType::Sarif { pretty }, Some(dst)) => Box::new(
+            SarifEmitter::new(dst, Some(registry), source_map, pretty)
+                .ui_testing(sopts.debugging_opts.ui_testing),
+        ),
     }
 }
 
@@ -1320,10 +1377,27 @@ pub fn build_session(
     ));
     let emitter = default_emitter(&sopts, registry, source_map.clone(), write_dest);
 
+    let sysroot = match &sopts.maybe_sysroot {
+        Some(sysroot) => sysroot.clone(),
+        None => filesearch::get_or_default_sysroot(),
+    };
+
+    let lint_config = sopts.debugging_opts.lint_config.as_ref().map(|path| {
+        LintConfig::load(path).unwrap_or_else(|e| early_error(sopts.error_format, &e.to_string()))
+    });
+    let lint_rules = sopts.lint_rules.as_ref().map(|path| {
+        LintRules::load(path).unwrap_or_else(|e| early_error(sopts.error_format, &e.to_string()))
+    });
+
+    let catalog = sopts.debugging_opts.translation_locale.as_ref().map(|locale| {
+        rustc_errors::Catalog::load(&sysroot, locale)
+            .unwrap_or_else(|e| early_error(sopts.error_format, &e.to_string()))
+    });
     let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(
         emitter,
         sopts.debugging_opts.diagnostic_handler_flags(can_emit_warnings),
-    );
+    )
+    .with_catalog(catalog);
 
     let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.debugging_opts.self_profile
     {
@@ -1347,10 +1421,11 @@ pub fn build_session(
     };
 
     let parse_sess = ParseSess::with_span_handler(span_diagnostic, source_map);
-    let sysroot = match &sopts.maybe_sysroot {
-        Some(sysroot) => sysroot.clone(),
-        None => filesearch::get_or_default_sysroot(),
-    };
+    // Changes to the lint configuration or rules change the output, so the
+    // build has to be rerun like for a changed source file.
+    for path in sopts.debugging_opts.lint_config.iter().chain(&sopts.lint_rules) {
+        parse_sess.file_depinfo.borrow_mut().insert(Symbol::intern(&path.to_string_lossy()));
+    }
 
     let host_triple = config::host_triple();
     let target_triple = sopts.target_triple.triple();
@@ -1457,6 +1532,8 @@ pub fn build_session(
         print_fuel,
         jobserver: jobserver::client(),
         driver_lint_caps,
+        lint_config,
+        lint_rules,
         trait_methods_not_found: Lock::new(Default::default()),
         confused_type_with_std_module: Lock::new(Default::default()),
         system_library_path: OneThread::new(RefCell::new(Default::default())),
@@ -1616,6 +1693,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, false))
         }
+        config::ErrorOutputType::Sarif { pretty } => Box::new(SarifEmitter::basic(pretty)),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_fatal(msg).emit();
@@ -1631,6 +1709,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, false))
         }
+        config::ErrorOutputType::Sarif { pretty } => Box::new(SarifEmitter::basic(pretty)),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_warn(msg).emit();
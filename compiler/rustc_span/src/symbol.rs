@@ -352,8 +352,10 @@ symbols! {
         concat_idents,
         conservative_impl_trait,
         console,
+        const_allocate,
         const_compare_raw_pointers,
         const_constructor,
+        const_deallocate,
         const_eval_limit,
         const_evaluatable_checked,
         const_extern_fn,
@@ -891,6 +893,7 @@ symbols! {
         roundf64,
         rt,
         rtm_target_feature,
+        rules,
         rust,
         rust_2015_preview,
         rust_2018_preview,
@@ -1039,6 +1042,7 @@ symbols! {
         sized,
         slice,
         slice_alloc,
+        slice_len_fn,
         slice_patterns,
         slice_u8,
         slice_u8_alloc,
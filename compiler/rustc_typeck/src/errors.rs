@@ -4,6 +4,7 @@ use rustc_span::{symbol::Ident, Span, Symbol};
 
 #[derive(SessionDiagnostic)]
 #[error = "E0062"]
+#[slug = "typeck-field-multiply-specified-in-initializer"]
 pub struct FieldMultiplySpecifiedInInitializer {
     #[message = "field `{ident}` specified more than once"]
     #[label = "used more than once"]
@@ -15,6 +16,7 @@ pub struct FieldMultiplySpecifiedInInitializer {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0092"]
+#[slug = "typeck-unrecognized-atomic-operation"]
 pub struct UnrecognizedAtomicOperation<'a> {
     #[message = "unrecognized atomic operation function: `{op}`"]
     #[label = "unrecognized atomic operation"]
@@ -24,6 +26,7 @@ pub struct UnrecognizedAtomicOperation<'a> {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0094"]
+#[slug = "typeck-wrong-number-of-type-arguments-to-instrinsic"]
 pub struct WrongNumberOfTypeArgumentsToInstrinsic {
     #[message = "intrinsic has wrong number of type \
                          parameters: found {found}, expected {expected}"]
@@ -35,6 +38,7 @@ pub struct WrongNumberOfTypeArgumentsToInstrinsic {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0093"]
+#[slug = "typeck-unrecognized-intrinsic-function"]
 pub struct UnrecognizedIntrinsicFunction {
     #[message = "unrecognized intrinsic function: `{name}`"]
     #[label = "unrecognized intrinsic"]
@@ -44,6 +48,7 @@ pub struct UnrecognizedIntrinsicFunction {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0195"]
+#[slug = "typeck-lifetimes-or-bounds-mismatch-on-trait"]
 pub struct LifetimesOrBoundsMismatchOnTrait {
     #[message = "lifetime parameters or bounds on {item_kind} `{ident}` do not match the trait declaration"]
     #[label = "lifetimes do not match {item_kind} in trait"]
@@ -56,6 +61,7 @@ pub struct LifetimesOrBoundsMismatchOnTrait {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0120"]
+#[slug = "typeck-drop-impl-on-wrong-item"]
 pub struct DropImplOnWrongItem {
     #[message = "the `Drop` trait may only be implemented for structs, enums, and unions"]
     #[label = "must be a struct, enum, or union"]
@@ -64,6 +70,7 @@ pub struct DropImplOnWrongItem {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0124"]
+#[slug = "typeck-field-already-declared"]
 pub struct FieldAlreadyDeclared {
     pub field_name: Ident,
     #[message = "field `{field_name}` is already declared"]
@@ -75,6 +82,7 @@ pub struct FieldAlreadyDeclared {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0184"]
+#[slug = "typeck-copy-impl-on-type-with-dtor"]
 pub struct CopyImplOnTypeWithDtor {
     #[message = "the trait `Copy` may not be implemented for this type; the \
                               type has a destructor"]
@@ -84,6 +92,7 @@ pub struct CopyImplOnTypeWithDtor {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0202"]
+#[slug = "typeck-assoc-type-on-inherent-impl"]
 pub struct AssocTypeOnInherentImpl {
     #[message = "associated types are not yet supported in inherent impls (see #8995)"]
     pub span: Span,
@@ -91,6 +100,7 @@ pub struct AssocTypeOnInherentImpl {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0203"]
+#[slug = "typeck-multiple-relaxed-default-bounds"]
 pub struct MultipleRelaxedDefaultBounds {
     #[message = "type parameter has more than one relaxed default bound, only one is supported"]
     pub span: Span,
@@ -98,6 +108,7 @@ pub struct MultipleRelaxedDefaultBounds {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0206"]
+#[slug = "typeck-copy-impl-on-non-adt"]
 pub struct CopyImplOnNonAdt {
     #[message = "the trait `Copy` may not be implemented for this type"]
     #[label = "type is not a structure or enumeration"]
@@ -106,6 +117,7 @@ pub struct CopyImplOnNonAdt {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0224"]
+#[slug = "typeck-trait-object-declared-with-no-traits"]
 pub struct TraitObjectDeclaredWithNoTraits {
     #[message = "at least one trait is required for an object type"]
     pub span: Span,
@@ -113,6 +125,7 @@ pub struct TraitObjectDeclaredWithNoTraits {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0227"]
+#[slug = "typeck-ambiguous-lifetime-bound"]
 pub struct AmbiguousLifetimeBound {
     #[message = "ambiguous lifetime bound, explicit lifetime bound required"]
     pub span: Span,
@@ -120,6 +133,7 @@ pub struct AmbiguousLifetimeBound {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0229"]
+#[slug = "typeck-assoc-type-binding-not-allowed"]
 pub struct AssocTypeBindingNotAllowed {
     #[message = "associated type bindings are not allowed here"]
     #[label = "associated type not allowed here"]
@@ -128,6 +142,7 @@ pub struct AssocTypeBindingNotAllowed {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0439"]
+#[slug = "typeck-simd-shuffle-missing-length"]
 pub struct SimdShuffleMissingLength {
     #[message = "invalid `simd_shuffle`, needs length: `{name}`"]
     pub span: Span,
@@ -136,6 +151,7 @@ pub struct SimdShuffleMissingLength {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0436"]
+#[slug = "typeck-functional-record-update-on-non-struct"]
 pub struct FunctionalRecordUpdateOnNonStruct {
     #[message = "functional record update syntax requires a struct"]
     pub span: Span,
@@ -143,6 +159,7 @@ pub struct FunctionalRecordUpdateOnNonStruct {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0516"]
+#[slug = "typeck-typeof-reserved-keyword-used"]
 pub struct TypeofReservedKeywordUsed {
     #[message = "`typeof` is a reserved keyword but unimplemented"]
     #[label = "reserved keyword"]
@@ -151,6 +168,7 @@ pub struct TypeofReservedKeywordUsed {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0572"]
+#[slug = "typeck-return-stmt-outside-of-fn-body"]
 pub struct ReturnStmtOutsideOfFnBody {
     #[message = "return statement outside of function body"]
     pub span: Span,
@@ -158,6 +176,7 @@ pub struct ReturnStmtOutsideOfFnBody {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0627"]
+#[slug = "typeck-yield-expr-outside-of-generator"]
 pub struct YieldExprOutsideOfGenerator {
     #[message = "yield expression outside of generator literal"]
     pub span: Span,
@@ -165,6 +184,7 @@ pub struct YieldExprOutsideOfGenerator {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0639"]
+#[slug = "typeck-struct-expr-non-exhaustive"]
 pub struct StructExprNonExhaustive {
     #[message = "cannot create non-exhaustive {what} using struct expression"]
     pub span: Span,
@@ -173,6 +193,7 @@ pub struct StructExprNonExhaustive {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0699"]
+#[slug = "typeck-method-call-on-unknown-type"]
 pub struct MethodCallOnUnknownType {
     #[message = "the type of this value must be known to call a method on a raw pointer on it"]
     pub span: Span,
@@ -180,6 +201,7 @@ pub struct MethodCallOnUnknownType {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0719"]
+#[slug = "typeck-value-of-associated-struct-already-specified"]
 pub struct ValueOfAssociatedStructAlreadySpecified {
     #[message = "the value of the associated type `{item_name}` (from trait `{def_path}`) is already specified"]
     #[label = "re-bound here"]
@@ -192,6 +214,7 @@ pub struct ValueOfAssociatedStructAlreadySpecified {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0745"]
+#[slug = "typeck-address-of-temporary-taken"]
 pub struct AddressOfTemporaryTaken {
     #[message = "cannot take address of a temporary"]
     #[label = "temporary value"]
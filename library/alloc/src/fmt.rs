@@ -541,6 +541,8 @@ pub use core::fmt::{Formatter, Result, Write};
 pub use core::fmt::{LowerExp, UpperExp};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use core::fmt::{LowerHex, Pointer, UpperHex};
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+pub use core::fmt::{formatted_len, write_to_slice, SliceWriter};
 
 use crate::string;
 
@@ -173,6 +173,8 @@ pub mod prelude;
 pub mod raw_vec;
 pub mod rc;
 pub mod slice;
+pub mod small_string;
+pub mod small_vec;
 pub mod str;
 pub mod string;
 #[cfg(target_has_atomic = "ptr")]
@@ -0,0 +1,396 @@
+//! A UTF-8 encoded, growable string that stores short contents inline,
+//! written `SmallString<N>`.
+//!
+//! A [`SmallString`] is to [`String`] what [`SmallVec`] is to [`Vec`]: up to
+//! `N` bytes are stored directly inside the value, and longer contents are
+//! moved to the heap.
+//!
+//! # Examples
+//!
+//! ```
+//! #![feature(small_vec)]
+//!
+//! use std::small_string::SmallString;
+//!
+//! let mut s: SmallString<8> = SmallString::from("hello");
+//! assert!(!s.spilled());
+//!
+//! s.push_str(", world");
+//! assert!(s.spilled());
+//! assert_eq!(s, "hello, world");
+//! ```
+//!
+//! [`String`]: crate::string::String
+//! [`SmallVec`]: crate::small_vec::SmallVec
+//! [`Vec`]: crate::vec::Vec
+
+#![unstable(feature = "small_vec", issue = "none")]
+
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut};
+use core::str;
+
+use crate::small_vec::SmallVec;
+use crate::string::String;
+
+/// A UTF-8 encoded, growable string that stores up to `N` bytes inline
+/// before spilling to the heap.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Default, Clone, PartialOrd, Eq, Ord)]
+pub struct SmallString<const N: usize> {
+    vec: SmallVec<u8, N>,
+}
+
+impl<const N: usize> SmallString<N> {
+    /// Creates a new, empty `SmallString` using its inline storage.
+    #[inline]
+    pub const fn new() -> Self {
+        SmallString { vec: SmallVec::new() }
+    }
+
+    /// Creates a new, empty `SmallString` with room for at least `capacity`
+    /// bytes.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SmallString { vec: SmallVec::with_capacity(capacity) }
+    }
+
+    /// Returns this string's capacity, in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns `true` if the contents have been moved to a heap allocation.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.vec.spilled()
+    }
+
+    /// Returns the length of this string, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if this string has a length of zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Extracts a string slice containing the entire string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the contents are always valid UTF-8.
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    /// Converts a `SmallString` into a mutable string slice.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: the contents are always valid UTF-8.
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    /// Returns a byte slice of this string's contents.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    /// Shrinks the capacity of this string as much as possible, moving the
+    /// contents back inline if they fit.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit()
+    }
+
+    /// Appends the given [`char`] to the end of this string.
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        match ch.len_utf8() {
+            1 => self.vec.push(ch as u8),
+            _ => self.vec.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+
+    /// Appends a given string slice onto the end of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(small_vec)]
+    ///
+    /// use std::small_string::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::new();
+    /// s.push_str("foo");
+    /// s.push_str("bar");
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    pub fn push_str(&mut self, string: &str) {
+        self.vec.extend_from_slice(string.as_bytes())
+    }
+
+    /// Removes the last character from the string buffer and returns it, or
+    /// [`None`] if this string is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().rev().next()?;
+        let newlen = self.len() - ch.len_utf8();
+        // SAFETY: `newlen` is the char boundary before the last character.
+        unsafe {
+            self.vec.set_len(newlen);
+        }
+        Some(ch)
+    }
+
+    /// Shortens this string to the specified length.
+    ///
+    /// If `new_len` is greater than the string's current length, this has no
+    /// effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            assert!(self.as_str().is_char_boundary(new_len));
+            self.vec.truncate(new_len)
+        }
+    }
+
+    /// Truncates this string, removing all contents.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Converts this string into a [`String`], reusing the heap buffer if the
+    /// contents have already spilled.
+    ///
+    /// [`String`]: crate::string::String
+    #[inline]
+    pub fn into_string(self) -> String {
+        // SAFETY: the contents are always valid UTF-8.
+        unsafe { String::from_utf8_unchecked(self.vec.into_vec()) }
+    }
+}
+
+impl<const N: usize> Deref for SmallString<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for SmallString<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for SmallString<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for SmallString<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> Borrow<str> for SmallString<N> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> BorrowMut<str> for SmallString<N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl<const N: usize> fmt::Display for SmallString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for SmallString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<const N: usize> fmt::Write for SmallString<N> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<const N: usize> Hash for SmallString<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        (**self).hash(hasher)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<SmallString<M>> for SmallString<N> {
+    #[inline]
+    fn eq(&self, other: &SmallString<M>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+macro_rules! impl_eq {
+    ($lhs:ty, $rhs: ty) => {
+        #[allow(unused_lifetimes)]
+        impl<'a, 'b, const N: usize> PartialEq<$rhs> for $lhs {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+
+        #[allow(unused_lifetimes)]
+        impl<'a, 'b, const N: usize> PartialEq<$lhs> for $rhs {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+    };
+}
+
+impl_eq! { SmallString<N>, str }
+impl_eq! { SmallString<N>, &'a str }
+impl_eq! { SmallString<N>, String }
+
+impl<const N: usize> PartialOrd<str> for SmallString<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&self[..], other)
+    }
+}
+
+impl<const N: usize> Extend<char> for SmallString<N> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iterator = iter.into_iter();
+        let (lower_bound, _) = iterator.size_hint();
+        self.reserve(lower_bound);
+        iterator.for_each(move |c| self.push(c));
+    }
+
+    #[inline]
+    fn extend_one(&mut self, c: char) {
+        self.push(c);
+    }
+
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+}
+
+impl<'a, const N: usize> Extend<&'a char> for SmallString<N> {
+    fn extend<I: IntoIterator<Item = &'a char>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+
+    #[inline]
+    fn extend_one(&mut self, &c: &'a char) {
+        self.push(c);
+    }
+
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+}
+
+impl<'a, const N: usize> Extend<&'a str> for SmallString<N> {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |s| self.push_str(s));
+    }
+
+    #[inline]
+    fn extend_one(&mut self, s: &'a str) {
+        self.push_str(s);
+    }
+}
+
+impl<const N: usize> FromIterator<char> for SmallString<N> {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut buf = SmallString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl<'a, const N: usize> FromIterator<&'a str> for SmallString<N> {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut buf = SmallString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl<const N: usize> From<&str> for SmallString<N> {
+    #[inline]
+    fn from(s: &str) -> Self {
+        let mut buf = SmallString::new();
+        buf.push_str(s);
+        buf
+    }
+}
+
+impl<const N: usize> From<String> for SmallString<N> {
+    /// Converts a [`String`] into a `SmallString`, reusing its allocation
+    /// unless the contents fit inline.
+    #[inline]
+    fn from(s: String) -> Self {
+        SmallString { vec: SmallVec::from(s.into_bytes()) }
+    }
+}
+
+impl<const N: usize> From<SmallString<N>> for String {
+    #[inline]
+    fn from(s: SmallString<N>) -> String {
+        s.into_string()
+    }
+}
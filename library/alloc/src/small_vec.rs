@@ -0,0 +1,939 @@
+//! A growable array type that stores a small number of elements inline,
+//! written `SmallVec<T, N>`.
+//!
+//! A [`SmallVec`] holds up to `N` elements directly inside the value, with no
+//! heap allocation. Pushing more elements than that moves the contents into a
+//! heap buffer managed by [`RawVec`], after which the vector behaves like a
+//! [`Vec`]. Through [`Deref`] to `[T]` it offers the same slice API as
+//! [`Vec`], along with the common mutation methods such as [`push`],
+//! [`insert`], [`retain`] and [`drain`].
+//!
+//! # Examples
+//!
+//! ```
+//! #![feature(small_vec)]
+//!
+//! use std::small_vec::SmallVec;
+//!
+//! let mut v: SmallVec<i32, 4> = SmallVec::new();
+//! v.extend(1..=4);
+//! assert!(!v.spilled());
+//!
+//! v.push(5);
+//! assert!(v.spilled());
+//! assert_eq!(v, [1, 2, 3, 4, 5]);
+//! ```
+//!
+//! [`Vec`]: crate::vec::Vec
+//! [`push`]: SmallVec::push
+//! [`insert`]: SmallVec::insert
+//! [`retain`]: SmallVec::retain
+//! [`drain`]: SmallVec::drain
+
+#![unstable(feature = "small_vec", issue = "none")]
+
+use core::cmp::{self, Ordering};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{self, Deref, DerefMut, Range, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use crate::raw_vec::RawVec;
+use crate::vec::Vec;
+
+/// A vector that stores up to `N` elements inline before spilling to the
+/// heap.
+///
+/// See the [module-level documentation](self) for more.
+pub struct SmallVec<T, const N: usize> {
+    len: usize,
+    data: Data<T, N>,
+}
+
+enum Data<T, const N: usize> {
+    Inline(MaybeUninit<[T; N]>),
+    Heap(RawVec<T>),
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    /// Constructs a new, empty `SmallVec<T, N>` using its inline storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(small_vec)]
+    ///
+    /// use std::small_vec::SmallVec;
+    ///
+    /// let v: SmallVec<u8, 16> = SmallVec::new();
+    /// assert_eq!(v.capacity(), 16);
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        SmallVec { len: 0, data: Data::Inline(MaybeUninit::uninit()) }
+    }
+
+    /// Constructs a new, empty `SmallVec<T, N>` that can hold at least
+    /// `capacity` elements without reallocating.
+    ///
+    /// The heap is only used if `capacity` is greater than `N`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut v = SmallVec::new();
+        v.reserve(capacity);
+        v
+    }
+
+    /// Returns the number of elements the vector can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match self.data {
+            Data::Inline(_) => {
+                if mem::size_of::<T>() == 0 {
+                    usize::MAX
+                } else {
+                    N
+                }
+            }
+            Data::Heap(ref buf) => buf.capacity(),
+        }
+    }
+
+    /// Returns `true` if the contents have been moved to a heap allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(small_vec)]
+    ///
+    /// use std::small_vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<u8, 1> = SmallVec::new();
+    /// v.push(1);
+    /// assert!(!v.spilled());
+    /// v.push(2);
+    /// assert!(v.spilled());
+    /// ```
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        match self.data {
+            Data::Inline(ref buf) => buf.as_ptr() as *const T,
+            Data::Heap(ref buf) => buf.ptr(),
+        }
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        match self.data {
+            Data::Inline(ref mut buf) => buf.as_mut_ptr() as *mut T,
+            Data::Heap(ref buf) => buf.ptr(),
+        }
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` elements of the buffer are initialized.
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` elements of the buffer are initialized.
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`capacity()`].
+    /// - The elements at `old_len..new_len` must be initialized.
+    ///
+    /// [`capacity()`]: SmallVec::capacity
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        self.len = new_len;
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// If the inline storage is too small, the contents are moved to the
+    /// heap, with the same amortized growth strategy as [`Vec::reserve`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        match self.data {
+            Data::Heap(ref mut buf) => buf.reserve(self.len, additional),
+            Data::Inline(_) => {
+                if self.capacity().wrapping_sub(self.len) < additional {
+                    // Grow at least geometrically, like `RawVec::reserve`.
+                    let required = self.required_capacity(additional);
+                    self.spill(cmp::max(required, 2 * N));
+                }
+            }
+        }
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        match self.data {
+            Data::Heap(ref mut buf) => buf.reserve_exact(self.len, additional),
+            Data::Inline(_) => {
+                if self.capacity().wrapping_sub(self.len) < additional {
+                    let required = self.required_capacity(additional);
+                    self.spill(required);
+                }
+            }
+        }
+    }
+
+    fn required_capacity(&self, additional: usize) -> usize {
+        self.len.checked_add(additional).expect("capacity overflow")
+    }
+
+    /// Moves the inline contents into a heap buffer of `capacity` elements.
+    #[cold]
+    fn spill(&mut self, capacity: usize) {
+        let buf = RawVec::with_capacity(capacity);
+        // SAFETY: the new buffer holds at least `len` elements and does not
+        // overlap with the inline storage. The inline elements are moved, not
+        // copied, because the inline storage is never dropped.
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr(), buf.ptr(), self.len);
+        }
+        self.data = Data::Heap(buf);
+    }
+
+    /// Shrinks the capacity of the vector as much as possible, moving the
+    /// contents back inline if they fit.
+    pub fn shrink_to_fit(&mut self) {
+        if let Data::Heap(ref mut buf) = self.data {
+            if self.len <= N && mem::size_of::<T>() != 0 {
+                let mut inline = MaybeUninit::<[T; N]>::uninit();
+                // SAFETY: the inline storage holds `N >= len` elements. The
+                // heap elements are moved, and dropping the `RawVec` only
+                // deallocates.
+                unsafe {
+                    ptr::copy_nonoverlapping(buf.ptr(), inline.as_mut_ptr() as *mut T, self.len);
+                }
+                self.data = Data::Inline(inline);
+            } else {
+                buf.shrink_to_fit(self.len);
+            }
+        }
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(small_vec)]
+    ///
+    /// use std::small_vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<i32, 2> = SmallVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// assert_eq!(v, [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        // SAFETY: there is room for at least one more element.
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(self.len), value);
+        }
+        self.len += 1;
+    }
+
+    /// Removes the last element from the vector and returns it, or [`None`]
+    /// if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            // SAFETY: the element at the old `len - 1` was initialized and is
+            // no longer considered part of the vector.
+            unsafe { Some(ptr::read(self.as_ptr().add(self.len))) }
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after
+    /// it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, element: T) {
+        let len = self.len;
+        assert!(index <= len, "insertion index (is {}) should be <= len (is {})", index, len);
+
+        if len == self.capacity() {
+            self.reserve(1);
+        }
+        // SAFETY: there is room for one more element, and `index <= len`.
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.offset(1), len - index);
+            ptr::write(p, element);
+        }
+        self.len = len + 1;
+    }
+
+    /// Removes and returns the element at position `index`, shifting all
+    /// elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len, "removal index (is {}) should be < len (is {})", index, len);
+
+        // SAFETY: `index < len`, so the element is initialized, and the tail
+        // is shifted over it before the length is reduced.
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let ret = ptr::read(p);
+            ptr::copy(p.offset(1), p, len - index - 1);
+            self.len = len - 1;
+            ret
+        }
+    }
+
+    /// Removes an element from the vector and returns it, replacing it with
+    /// the last element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len, "swap_remove index (is {}) should be < len (is {})", index, len);
+
+        // SAFETY: both `index` and `len - 1` are in bounds; the last element
+        // is moved into the hole before the length is reduced.
+        unsafe {
+            let last = ptr::read(self.as_ptr().add(len - 1));
+            let hole = self.as_mut_ptr().add(index);
+            self.len = len - 1;
+            ptr::replace(hole, last)
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
+    pub fn truncate(&mut self, len: usize) {
+        if len > self.len {
+            return;
+        }
+        // SAFETY: the elements in `len..self.len` are initialized, and the
+        // length is updated first so that a panicking destructor cannot cause
+        // a double drop.
+        unsafe {
+            let remaining_len = self.len - len;
+            let s = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), remaining_len);
+            self.len = len;
+            ptr::drop_in_place(s);
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// This has no effect on the allocated capacity.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns
+    /// `false`. This method operates in place, visiting each element exactly
+    /// once in the original order, and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(small_vec)]
+    ///
+    /// use std::small_vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<i32, 4> = (1..=6).collect();
+    /// v.retain(|&x| x % 2 == 0);
+    /// assert_eq!(v, [2, 4, 6]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len();
+        let mut del = 0;
+        {
+            let v = &mut **self;
+
+            for i in 0..len {
+                if !f(&v[i]) {
+                    del += 1;
+                } else if del > 0 {
+                    v.swap(i - del, i);
+                }
+            }
+        }
+        if del > 0 {
+            self.truncate(len - del);
+        }
+    }
+
+    /// Creates a draining iterator that removes the specified range in the
+    /// vector and yields the removed items.
+    ///
+    /// As with [`Vec::drain`], the range is removed even if the iterator is
+    /// not fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the
+    /// end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(small_vec)]
+    ///
+    /// use std::small_vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<i32, 4> = (1..=3).collect();
+    /// let u: Vec<_> = v.drain(1..).collect();
+    /// assert_eq!(v, [1]);
+    /// assert_eq!(u, [2, 3]);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        // Memory safety
+        //
+        // As in `Vec::drain`, the length is shortened to the start of the
+        // range first, so that no moved-from elements are reachable if the
+        // `Drain` is leaked. Its destructor moves the tail back.
+        let len = self.len();
+        let Range { start, end } = range.assert_len(len);
+
+        unsafe {
+            self.set_len(start);
+            let range_slice = slice::from_raw_parts_mut(self.as_mut_ptr().add(start), end - start);
+            Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter: range_slice.iter(),
+                vec: NonNull::from(self),
+            }
+        }
+    }
+
+    /// Converts the vector into a [`Vec`], reusing the heap buffer if the
+    /// contents have already spilled.
+    ///
+    /// [`Vec`]: crate::vec::Vec
+    pub fn into_vec(self) -> Vec<T> {
+        let mut this = ManuallyDrop::new(self);
+        let len = this.len;
+        // SAFETY: `this` is never used again and its destructor does not
+        // run, so the elements and the heap buffer are moved out exactly
+        // once.
+        unsafe {
+            match ptr::read(&this.data) {
+                Data::Heap(buf) => {
+                    let buf = ManuallyDrop::new(buf);
+                    Vec::from_raw_parts(buf.ptr(), len, buf.capacity())
+                }
+                Data::Inline(_) => {
+                    let mut vec = Vec::with_capacity(len);
+                    ptr::copy_nonoverlapping(this.as_mut_ptr(), vec.as_mut_ptr(), len);
+                    vec.set_len(len);
+                    vec
+                }
+            }
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> SmallVec<T, N> {
+    /// Clones and appends all elements in a slice to the vector.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        self.reserve(other.len());
+        for value in other {
+            self.push(value.clone());
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: the first `len` elements are initialized. The buffer itself
+        // is released when the `RawVec`, if any, is dropped afterwards.
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for SmallVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for SmallVec<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, I: slice::SliceIndex<[T]>, const N: usize> ops::Index<I> for SmallVec<T, N> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        ops::Index::index(&**self, index)
+    }
+}
+
+impl<T, I: slice::SliceIndex<[T]>, const N: usize> ops::IndexMut<I> for SmallVec<T, N> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        ops::IndexMut::index_mut(&mut **self, index)
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    fn default() -> Self {
+        SmallVec::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for SmallVec<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<SmallVec<U, M>> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &SmallVec<U, M>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize> PartialEq<[U]> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize> PartialEq<&[U]> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &&[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<[U; M]> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U; M]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize> PartialEq<Vec<U>> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for SmallVec<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for SmallVec<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.reserve(lower);
+        for element in iter {
+            self.push(element);
+        }
+    }
+
+    #[inline]
+    fn extend_one(&mut self, item: T) {
+        self.push(item);
+    }
+
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+}
+
+impl<'a, T: Copy + 'a, const N: usize> Extend<&'a T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+
+    #[inline]
+    fn extend_one(&mut self, &item: &'a T) {
+        self.push(item);
+    }
+
+    #[inline]
+    fn extend_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = SmallVec::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: Clone, const N: usize> From<&[T]> for SmallVec<T, N> {
+    fn from(s: &[T]) -> Self {
+        let mut v = SmallVec::new();
+        v.extend_from_slice(s);
+        v
+    }
+}
+
+impl<T, const N: usize> From<Vec<T>> for SmallVec<T, N> {
+    /// Converts a [`Vec`] into a `SmallVec`, reusing its allocation unless
+    /// the elements fit inline.
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let mut vec = ManuallyDrop::new(vec);
+        // SAFETY: ownership of the elements and the allocation is transferred
+        // from `vec`, whose destructor does not run.
+        let buf = unsafe { RawVec::from_raw_parts(vec.as_mut_ptr(), vec.capacity()) };
+        let mut v = SmallVec { len, data: Data::Heap(buf) };
+        if len <= N {
+            v.shrink_to_fit();
+        }
+        v
+    }
+}
+
+impl<T, const N: usize> From<SmallVec<T, N>> for Vec<T> {
+    fn from(v: SmallVec<T, N>) -> Vec<T> {
+        v.into_vec()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    /// Creates a consuming iterator that moves each value out of the vector,
+    /// from start to end.
+    fn into_iter(self) -> IntoIter<T, N> {
+        let end = self.len;
+        let mut vec = self;
+        // SAFETY: the iterator takes over the elements; resetting the length
+        // keeps the vector's destructor from dropping them again.
+        unsafe { vec.set_len(0) };
+        IntoIter { vec, range: 0..end }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator that moves out of a [`SmallVec`].
+///
+/// This `struct` is created by the `into_iter` method on [`SmallVec`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, const N: usize> {
+    // Owns the buffer; its length is zero, so it only frees the storage.
+    vec: SmallVec<T, N>,
+    // The elements of the buffer not yet yielded.
+    range: Range<usize>,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the elements in `range` are initialized and not yet moved.
+        unsafe {
+            slice::from_raw_parts(self.vec.as_ptr().add(self.range.start), self.range.len())
+        }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the elements in `range` are initialized and not yet moved.
+        unsafe {
+            slice::from_raw_parts_mut(self.vec.as_mut_ptr().add(self.range.start), self.range.len())
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for IntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let index = self.range.next()?;
+        // SAFETY: `index` was in the not-yet-yielded range.
+        unsafe { Some(ptr::read(self.vec.as_ptr().add(index))) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        let index = self.range.next_back()?;
+        // SAFETY: `index` was in the not-yet-yielded range.
+        unsafe { Some(ptr::read(self.vec.as_ptr().add(index))) }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: drop the elements that were never yielded; `vec` then only
+        // releases the storage because its length is zero.
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+/// A draining iterator for [`SmallVec`].
+///
+/// This `struct` is created by [`SmallVec::drain`].
+/// See its documentation for more.
+pub struct Drain<'a, T: 'a, const N: usize> {
+    /// Index of tail to preserve
+    tail_start: usize,
+    /// Length of tail
+    tail_len: usize,
+    /// Current remaining range to remove
+    iter: slice::Iter<'a, T>,
+    vec: NonNull<SmallVec<T, N>>,
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for Drain<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
+    }
+}
+
+impl<'a, T, const N: usize> Drain<'a, T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+}
+
+unsafe impl<T: Sync, const N: usize> Sync for Drain<'_, T, N> {}
+unsafe impl<T: Send, const N: usize> Send for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|elt| unsafe { ptr::read(elt as *const _) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|elt| unsafe { ptr::read(elt as *const _) })
+    }
+}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        /// Continues dropping the remaining elements in the `Drain`, then moves back the
+        /// un-`Drain`ed elements to restore the original `SmallVec`.
+        struct DropGuard<'r, 'a, T, const N: usize>(&'r mut Drain<'a, T, N>);
+
+        impl<'r, 'a, T, const N: usize> Drop for DropGuard<'r, 'a, T, N> {
+            fn drop(&mut self) {
+                // Continue the same loop we have below. If the loop already finished, this does
+                // nothing.
+                self.0.for_each(drop);
+
+                if self.0.tail_len > 0 {
+                    unsafe {
+                        let source_vec = self.0.vec.as_mut();
+                        // memmove back untouched tail, update to new length
+                        let start = source_vec.len();
+                        let tail = self.0.tail_start;
+                        if tail != start {
+                            let src = source_vec.as_ptr().add(tail);
+                            let dst = source_vec.as_mut_ptr().add(start);
+                            ptr::copy(src, dst, self.0.tail_len);
+                        }
+                        source_vec.set_len(start + self.0.tail_len);
+                    }
+                }
+            }
+        }
+
+        // exhaust self first
+        while let Some(item) = self.next() {
+            let guard = DropGuard(self);
+            drop(item);
+            mem::forget(guard);
+        }
+
+        // Drop a `DropGuard` to move back the non-drained tail of `self`.
+        DropGuard(self);
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {
+    fn is_empty(&self) -> bool {
+        self.iter.is_empty()
+    }
+}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
@@ -14,6 +14,7 @@
 #![feature(binary_heap_into_iter_sorted)]
 #![feature(binary_heap_drain_sorted)]
 #![feature(slice_ptr_get)]
+#![feature(small_vec)]
 #![feature(split_inclusive)]
 #![feature(binary_heap_retain)]
 #![feature(deque_range)]
@@ -36,6 +37,7 @@ mod heap;
 mod linked_list;
 mod rc;
 mod slice;
+mod small_vec;
 mod str;
 mod string;
 mod vec;
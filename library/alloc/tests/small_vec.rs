@@ -0,0 +1,124 @@
+use std::cell::Cell;
+use std::fmt::Write;
+use std::small_string::SmallString;
+use std::small_vec::SmallVec;
+
+#[test]
+fn test_push_spill() {
+    let mut v: SmallVec<usize, 4> = SmallVec::new();
+    for i in 0..4 {
+        v.push(i);
+    }
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 4);
+
+    v.push(4);
+    assert!(v.spilled());
+    assert!(v.capacity() >= 5);
+    assert_eq!(v, [0, 1, 2, 3, 4]);
+
+    assert_eq!(v.pop(), Some(4));
+    v.shrink_to_fit();
+    assert!(!v.spilled());
+    assert_eq!(v, [0, 1, 2, 3]);
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut v: SmallVec<i32, 2> = SmallVec::new();
+    v.insert(0, 2);
+    v.insert(0, 1);
+    v.insert(2, 4);
+    v.insert(2, 3);
+    assert_eq!(v, [1, 2, 3, 4]);
+    assert_eq!(v.remove(1), 2);
+    assert_eq!(v.swap_remove(0), 1);
+    assert_eq!(v, [4, 3]);
+}
+
+#[test]
+fn test_retain_drain() {
+    let mut v: SmallVec<i32, 8> = (1..=10).collect();
+    v.retain(|&x| x % 2 == 0);
+    assert_eq!(v, [2, 4, 6, 8, 10]);
+
+    let drained: Vec<_> = v.drain(1..3).collect();
+    assert_eq!(drained, [4, 6]);
+    assert_eq!(v, [2, 8, 10]);
+
+    // the tail is moved back even if the iterator is dropped early
+    let mut drain = v.drain(..2);
+    assert_eq!(drain.next(), Some(2));
+    drop(drain);
+    assert_eq!(v, [10]);
+}
+
+#[test]
+fn test_drops() {
+    struct Elem<'a>(&'a Cell<usize>);
+    impl Drop for Elem<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    {
+        let mut v: SmallVec<Elem<'_>, 2> = SmallVec::new();
+        v.extend((0..5).map(|_| Elem(&drops)));
+        v.truncate(3);
+        assert_eq!(drops.get(), 2);
+
+        let mut iter = v.into_iter();
+        drop(iter.next());
+        assert_eq!(drops.get(), 3);
+    }
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_vec_conversions() {
+    let v: SmallVec<i32, 2> = SmallVec::from(vec![1, 2, 3]);
+    assert!(v.spilled());
+    assert_eq!(v.into_vec(), [1, 2, 3]);
+
+    let v: SmallVec<i32, 4> = SmallVec::from(vec![1, 2]);
+    assert!(!v.spilled());
+    let back: Vec<i32> = v.into();
+    assert_eq!(back, [1, 2]);
+}
+
+#[test]
+fn test_zero_sized() {
+    let mut v: SmallVec<(), 0> = SmallVec::new();
+    for _ in 0..100 {
+        v.push(());
+    }
+    assert!(!v.spilled());
+    assert_eq!(v.len(), 100);
+    assert_eq!(v.into_iter().count(), 100);
+}
+
+#[test]
+fn test_small_string() {
+    let mut s: SmallString<4> = SmallString::new();
+    s.push_str("ab");
+    s.push('c');
+    assert!(!s.spilled());
+    s.push('é');
+    assert!(s.spilled());
+    assert_eq!(s, "abcé");
+    assert_eq!(s.pop(), Some('é'));
+    assert_eq!(s.len(), 3);
+
+    write!(s, "{}", 42).unwrap();
+    assert_eq!(s, "abc42");
+    assert_eq!(s.into_string(), String::from("abc42"));
+}
+
+#[test]
+#[should_panic]
+fn test_small_string_truncate_invalid() {
+    let mut s: SmallString<8> = SmallString::from("é");
+    s.truncate(1);
+}
@@ -95,6 +95,24 @@ fn test_find_str() {
     }
 }
 
+#[test]
+fn test_find_str_long_haystack() {
+    // needles on either side of the short-needle length limit, matched at
+    // every offset of a haystack much longer than one vector
+    for needle_len in 1..40 {
+        let needle: String = (0..needle_len).map(|i| (b'a' + (i % 26) as u8) as char).collect();
+        for pos in 0..100 {
+            let mut haystack = "x".repeat(pos);
+            haystack.push_str(&needle);
+            haystack.push_str(&"y".repeat(100 - pos));
+            assert_eq!(haystack.find(&*needle), Some(pos));
+            assert!(haystack.contains(&*needle));
+            assert_eq!(haystack.matches(&*needle).count(), 1);
+        }
+        assert_eq!("x".repeat(200).find(&*needle), None);
+    }
+}
+
 fn s(x: &str) -> String {
     x.to_string()
 }
@@ -865,6 +883,21 @@ fn from_utf8_mostly_ascii() {
     }
 }
 
+#[test]
+fn from_utf8_vector_boundaries() {
+    // place multibyte and invalid sequences on both sides of every position
+    // a vectorized ascii scan could stop at
+    for i in 0..80 {
+        let mut data = [b'a'; 96];
+        data[i..i + 2].copy_from_slice("é".as_bytes());
+        assert!(from_utf8(&data).is_ok());
+        data[i] = 0xFF;
+        let error = from_utf8(&data).unwrap_err();
+        assert_eq!(error.valid_up_to(), i);
+        assert_eq!(error.error_len(), Some(1));
+    }
+}
+
 #[test]
 fn from_utf8_error() {
     macro_rules! test {
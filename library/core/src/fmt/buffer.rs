@@ -0,0 +1,236 @@
+//! Formatting into fixed-size byte buffers, and measuring formatted output.
+
+use crate::fmt::{self, Arguments, Write};
+use crate::str;
+
+/// A [`Write`] implementation that formats into a borrowed byte slice.
+///
+/// No allocation is performed; once the slice is full, further output is
+/// either rejected with an error or silently dropped, depending on how the
+/// writer was created:
+///
+/// * [`SliceWriter::new`] returns [`fmt::Error`] from the write that does
+///   not fit, leaving the output written before it in place.
+/// * [`SliceWriter::truncating`] keeps the longest prefix of the output that
+///   fits and discards the rest, recording that it did so in
+///   [`is_truncated`].
+///
+/// In both modes the written bytes always form valid UTF-8: output is only
+/// ever cut at a `char` boundary.
+///
+/// [`is_truncated`]: SliceWriter::is_truncated
+///
+/// # Examples
+///
+/// ```
+/// #![feature(fmt_slice_writer)]
+///
+/// use std::fmt::{SliceWriter, Write};
+///
+/// let mut buf = [0u8; 16];
+/// let mut w = SliceWriter::new(&mut buf);
+/// write!(w, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// assert_eq!(w.as_str(), "1 + 2 = 3");
+///
+/// let mut buf = [0u8; 8];
+/// let mut w = SliceWriter::truncating(&mut buf);
+/// write!(w, "hello, {}", "world").unwrap();
+/// assert_eq!(w.as_str(), "hello, w");
+/// assert!(w.is_truncated());
+/// ```
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    truncate: bool,
+    truncated: bool,
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Creates a writer that returns an error when the output does not fit
+    /// into `buf`.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        SliceWriter { buf, len: 0, truncate: false, truncated: false }
+    }
+
+    /// Creates a writer that drops the output that does not fit into `buf`.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn truncating(buf: &'a mut [u8]) -> Self {
+        SliceWriter { buf, len: 0, truncate: true, truncated: false }
+    }
+
+    /// Returns the output written so far.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: only whole `str`s, or prefixes of them ending on a char
+        // boundary, are ever copied into `buf[..len]`.
+        unsafe { str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// Consumes the writer, returning the output written into the
+    /// underlying buffer.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn into_str(self) -> &'a str {
+        // SAFETY: see `as_str`.
+        unsafe { str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// Returns the number of bytes written so far.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if nothing has been written yet.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the size of the underlying buffer.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the number of bytes that can still be written.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    /// Returns `true` if a truncating writer has dropped any output.
+    ///
+    /// Always `false` for a writer created with [`SliceWriter::new`].
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Discards the output written so far, so that the whole buffer can be
+    /// reused.
+    #[inline]
+    #[unstable(feature = "fmt_slice_writer", issue = "none")]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+}
+
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Once output has been dropped, keep dropping it, so that the buffer
+        // always holds a prefix of the complete output.
+        if self.truncated {
+            return Ok(());
+        }
+        let remaining = self.remaining();
+        let n = if s.len() <= remaining {
+            s.len()
+        } else if self.truncate {
+            self.truncated = true;
+            // Find the last char boundary that still fits.
+            let mut n = remaining;
+            while !s.is_char_boundary(n) {
+                n -= 1;
+            }
+            n
+        } else {
+            return Err(fmt::Error);
+        };
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+impl fmt::Debug for SliceWriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SliceWriter")
+            .field("output", &self.as_str())
+            .field("capacity", &self.capacity())
+            .field("truncated", &self.truncated)
+            .finish()
+    }
+}
+
+/// Formats `args` into `buf`, returning the written part of the buffer as a
+/// string slice.
+///
+/// Returns an error if the output does not fit; use
+/// [`SliceWriter::truncating`] to keep a truncated prefix instead.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(fmt_slice_writer)]
+///
+/// use std::fmt;
+///
+/// let mut buf = [0u8; 32];
+/// let s = fmt::write_to_slice(&mut buf, format_args!("{:>5}|{:<5}|", 1, 2)).unwrap();
+/// assert_eq!(s, "    1|2    |");
+///
+/// let mut small = [0u8; 4];
+/// assert!(fmt::write_to_slice(&mut small, format_args!("{}", 123456)).is_err());
+/// ```
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+pub fn write_to_slice<'a>(buf: &'a mut [u8], args: Arguments<'_>) -> Result<&'a str, fmt::Error> {
+    let mut writer = SliceWriter::new(buf);
+    writer.write_fmt(args)?;
+    Ok(writer.into_str())
+}
+
+/// Returns the length in bytes of the output of formatting `args`, without
+/// storing it anywhere.
+///
+/// The arguments are formatted into a sink that only counts bytes, so this
+/// costs about as much as formatting itself. It can be used to size a buffer
+/// exactly before writing into it.
+///
+/// If a formatting trait implementation returns an error, the length of the
+/// output produced until then is returned.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(fmt_slice_writer)]
+///
+/// use std::fmt;
+///
+/// assert_eq!(fmt::formatted_len(format_args!("{}-{:04}", "id", 7)), 7);
+/// assert_eq!(fmt::formatted_len(format_args!("{}", 'é')), 2);
+/// ```
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+pub fn formatted_len(args: Arguments<'_>) -> usize {
+    struct Counter(usize);
+
+    impl Write for Counter {
+        #[inline]
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    // Fast path for arguments without placeholders.
+    if let Some(s) = args.as_str() {
+        return s.len();
+    }
+
+    let mut counter = Counter(0);
+    let _ = counter.write_fmt(args);
+    counter.0
+}
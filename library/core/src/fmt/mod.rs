@@ -10,6 +10,7 @@ use crate::ops::Deref;
 use crate::result;
 use crate::str;
 
+mod buffer;
 mod builders;
 mod float;
 mod num;
@@ -29,6 +30,8 @@ pub enum Alignment {
     Center,
 }
 
+#[unstable(feature = "fmt_slice_writer", issue = "none")]
+pub use self::buffer::{formatted_len, write_to_slice, SliceWriter};
 #[stable(feature = "debug_builders", since = "1.2.0")]
 pub use self::builders::{DebugList, DebugMap, DebugSet, DebugStruct, DebugTuple};
 
@@ -1295,43 +1295,51 @@ extern "rust-intrinsic" {
     ///
     /// The stabilized version of this intrinsic is
     /// [`std::f32::abs`](../../std/primitive.f32.html#method.abs)
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn fabsf32(x: f32) -> f32;
     /// Returns the absolute value of an `f64`.
     ///
     /// The stabilized version of this intrinsic is
     /// [`std::f64::abs`](../../std/primitive.f64.html#method.abs)
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn fabsf64(x: f64) -> f64;
 
     /// Returns the minimum of two `f32` values.
     ///
     /// The stabilized version of this intrinsic is
     /// [`f32::min`]
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn minnumf32(x: f32, y: f32) -> f32;
     /// Returns the minimum of two `f64` values.
     ///
     /// The stabilized version of this intrinsic is
     /// [`f64::min`]
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn minnumf64(x: f64, y: f64) -> f64;
     /// Returns the maximum of two `f32` values.
     ///
     /// The stabilized version of this intrinsic is
     /// [`f32::max`]
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn maxnumf32(x: f32, y: f32) -> f32;
     /// Returns the maximum of two `f64` values.
     ///
     /// The stabilized version of this intrinsic is
     /// [`f64::max`]
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn maxnumf64(x: f64, y: f64) -> f64;
 
     /// Copies the sign from `y` to `x` for `f32` values.
     ///
     /// The stabilized version of this intrinsic is
     /// [`std::f32::copysign`](../../std/primitive.f32.html#method.copysign)
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn copysignf32(x: f32, y: f32) -> f32;
     /// Copies the sign from `y` to `x` for `f64` values.
     ///
     /// The stabilized version of this intrinsic is
     /// [`std::f64::copysign`](../../std/primitive.f64.html#method.copysign)
+    #[rustc_const_unstable(feature = "const_float_methods", issue = "none")]
     pub fn copysignf64(x: f64, y: f64) -> f64;
 
     /// Returns the largest integer less than or equal to an `f32`.
@@ -1732,6 +1740,33 @@ extern "rust-intrinsic" {
     /// See documentation of `<*const T>::guaranteed_ne` for details.
     #[rustc_const_unstable(feature = "const_raw_ptr_comparison", issue = "53020")]
     pub fn ptr_guaranteed_ne<T>(ptr: *const T, other: *const T) -> bool;
+
+    /// Allocates a block of memory at compile time.
+    /// At runtime, just returns a null pointer.
+    ///
+    /// Memory that is not deallocated with `const_deallocate` by the end of the evaluation
+    /// must be part of its final value, and only behind shared references: it becomes
+    /// immutable static memory.
+    ///
+    /// # Safety
+    ///
+    /// - The `align` argument must be a power of two.
+    ///    - At compile time, a compile error occurs if this constraint is violated.
+    ///    - At runtime, it is not checked.
+    #[rustc_const_unstable(feature = "const_heap", issue = "none")]
+    pub fn const_allocate(size: usize, align: usize) -> *mut u8;
+
+    /// Deallocates a block of memory allocated by `const_allocate` at compile time.
+    /// At runtime, does nothing.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must have been returned by `const_allocate` with the same `size` and `align`,
+    ///   and not deallocated since.
+    ///    - At compile time, a compile error occurs if this constraint is violated.
+    ///    - At runtime, it is not checked.
+    #[rustc_const_unstable(feature = "const_heap", issue = "none")]
+    pub fn const_deallocate(ptr: *mut u8, size: usize, align: usize);
 }
 
 // Some functions are defined here because they accidentally got made
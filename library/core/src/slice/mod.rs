@@ -86,6 +86,7 @@ impl<T> [T] {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     #[rustc_const_stable(feature = "const_slice_len", since = "1.32.0")]
+    #[cfg_attr(not(bootstrap), lang = "slice_len_fn")]
     #[inline]
     // SAFETY: const sound because we transmute out the length field as a usize (which it must be)
     #[cfg_attr(not(bootstrap), rustc_allow_const_fn_unstable(const_fn_union))]
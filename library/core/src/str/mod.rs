@@ -9,6 +9,7 @@
 mod converts;
 mod error;
 mod iter;
+mod simd;
 mod traits;
 mod validations;
 
@@ -40,6 +40,8 @@ use crate::cmp;
 use crate::fmt;
 use crate::slice::memchr;
 
+use super::simd;
+
 // Pattern
 
 /// A string pattern.
@@ -1019,6 +1021,9 @@ unsafe impl<'a, 'b> Searcher<'a> for StrSearcher<'a, 'b> {
                 }
             },
             StrSearcherImpl::TwoWay(ref mut searcher) => {
+                if simd::ENABLED && self.needle.len() <= simd::MAX_SHORT_NEEDLE {
+                    return searcher.next_short(self.haystack.as_bytes(), self.needle.as_bytes());
+                }
                 let is_long = searcher.memory == usize::MAX;
                 // write out `true` and `false` cases to encourage the compiler
                 // to specialize the two cases separately.
@@ -1363,6 +1368,29 @@ impl TwoWaySearcher {
         }
     }
 
+    // Forward search for short needles, using the vectorized candidate filter
+    // in `str::simd` instead of the critical factorization. Only used for
+    // `next_match`, where no `Reject` steps have to be reported.
+    //
+    // Resetting `memory` is always sound: it only records how much of the
+    // needle is already known to match at `position`, and zero means nothing.
+    #[inline]
+    fn next_short(&mut self, haystack: &[u8], needle: &[u8]) -> Option<(usize, usize)> {
+        if self.memory != usize::MAX {
+            self.memory = 0;
+        }
+        match simd::find_short(haystack, needle, self.position) {
+            Some(match_pos) => {
+                self.position = match_pos + needle.len();
+                Some((match_pos, match_pos + needle.len()))
+            }
+            None => {
+                self.position = haystack.len();
+                None
+            }
+        }
+    }
+
     // Follows the ideas in `next()`.
     //
     // The definitions are symmetrical, with period(x) = period(reverse(x))
@@ -0,0 +1,400 @@
+//! Vectorized fast paths for UTF-8 validation and short substring search.
+//!
+//! The instruction sets enabled at compile time are used: SSE2 (or AVX2 if
+//! enabled) on x86, and NEON on AArch64. On every other target `ENABLED` is
+//! `false` and callers keep using the scalar algorithms, which also handle
+//! whatever bytes are left over at the end of a slice.
+//!
+//! On x86 targets that only enable SSE2 at compile time, AVX2 is detected
+//! with `cpuid` on first use, and the AVX2 versions are used if the CPU and
+//! the OS support it.
+
+/// The longest needle that `find_short` is used for. Longer needles are
+/// handed to the Two-Way searcher, whose linear worst case matters more than
+/// the constant factor once candidates have to be verified byte by byte.
+pub(super) const MAX_SHORT_NEEDLE: usize = 32;
+
+pub(super) use imp::ENABLED;
+
+/// A vector of bytes and the operations that the searches need on it.
+trait Vector: Copy {
+    const LANES: usize;
+
+    /// # Safety
+    ///
+    /// There must be `LANES` readable bytes at `ptr`, and the target features
+    /// of the vector must be available.
+    unsafe fn load(ptr: *const u8) -> Self;
+
+    /// # Safety
+    ///
+    /// The target features of the vector must be available, as for all the
+    /// other methods.
+    unsafe fn splat(byte: u8) -> Self;
+
+    unsafe fn eq(self, other: Self) -> Self;
+
+    unsafe fn and(self, other: Self) -> Self;
+
+    /// Bit `i` of the result is the high bit of lane `i`.
+    unsafe fn mask(self) -> u32;
+}
+
+/// Returns the first index at or after `index` at which a vector of `v`
+/// contains a non-ASCII byte, or at which fewer than a full vector of bytes
+/// remain. All bytes in `v[index..returned]` are ASCII.
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon"),
+))]
+#[inline]
+pub(super) fn skip_ascii(v: &[u8], index: usize) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    if runtime::avx2_enabled() {
+        // SAFETY: the CPU supports AVX2.
+        return unsafe { runtime::skip_ascii_avx2(v, index) };
+    }
+    // SAFETY: the target features of `imp::V` are enabled at compile time.
+    unsafe { skip_ascii_with::<imp::V>(v, index) }
+}
+
+/// Returns the position of the first occurrence of `needle` in
+/// `haystack[start..]`, as an index into `haystack`.
+///
+/// Candidates are found by comparing a vector of haystack bytes against the
+/// first byte of the needle and a second, offset vector against its last
+/// byte; only positions where both match are verified.
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon"),
+))]
+#[inline]
+pub(super) fn find_short(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+    debug_assert!(!needle.is_empty() && needle.len() <= MAX_SHORT_NEEDLE);
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    if runtime::avx2_enabled() {
+        // SAFETY: the CPU supports AVX2.
+        return unsafe { runtime::find_short_avx2(haystack, needle, start) };
+    }
+    // SAFETY: the target features of `imp::V` are enabled at compile time.
+    unsafe { find_short_with::<imp::V>(haystack, needle, start) }
+}
+
+/// Never called, since `ENABLED` is `false` on these targets.
+#[cfg(not(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon"),
+)))]
+pub(super) fn skip_ascii(_: &[u8], index: usize) -> usize {
+    index
+}
+
+/// Never called, since `ENABLED` is `false` on these targets.
+#[cfg(not(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon"),
+)))]
+pub(super) fn find_short(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+    haystack.get(start..)?.windows(needle.len()).position(|w| w == needle).map(|pos| start + pos)
+}
+
+/// # Safety
+///
+/// The target features of `V` must be available.
+#[inline(always)]
+unsafe fn skip_ascii_with<V: Vector>(v: &[u8], mut index: usize) -> usize {
+    let len = v.len();
+    let ptr = v.as_ptr();
+    while index + V::LANES <= len {
+        // SAFETY: `index + LANES <= len`, so the whole vector is in bounds,
+        // and the caller guarantees the target features.
+        let high_bits = unsafe { V::load(ptr.add(index)).mask() };
+        if high_bits != 0 {
+            break;
+        }
+        index += V::LANES;
+    }
+    index
+}
+
+/// # Safety
+///
+/// The target features of `V` must be available.
+#[inline(always)]
+unsafe fn find_short_with<V: Vector>(
+    haystack: &[u8],
+    needle: &[u8],
+    start: usize,
+) -> Option<usize> {
+    let len = haystack.len();
+    let last = needle.len() - 1;
+    let ptr = haystack.as_ptr();
+    let mut index = start;
+
+    // SAFETY: the caller guarantees the target features.
+    let (first_byte, last_byte) = unsafe { (V::splat(needle[0]), V::splat(needle[last])) };
+    while index + last + V::LANES <= len {
+        // SAFETY: `index + last + LANES <= len`, so both vectors are in bounds,
+        // and the caller guarantees the target features.
+        let mut candidates = unsafe {
+            let front = V::load(ptr.add(index)).eq(first_byte);
+            let back = V::load(ptr.add(index + last)).eq(last_byte);
+            front.and(back).mask()
+        };
+        while candidates != 0 {
+            let offset = candidates.trailing_zeros() as usize;
+            if &haystack[index + offset..index + offset + needle.len()] == needle {
+                return Some(index + offset);
+            }
+            candidates &= candidates - 1;
+        }
+        index += V::LANES;
+    }
+
+    haystack.get(index..)?.windows(needle.len()).position(|w| w == needle).map(|pos| index + pos)
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(target_feature = "avx2")
+))]
+mod runtime {
+    use super::{find_short_with, skip_ascii_with, x86::Avx2};
+    #[cfg(target_arch = "x86")]
+    use crate::arch::x86::{__cpuid, __cpuid_count, __get_cpuid_max, _xgetbv};
+    #[cfg(target_arch = "x86_64")]
+    use crate::arch::x86_64::{__cpuid, __cpuid_count, __get_cpuid_max, _xgetbv};
+    use crate::sync::atomic::{AtomicU8, Ordering};
+
+    /// `UNKNOWN` until AVX2 is detected, and then whether it is available.
+    static AVX2: AtomicU8 = AtomicU8::new(UNKNOWN);
+    const UNKNOWN: u8 = 0;
+    const UNAVAILABLE: u8 = 1;
+    const AVAILABLE: u8 = 2;
+
+    #[inline]
+    pub fn avx2_enabled() -> bool {
+        match AVX2.load(Ordering::Relaxed) {
+            UNKNOWN => detect_avx2(),
+            state => state == AVAILABLE,
+        }
+    }
+
+    #[cold]
+    fn detect_avx2() -> bool {
+        let available = has_avx2();
+        AVX2.store(if available { AVAILABLE } else { UNAVAILABLE }, Ordering::Relaxed);
+        available
+    }
+
+    /// Whether the CPU supports AVX2 and the OS saves the AVX registers, as
+    /// checked by `is_x86_feature_detected!("avx2")` in `std`.
+    fn has_avx2() -> bool {
+        // SAFETY: every CPU with SSE2 has `cpuid`.
+        unsafe {
+            if __get_cpuid_max(0).0 < 7 {
+                return false;
+            }
+            // The OS enabled `xgetbv` and the CPU supports AVX.
+            let ecx = __cpuid(1).ecx;
+            if ecx & (1 << 27) == 0 || ecx & (1 << 28) == 0 {
+                return false;
+            }
+        }
+        // SAFETY: `xgetbv` is enabled, as checked above.
+        let xcr0 = unsafe { _xgetbv(0) };
+        // The OS saves the SSE and the AVX registers.
+        if xcr0 & 0b110 != 0b110 {
+            return false;
+        }
+        // SAFETY: the largest leaf of `cpuid` is at least 7.
+        unsafe { __cpuid_count(7, 0).ebx & (1 << 5) != 0 }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn skip_ascii_avx2(v: &[u8], index: usize) -> usize {
+        // SAFETY: AVX2 is enabled for this function.
+        unsafe { skip_ascii_with::<Avx2>(v, index) }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn find_short_avx2(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+        // SAFETY: AVX2 is enabled for this function.
+        unsafe { find_short_with::<Avx2>(haystack, needle, start) }
+    }
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+mod x86 {
+    use super::Vector;
+    #[cfg(target_arch = "x86")]
+    use crate::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use crate::arch::x86_64::*;
+
+    #[cfg(not(target_feature = "avx2"))]
+    #[derive(Clone, Copy)]
+    pub struct Sse2(__m128i);
+
+    #[cfg(not(target_feature = "avx2"))]
+    impl Vector for Sse2 {
+        const LANES: usize = 16;
+
+        #[inline(always)]
+        unsafe fn load(ptr: *const u8) -> Self {
+            // SAFETY: the caller guarantees `LANES` readable bytes at `ptr`.
+            Sse2(unsafe { _mm_loadu_si128(ptr as *const __m128i) })
+        }
+
+        #[inline(always)]
+        unsafe fn splat(byte: u8) -> Self {
+            // SAFETY: SSE2 is enabled at compile time.
+            Sse2(unsafe { _mm_set1_epi8(byte as i8) })
+        }
+
+        #[inline(always)]
+        unsafe fn eq(self, other: Self) -> Self {
+            // SAFETY: SSE2 is enabled at compile time.
+            Sse2(unsafe { _mm_cmpeq_epi8(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            // SAFETY: SSE2 is enabled at compile time.
+            Sse2(unsafe { _mm_and_si128(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        unsafe fn mask(self) -> u32 {
+            // SAFETY: SSE2 is enabled at compile time.
+            unsafe { _mm_movemask_epi8(self.0) as u32 }
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct Avx2(__m256i);
+
+    impl Vector for Avx2 {
+        const LANES: usize = 32;
+
+        #[inline(always)]
+        unsafe fn load(ptr: *const u8) -> Self {
+            // SAFETY: the caller guarantees `LANES` readable bytes at `ptr`
+            // and the AVX2 support.
+            Avx2(unsafe { _mm256_loadu_si256(ptr as *const __m256i) })
+        }
+
+        #[inline(always)]
+        unsafe fn splat(byte: u8) -> Self {
+            // SAFETY: the caller guarantees the AVX2 support.
+            Avx2(unsafe { _mm256_set1_epi8(byte as i8) })
+        }
+
+        #[inline(always)]
+        unsafe fn eq(self, other: Self) -> Self {
+            // SAFETY: the caller guarantees the AVX2 support.
+            Avx2(unsafe { _mm256_cmpeq_epi8(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            // SAFETY: the caller guarantees the AVX2 support.
+            Avx2(unsafe { _mm256_and_si256(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        unsafe fn mask(self) -> u32 {
+            // SAFETY: the caller guarantees the AVX2 support.
+            unsafe { _mm256_movemask_epi8(self.0) as u32 }
+        }
+    }
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(target_feature = "avx2")
+))]
+mod imp {
+    pub const ENABLED: bool = true;
+    pub use super::x86::Sse2 as V;
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx2"))]
+mod imp {
+    pub const ENABLED: bool = true;
+    pub use super::x86::Avx2 as V;
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod imp {
+    use super::Vector;
+    use crate::arch::aarch64::*;
+
+    pub const ENABLED: bool = true;
+    const LANES: usize = 16;
+
+    #[derive(Clone, Copy)]
+    pub struct V(uint8x16_t);
+
+    impl Vector for V {
+        const LANES: usize = LANES;
+
+        #[inline(always)]
+        unsafe fn load(ptr: *const u8) -> Self {
+            // SAFETY: the caller guarantees `LANES` readable bytes at `ptr`.
+            V(unsafe { vld1q_u8(ptr) })
+        }
+
+        #[inline(always)]
+        unsafe fn splat(byte: u8) -> Self {
+            // SAFETY: NEON is enabled at compile time.
+            V(unsafe { vdupq_n_u8(byte) })
+        }
+
+        #[inline(always)]
+        unsafe fn eq(self, other: Self) -> Self {
+            // SAFETY: NEON is enabled at compile time.
+            V(unsafe { vceqq_u8(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            // SAFETY: NEON is enabled at compile time.
+            V(unsafe { vandq_u8(self.0, other.0) })
+        }
+
+        /// NEON has no `movemask`, so the common all-clear case is answered
+        /// with a horizontal maximum and the lanes are only spilled when it
+        /// fails.
+        #[inline(always)]
+        unsafe fn mask(self) -> u32 {
+            // SAFETY: plain NEON arithmetic and a store to a local array.
+            unsafe {
+                if vmaxvq_u8(self.0) < 0x80 {
+                    return 0;
+                }
+                let mut lanes = [0u8; LANES];
+                vst1q_u8(lanes.as_mut_ptr(), self.0);
+                lanes.iter().enumerate().fold(0, |acc, (i, &b)| acc | (((b >> 7) as u32) << i))
+            }
+        }
+    }
+}
+
+#[cfg(not(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon"),
+)))]
+mod imp {
+    pub const ENABLED: bool = false;
+}
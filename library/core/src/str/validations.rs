@@ -2,6 +2,7 @@
 
 use crate::mem;
 
+use super::simd;
 use super::Utf8Error;
 
 /// Returns the initial codepoint accumulator for the first byte.
@@ -196,9 +197,17 @@ pub(super) fn run_utf8_validation(v: &[u8]) -> Result<(), Utf8Error> {
             index += 1;
         } else {
             // Ascii case, try to skip forward quickly.
-            // When the pointer is aligned, read 2 words of data per iteration
-            // until we find a word containing a non-ascii byte.
-            if align != usize::MAX && align.wrapping_sub(index) % usize_bytes == 0 {
+            // With SIMD available, check a whole vector of unaligned bytes per
+            // iteration until we find one containing a non-ascii byte.
+            // Otherwise, when the pointer is aligned, read 2 words of data per
+            // iteration until we find a word containing a non-ascii byte.
+            if simd::ENABLED {
+                index = simd::skip_ascii(v, index);
+                // step from the point where the vector loop stopped
+                while index < len && v[index] < 128 {
+                    index += 1;
+                }
+            } else if align != usize::MAX && align.wrapping_sub(index) % usize_bytes == 0 {
                 let ptr = v.as_ptr();
                 while index < blocks_end {
                     // SAFETY: since `align - index` and `ascii_block_size` are
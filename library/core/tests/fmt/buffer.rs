@@ -0,0 +1,53 @@
+use core::fmt::{self, SliceWriter, Write};
+
+#[test]
+fn test_slice_writer() {
+    let mut buf = [0u8; 16];
+    let mut w = SliceWriter::new(&mut buf);
+    assert!(w.is_empty());
+    write!(w, "{:>4}", 12).unwrap();
+    write!(w, "|{:x}", 255).unwrap();
+    assert_eq!(w.as_str(), "  12|ff");
+    assert_eq!(w.len(), 7);
+    assert_eq!(w.remaining(), 9);
+
+    // A write that does not fit fails and leaves earlier output in place.
+    assert!(w.write_str("0123456789").is_err());
+    assert_eq!(w.as_str(), "  12|ff");
+    assert!(!w.is_truncated());
+
+    w.clear();
+    write!(w, "{}", "abc").unwrap();
+    assert_eq!(w.into_str(), "abc");
+}
+
+#[test]
+fn test_slice_writer_truncating() {
+    let mut buf = [0u8; 5];
+    let mut w = SliceWriter::truncating(&mut buf);
+    write!(w, "ab{}", "cd").unwrap();
+    assert!(!w.is_truncated());
+    // 'é' is two bytes and does not fit in the one remaining byte.
+    write!(w, "é").unwrap();
+    assert_eq!(w.as_str(), "abcd");
+    assert!(w.is_truncated());
+    // Later output is dropped too, even if it would fit.
+    write!(w, "z").unwrap();
+    assert_eq!(w.as_str(), "abcd");
+}
+
+#[test]
+fn test_write_to_slice() {
+    let mut buf = [0u8; 8];
+    assert_eq!(fmt::write_to_slice(&mut buf, format_args!("{}{}", 1, 2)), Ok("12"));
+    assert_eq!(fmt::write_to_slice(&mut buf, format_args!("{:9}", 0)), Err(fmt::Error));
+}
+
+#[test]
+fn test_formatted_len() {
+    assert_eq!(fmt::formatted_len(format_args!("")), 0);
+    assert_eq!(fmt::formatted_len(format_args!("hello")), 5);
+    assert_eq!(fmt::formatted_len(format_args!("{:?}", "a\nb")), 6);
+    assert_eq!(fmt::formatted_len(format_args!("{:8.3}", 1.5)), 8);
+    assert_eq!(fmt::formatted_len(format_args!("{}", '€')), 3);
+}
@@ -1,3 +1,4 @@
+mod buffer;
 mod builders;
 mod float;
 mod num;
@@ -60,6 +60,7 @@
 #![feature(once_cell)]
 #![feature(unsafe_block_in_unsafe_fn)]
 #![feature(int_bits_const)]
+#![feature(fmt_slice_writer)]
 #![deny(unsafe_op_in_unsafe_fn)]
 
 extern crate test;
@@ -1029,6 +1029,266 @@ where
     }
 }
 
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Computes the hash of `k` with this map's hasher.
+    ///
+    /// The result can be passed to the `*_hashed_nocheck` methods to avoid
+    /// hashing the same key more than once, for example when a key is
+    /// looked up and then inserted, or is looked up in several maps that
+    /// share a [`BuildHasher`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_map_equivalent)]
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// let hash = map.hash_key("poneyland");
+    /// if map.get_hashed_nocheck(hash, "poneyland").is_none() {
+    ///     map.insert_hashed_nocheck(hash, "poneyland", 3);
+    /// }
+    /// assert_eq!(map["poneyland"], 3);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn hash_key<Q: ?Sized + Hash>(&self, k: &Q) -> u64 {
+        make_hash(self.hasher(), k)
+    }
+
+    /// Returns a reference to the value corresponding to a key that is
+    /// [`Equivalent`] to a key in the map.
+    ///
+    /// Unlike [`get`], the lookup key does not have to be a [`Borrow`]ed
+    /// form of the map's key type, but its [`Hash`] implementation must
+    /// produce the same hash as the key it is equivalent to.
+    ///
+    /// [`get`]: HashMap::get
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_map_equivalent)]
+    /// use std::collections::hash_map::{Equivalent, HashMap};
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// struct PairRef<'a>(&'a str, &'a str);
+    ///
+    /// impl Hash for PairRef<'_> {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         // Must match the hash of `(String, String)`.
+    ///         (self.0, self.1).hash(state);
+    ///     }
+    /// }
+    ///
+    /// impl Equivalent<(String, String)> for PairRef<'_> {
+    ///     fn equivalent(&self, key: &(String, String)) -> bool {
+    ///         self.0 == key.0 && self.1 == key.1
+    ///     }
+    /// }
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert(("a".to_string(), "b".to_string()), 1);
+    /// assert_eq!(map.get_equivalent(&PairRef("a", "b")), Some(&1));
+    /// assert_eq!(map.get_equivalent(&PairRef("b", "a")), None);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn get_equivalent<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        Q: Hash + Equivalent<K>,
+    {
+        self.get_key_value_equivalent(k).map(|(_, v)| v)
+    }
+
+    /// Returns the key-value pair corresponding to a key that is
+    /// [`Equivalent`] to a key in the map.
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn get_key_value_equivalent<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        Q: Hash + Equivalent<K>,
+    {
+        self.get_key_value_hashed_nocheck(self.hash_key(k), k)
+    }
+
+    /// Returns `true` if the map contains a key that is [`Equivalent`] to
+    /// `k`.
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn contains_key_equivalent<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: Hash + Equivalent<K>,
+    {
+        self.get_key_value_equivalent(k).is_some()
+    }
+
+    /// Returns a mutable reference to the value corresponding to a key that
+    /// is [`Equivalent`] to a key in the map.
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn get_mut_equivalent<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        Q: Hash + Equivalent<K>,
+    {
+        let hash = self.hash_key(k);
+        self.get_mut_hashed_nocheck(hash, k)
+    }
+
+    /// Removes a key that is [`Equivalent`] to `k` from the map, returning
+    /// the stored key and value if it was previously in the map.
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn remove_entry_equivalent<Q: ?Sized>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        Q: Hash + Equivalent<K>,
+    {
+        let hash = self.hash_key(k);
+        self.remove_entry_hashed_nocheck(hash, k)
+    }
+
+    /// Returns the key-value pair corresponding to `k`, using a hash that
+    /// was computed beforehand.
+    ///
+    /// `hash` must have been computed with [`hash_key`] on this map, or with
+    /// an equal [`BuildHasher`]. If it is not the hash of `k`, the lookup
+    /// silently fails.
+    ///
+    /// [`hash_key`]: HashMap::hash_key
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn get_key_value_hashed_nocheck<Q: ?Sized>(&self, hash: u64, k: &Q) -> Option<(&K, &V)>
+    where
+        Q: Equivalent<K>,
+    {
+        self.base.raw_entry().from_hash(hash, |key| k.equivalent(key))
+    }
+
+    /// Returns a reference to the value corresponding to `k`, using a hash
+    /// that was computed beforehand.
+    ///
+    /// See [`get_key_value_hashed_nocheck`] for the requirements on `hash`.
+    ///
+    /// [`get_key_value_hashed_nocheck`]: HashMap::get_key_value_hashed_nocheck
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn get_hashed_nocheck<Q: ?Sized>(&self, hash: u64, k: &Q) -> Option<&V>
+    where
+        Q: Equivalent<K>,
+    {
+        self.get_key_value_hashed_nocheck(hash, k).map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference to the value corresponding to `k`, using
+    /// a hash that was computed beforehand.
+    ///
+    /// See [`get_key_value_hashed_nocheck`] for the requirements on `hash`.
+    ///
+    /// [`get_key_value_hashed_nocheck`]: HashMap::get_key_value_hashed_nocheck
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn get_mut_hashed_nocheck<Q: ?Sized>(&mut self, hash: u64, k: &Q) -> Option<&mut V>
+    where
+        Q: Equivalent<K>,
+    {
+        match self.base.raw_entry_mut().from_hash(hash, |key| k.equivalent(key)) {
+            base::RawEntryMut::Occupied(entry) => Some(entry.into_mut()),
+            base::RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Inserts a key-value pair into the map, using a hash that was computed
+    /// beforehand.
+    ///
+    /// This behaves like [`insert`], but `k` is not hashed again. `hash`
+    /// must be the hash of `k` as returned by [`hash_key`]; otherwise the
+    /// map is left in an inconsistent state which, while memory-safe, makes
+    /// later lookups of `k` fail.
+    ///
+    /// [`insert`]: HashMap::insert
+    /// [`hash_key`]: HashMap::hash_key
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn insert_hashed_nocheck(&mut self, hash: u64, k: K, v: V) -> Option<V> {
+        match self.base.raw_entry_mut().from_key_hashed_nocheck(hash, &k) {
+            base::RawEntryMut::Occupied(mut entry) => Some(entry.insert(v)),
+            base::RawEntryMut::Vacant(entry) => {
+                entry.insert_hashed_nocheck(hash, k, v);
+                None
+            }
+        }
+    }
+
+    /// Removes `k` from the map, using a hash that was computed beforehand,
+    /// and returns the stored key and value if it was previously in the map.
+    ///
+    /// See [`get_key_value_hashed_nocheck`] for the requirements on `hash`.
+    ///
+    /// [`get_key_value_hashed_nocheck`]: HashMap::get_key_value_hashed_nocheck
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn remove_entry_hashed_nocheck<Q: ?Sized>(&mut self, hash: u64, k: &Q) -> Option<(K, V)>
+    where
+        Q: Equivalent<K>,
+    {
+        match self.base.raw_entry_mut().from_hash(hash, |key| k.equivalent(key)) {
+            base::RawEntryMut::Occupied(entry) => Some(entry.remove_entry()),
+            base::RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Removes `k` from the map, using a hash that was computed beforehand,
+    /// and returns the value if it was previously in the map.
+    ///
+    /// See [`get_key_value_hashed_nocheck`] for the requirements on `hash`.
+    ///
+    /// [`get_key_value_hashed_nocheck`]: HashMap::get_key_value_hashed_nocheck
+    #[inline]
+    #[unstable(feature = "hash_map_equivalent", issue = "none")]
+    pub fn remove_hashed_nocheck<Q: ?Sized>(&mut self, hash: u64, k: &Q) -> Option<V>
+    where
+        Q: Equivalent<K>,
+    {
+        self.remove_entry_hashed_nocheck(hash, k).map(|(_, v)| v)
+    }
+}
+
+/// Key equivalence used by the `*_equivalent` and `*_hashed_nocheck` lookup
+/// methods on [`HashMap`].
+///
+/// A type implementing `Equivalent<K>` can be used to look up keys of type
+/// `K` without being a [`Borrow`]ed form of `K`. This allows, for example,
+/// looking up a `(String, String)` key with a pair of string slices.
+///
+/// `Equivalent` is implemented for every `Q` such that `K: Borrow<Q>` and
+/// `Q: Eq`, so any type accepted by [`HashMap::get`] also works here.
+///
+/// Implementations must be consistent with the key's [`Eq`]: if
+/// `q.equivalent(a)` and `a == b`, then `q.equivalent(b)` must hold. When a
+/// lookup also hashes the query, its [`Hash`] implementation must produce
+/// the same hash as every key it is equivalent to.
+#[unstable(feature = "hash_map_equivalent", issue = "none")]
+pub trait Equivalent<K: ?Sized> {
+    /// Checks whether `self` is equivalent to `key`.
+    fn equivalent(&self, key: &K) -> bool;
+}
+
+#[unstable(feature = "hash_map_equivalent", issue = "none")]
+impl<Q: ?Sized, K: ?Sized> Equivalent<K> for Q
+where
+    Q: Eq,
+    K: Borrow<Q>,
+{
+    #[inline]
+    fn equivalent(&self, key: &K) -> bool {
+        *self == *key.borrow()
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<K, V, S> PartialEq for HashMap<K, V, S>
 where
@@ -2867,6 +3127,13 @@ impl fmt::Debug for RandomState {
     }
 }
 
+#[inline]
+fn make_hash<Q: ?Sized + Hash, S: BuildHasher>(hash_builder: &S, val: &Q) -> u64 {
+    let mut state = hash_builder.build_hasher();
+    val.hash(&mut state);
+    state.finish()
+}
+
 #[inline]
 fn map_entry<'a, K: 'a, V: 'a>(raw: base::RustcEntry<'a, K, V>) -> Entry<'a, K, V> {
     match raw {
@@ -925,6 +925,68 @@ fn test_raw_entry() {
     }
 }
 
+#[test]
+fn test_equivalent() {
+    use super::Equivalent;
+    use crate::hash::{Hash, Hasher};
+
+    struct PairRef<'a>(&'a str, &'a str);
+
+    impl Hash for PairRef<'_> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            (self.0, self.1).hash(state);
+        }
+    }
+
+    impl Equivalent<(String, String)> for PairRef<'_> {
+        fn equivalent(&self, key: &(String, String)) -> bool {
+            self.0 == key.0 && self.1 == key.1
+        }
+    }
+
+    let mut map = HashMap::new();
+    map.insert(("a".to_string(), "b".to_string()), 1);
+    map.insert(("c".to_string(), "d".to_string()), 2);
+
+    assert_eq!(map.get_equivalent(&PairRef("a", "b")), Some(&1));
+    assert!(map.contains_key_equivalent(&PairRef("c", "d")));
+    assert!(!map.contains_key_equivalent(&PairRef("b", "a")));
+
+    *map.get_mut_equivalent(&PairRef("c", "d")).unwrap() += 10;
+    assert_eq!(map.remove_entry_equivalent(&PairRef("c", "d")).map(|(_, v)| v), Some(12));
+    assert_eq!(map.len(), 1);
+
+    // `Borrow` forms of the key are equivalent too.
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), 1);
+    assert_eq!(map.get_equivalent("x"), Some(&1));
+}
+
+#[test]
+fn test_hashed_nocheck() {
+    let mut map = HashMap::new();
+    for k in 0..100 {
+        let hash = map.hash_key(&k);
+        assert_eq!(map.insert_hashed_nocheck(hash, k, k * 10), None);
+    }
+    assert_eq!(map.len(), 100);
+
+    for k in 0..100 {
+        let hash = map.hash_key(&k);
+        assert_eq!(map.get(&k), Some(&(k * 10)));
+        assert_eq!(map.get_hashed_nocheck(hash, &k), Some(&(k * 10)));
+        assert_eq!(map.insert_hashed_nocheck(hash, k, k), Some(k * 10));
+    }
+    assert_eq!(map.len(), 100);
+
+    for k in 0..50 {
+        let hash = map.hash_key(&k);
+        assert_eq!(map.remove_hashed_nocheck(hash, &k), Some(k));
+        assert_eq!(map.remove_hashed_nocheck(hash, &k), None);
+    }
+    assert_eq!(map.len(), 50);
+}
+
 mod test_drain_filter {
     use super::*;
 
@@ -239,6 +239,8 @@
 #![feature(concat_idents)]
 #![feature(const_cstr_unchecked)]
 #![feature(const_fn_floating_point_arithmetic)]
+#![feature(const_float_classify)]
+#![feature(const_float_methods)]
 #![feature(const_fn_transmute)]
 #![feature(const_fn)]
 #![feature(const_fn_fn_ptr_basics)]
@@ -379,6 +381,10 @@ pub use alloc_crate::format;
 pub use alloc_crate::rc;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use alloc_crate::slice;
+#[unstable(feature = "small_vec", issue = "none")]
+pub use alloc_crate::small_string;
+#[unstable(feature = "small_vec", issue = "none")]
+pub use alloc_crate::small_vec;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use alloc_crate::str;
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -479,17 +485,7 @@ pub mod time;
 pub mod lazy;
 
 #[stable(feature = "futures_api", since = "1.36.0")]
-pub mod task {
-    //! Types and Traits for working with asynchronous tasks.
-
-    #[doc(inline)]
-    #[stable(feature = "futures_api", since = "1.36.0")]
-    pub use core::task::*;
-
-    #[doc(inline)]
-    #[unstable(feature = "wake_trait", issue = "69912")]
-    pub use alloc::task::*;
-}
+pub mod task;
 
 #[stable(feature = "futures_api", since = "1.36.0")]
 pub mod future;
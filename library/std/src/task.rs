@@ -0,0 +1,72 @@
+//! Types and Traits for working with asynchronous tasks.
+
+#[cfg(test)]
+mod tests;
+
+use crate::future::Future;
+use crate::pin::Pin;
+use crate::sync::Arc;
+use crate::thread::{self, Thread};
+
+#[doc(inline)]
+#[stable(feature = "futures_api", since = "1.36.0")]
+pub use core::task::*;
+
+#[doc(inline)]
+#[unstable(feature = "wake_trait", issue = "69912")]
+pub use alloc::task::*;
+
+/// Runs a future to completion on the current thread.
+///
+/// The future is polled in a loop. Whenever it returns [`Poll::Pending`], the
+/// current thread is [parked][thread::park] until the [`Waker`] passed to the
+/// future is used to wake it, at which point the future is polled again.
+///
+/// This is a minimal executor: it does not drive any I/O or timers itself, so
+/// the future must arrange to be woken by some other thread or by an event
+/// source that calls [`Waker::wake`]. Spurious wakeups are harmless and only
+/// result in an extra call to [`Future::poll`].
+///
+/// # Examples
+///
+/// ```
+/// #![feature(block_on)]
+///
+/// use std::task::block_on;
+///
+/// async fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// assert_eq!(block_on(add(1, 2)), 3);
+/// ```
+#[unstable(feature = "block_on", issue = "none")]
+pub fn block_on<F: Future>(mut future: F) -> F::Output {
+    // SAFETY: `future` is shadowed below and is never moved again.
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            // An `unpark` that happened between the poll and this call leaves
+            // the token set, so the wakeup cannot be lost.
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// A [`Wake`] implementation that unparks the thread that created it.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
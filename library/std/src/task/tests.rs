@@ -0,0 +1,55 @@
+use super::*;
+use crate::sync::atomic::{AtomicBool, Ordering};
+use crate::sync::Mutex;
+use crate::thread;
+use crate::time::Duration;
+
+#[test]
+fn block_on_ready() {
+    assert_eq!(block_on(crate::future::ready(7)), 7);
+}
+
+#[test]
+fn block_on_async_fn() {
+    async fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    async fn quadruple(x: u32) -> u32 {
+        double(double(x).await).await
+    }
+
+    assert_eq!(block_on(quadruple(3)), 12);
+}
+
+/// A future that becomes ready once another thread has set a flag, and only
+/// learns about it through its waker.
+struct Flag {
+    state: Arc<(AtomicBool, Mutex<Option<Waker>>)>,
+}
+
+impl Future for Flag {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        *self.state.1.lock().unwrap() = Some(cx.waker().clone());
+        if self.state.0.load(Ordering::SeqCst) { Poll::Ready(()) } else { Poll::Pending }
+    }
+}
+
+#[test]
+fn block_on_woken_from_other_thread() {
+    let state = Arc::new((AtomicBool::new(false), Mutex::new(None::<Waker>)));
+
+    let remote = state.clone();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        remote.0.store(true, Ordering::SeqCst);
+        if let Some(waker) = remote.1.lock().unwrap().take() {
+            waker.wake();
+        }
+    });
+
+    block_on(Flag { state });
+    handle.join().unwrap();
+}
@@ -346,6 +346,15 @@ fn opts() -> Vec<RustcOptGroup> {
         stable("allow", |o| o.optmulti("A", "allow", "Set lint allowed", "OPT")),
         stable("deny", |o| o.optmulti("D", "deny", "Set lint denied", "OPT")),
         stable("forbid", |o| o.optmulti("F", "forbid", "Set lint forbidden", "OPT")),
+        unstable("force-warn", |o| {
+            o.optmulti(
+                "",
+                "force-warn",
+                "Set lint to warn, overriding any other level set in the source \
+                 code or by `--cap-lints`",
+                "LINT",
+            )
+        }),
         stable("cap-lints", |o| {
             o.optmulti(
                 "",
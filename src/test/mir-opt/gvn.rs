@@ -0,0 +1,10 @@
+// EMIT_MIR gvn.repeated_computations.GVN.diff
+fn repeated_computations(x: f32, y: f32) -> f32 {
+    let a = x * y;
+    let b = x * y;
+    a + b
+}
+
+fn main() {
+    repeated_computations(1.0, 2.0);
+}
@@ -0,0 +1,13 @@
+// Checks that the MIR inliner runs in optimized builds, without
+// `-Z mir-opt-level=2`.
+//
+// compile-flags: -O -Z mir-opt-level=1
+
+// EMIT_MIR inline_at_opt_level.main.Inline.diff
+fn main() {
+    let x = f();
+}
+
+fn f() -> u32 {
+    123
+}
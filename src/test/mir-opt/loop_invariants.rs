@@ -0,0 +1,19 @@
+// compile-flags: -C overflow-checks=off
+// Checks that the length of the slice in the bounds check is computed before
+// the loop, and that the bounds check implied by the loop condition is removed.
+
+// EMIT_MIR loop_invariants.sum.LoopInvariantCodeMotion.diff
+// EMIT_MIR loop_invariants.sum.RemoveRedundantBoundsChecks.diff
+fn sum(s: &[u32]) -> u32 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < s.len() {
+        total += s[i];
+        i += 1;
+    }
+    total
+}
+
+fn main() {
+    sum(&[1, 2, 3]);
+}
@@ -0,0 +1,13 @@
+#![crate_type = "lib"]
+
+pub fn unused_mut() -> u32 {
+    let x = 1;
+    x
+}
+
+pub fn unused_parens(x: u32) -> u32 {
+    if x > 1 { 0 } else { x }
+}
+
+// The suggestion to rename the function may be incorrect.
+pub fn not_snake_case() {}
@@ -0,0 +1,8 @@
+fn sum(v: Vec<u32>) -> u32 {
+    let w = v;
+    v.iter().sum::<u32>() + w.len() as u32
+}
+
+fn main() {
+    assert_eq!(sum(vec![1, 2, 3]), 9);
+}
@@ -0,0 +1,11 @@
+fn sum(s: &[u32]) -> u32 {
+    let mut total = 0;
+    for x in s {
+        total += x;
+    }
+    total
+}
+
+fn main() {
+    assert_eq!(sum(&[1, 2, 3]), 6);
+}
@@ -0,0 +1,10 @@
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+use std::intrinsics;
+
+const LEAK: () = unsafe {
+    //~^ ERROR heap allocation leaked
+    intrinsics::const_allocate(4, 4);
+};
+
+fn main() {}
@@ -0,0 +1,8 @@
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+use std::intrinsics;
+
+const PTR: *mut u8 = unsafe { intrinsics::const_allocate(4, 4) };
+//~^ ERROR heap allocation in the final value of a constant or static that is not behind a shared
+
+fn main() {}
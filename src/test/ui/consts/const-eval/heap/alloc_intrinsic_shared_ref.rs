@@ -0,0 +1,17 @@
+// run-pass
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_raw_ptr_deref)]
+#![feature(const_mut_refs)]
+use std::intrinsics;
+
+// The heap allocation becomes static memory.
+const TABLE: &[u8; 4] = unsafe {
+    let ptr = intrinsics::const_allocate(4, 1) as *mut [u8; 4];
+    *ptr = [1, 2, 4, 8];
+    &*ptr
+};
+
+fn main() {
+    assert_eq!(*TABLE, [1, 2, 4, 8]);
+}
@@ -0,0 +1,24 @@
+// run-pass
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_raw_ptr_deref)]
+#![feature(const_mut_refs)]
+use std::intrinsics;
+
+const FOO: i32 = foo();
+
+const fn foo() -> i32 {
+    unsafe {
+        let ptr = intrinsics::const_allocate(4, 4) as *mut i32;
+        *ptr = 20;
+        let value = *ptr;
+        intrinsics::const_deallocate(ptr as *mut u8, 4, 4);
+        value
+    }
+}
+
+fn main() {
+    assert_eq!(FOO, 20);
+    // Nothing is allocated at runtime.
+    assert!(unsafe { intrinsics::const_allocate(4, 4) }.is_null());
+}
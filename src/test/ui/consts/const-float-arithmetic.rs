@@ -0,0 +1,51 @@
+// compile-flags: -Zmir-opt-level=0
+// run-pass
+
+// Float arithmetic and comparisons in `const fn` are still unstable, but what is
+// computed at compile time must match what is computed at runtime.
+
+#![feature(const_panic)]
+#![feature(const_fn_floating_point_arithmetic)]
+
+// Don't promote
+const fn nop<T>(x: T) -> T { x }
+
+macro_rules! const_assert {
+    ($a:expr, $b:expr) => {
+        {
+            const _: () = assert!($a == $b);
+            assert_eq!(nop($a), nop($b));
+        }
+    };
+}
+
+const fn add(f: f32, g: f32) -> f32 { f + g }
+const fn mul(f: f64, g: f64) -> f64 { f * g }
+const fn div(f: f64, g: f64) -> f64 { f / g }
+const fn rem(f: f64, g: f64) -> f64 { f % g }
+const fn lt(f: f64, g: f64) -> bool { f < g }
+const fn eq(f: f64, g: f64) -> bool { f == g }
+const fn to_int(f: f64) -> i32 { f as i32 }
+const fn to_u8(f: f32) -> u8 { f as u8 }
+const fn to_f32(f: f64) -> f32 { f as f32 }
+const fn to_f64(i: u64) -> f64 { i as f64 }
+
+fn main() {
+    const_assert!(add(0.1, 0.2), 0.3);
+    const_assert!(add(16777216.0, 1.0), 16777216.0);
+    const_assert!(mul(1.5, 2.0), 3.0);
+    const_assert!(div(1.0, 0.0), f64::INFINITY);
+    const_assert!(div(-1.0, 0.0), f64::NEG_INFINITY);
+    const_assert!(rem(-5.5, 2.0), -1.5);
+    const_assert!(lt(-0.0, 0.0), false);
+    const_assert!(eq(-0.0, 0.0), true);
+    const_assert!(eq(f64::NAN, f64::NAN), false);
+    const_assert!(lt(f64::NAN, 1.0), false);
+    const_assert!(to_int(-2.5), -2);
+    const_assert!(to_int(1e10), i32::MAX);
+    const_assert!(to_int(f64::NAN), 0);
+    const_assert!(to_u8(-1.0), 0);
+    const_assert!(to_u8(300.0), u8::MAX);
+    const_assert!(to_f32(0.1), 0.1f32);
+    const_assert!(to_f64(u64::MAX), 18446744073709551615.0);
+}
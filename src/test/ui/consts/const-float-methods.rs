@@ -0,0 +1,88 @@
+// compile-flags: -Zmir-opt-level=0
+// run-pass
+
+#![feature(const_panic)]
+#![feature(const_float_bits_conv)]
+#![feature(const_float_classify)]
+#![feature(const_float_methods)]
+#![feature(const_fn_floating_point_arithmetic)]
+
+// Don't promote
+const fn nop<T>(x: T) -> T { x }
+
+macro_rules! const_assert {
+    ($a:expr) => {
+        {
+            const _: () = assert!($a);
+            assert!(nop($a));
+        }
+    };
+    ($a:expr, $b:expr) => {
+        {
+            const _: () = assert!($a == $b);
+            assert_eq!(nop($a), nop($b));
+        }
+    };
+}
+
+fn f32() {
+    const_assert!((-2.5f32).abs(), 2.5);
+    const_assert!((-0.0f32).abs().to_bits(), 0x00000000);
+    const_assert!(f32::NAN.abs().is_nan());
+    const_assert!((3.0f32).copysign(-0.0), -3.0);
+    const_assert!((-3.0f32).signum(), -1.0);
+    const_assert!(f32::NAN.signum().is_nan());
+    const_assert!((4.0f32).recip(), 0.25);
+    const_assert!((1.0f32).max(2.0), 2.0);
+    const_assert!((1.0f32).min(2.0), 1.0);
+    const_assert!(f32::NAN.max(2.0), 2.0);
+    const_assert!((1.0f32).min(f32::NAN), 1.0);
+    const_assert!((180.0f32).to_radians(), std::f32::consts::PI);
+    const_assert!(std::f32::consts::PI.to_degrees(), 180.0);
+    const_assert!((0.1f32 + 0.2f32).to_bits(), 0x3e99999a);
+    const_assert!((16777217.0f64 as f32), 16777216.0);
+    const_assert!((-1.5f32 as i32), -1);
+    const_assert!((300.0f32 as u8), 255);
+    const_assert!((f32::NAN as i32), 0);
+}
+
+fn f64() {
+    const_assert!((-2.5f64).abs(), 2.5);
+    const_assert!((-0.0f64).abs().to_bits(), 0x0000000000000000);
+    const_assert!(f64::NAN.abs().is_nan());
+    const_assert!((3.0f64).copysign(-0.0), -3.0);
+    const_assert!((-3.0f64).signum(), -1.0);
+    const_assert!(f64::NAN.signum().is_nan());
+    const_assert!((4.0f64).recip(), 0.25);
+    const_assert!((1.0f64).max(2.0), 2.0);
+    const_assert!((1.0f64).min(2.0), 1.0);
+    const_assert!(f64::NAN.max(2.0), 2.0);
+    const_assert!((1.0f64).min(f64::NAN), 1.0);
+    const_assert!((180.0f64).to_radians(), std::f64::consts::PI);
+    const_assert!(std::f64::consts::PI.to_degrees(), 180.0);
+    const_assert!((0.1f64 + 0.2f64).to_bits(), 0x3fd3333333333334);
+    const_assert!((-1.5f64 as i64), -1);
+    const_assert!((f64::INFINITY as i64), i64::MAX);
+}
+
+// A lookup table built at compile time matches the one built at runtime.
+const fn squares() -> [f64; 8] {
+    let mut table = [0.0; 8];
+    let mut i = 0;
+    while i < table.len() {
+        let x = i as f64 / 8.0;
+        table[i] = x * x - 0.5;
+        i += 1;
+    }
+    table
+}
+
+const SQUARES: [f64; 8] = squares();
+
+fn main() {
+    f32();
+    f64();
+
+    let runtime = nop(squares);
+    assert_eq!(SQUARES, runtime());
+}
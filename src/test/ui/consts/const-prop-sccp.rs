@@ -0,0 +1,50 @@
+// build-fail
+
+// Constants are propagated through branches, loops and the fields of structs.
+// The code that is never reached is still linted, but without the values that
+// only hold on the way to it.
+
+struct Pair {
+    a: u8,
+    b: u8,
+}
+
+fn branches(c: bool) {
+    let x = if c { 255u8 } else { 255 };
+    let _y = x + 1; //~ ERROR this arithmetic operation will overflow
+}
+
+fn loops(n: usize) {
+    let arr = [0u8; 4];
+    let i = 4;
+    for _ in 0..n {}
+    let _y = arr[i]; //~ ERROR this operation will panic at runtime
+}
+
+fn fields() {
+    let mut p = Pair { a: 1, b: 200 };
+    p.a = 100;
+    let _y = p.a + p.b; //~ ERROR this arithmetic operation will overflow
+}
+
+fn dead_branch() {
+    let d = 0;
+    if d != 0 {
+        let _y = 1 / d;
+    }
+}
+
+fn dead_code() {
+    let debug = false;
+    if debug {
+        let _y = 255u8 + 1; //~ ERROR this arithmetic operation will overflow
+    }
+}
+
+fn main() {
+    branches(true);
+    loops(1);
+    fields();
+    dead_branch();
+    dead_code();
+}
@@ -0,0 +1,19 @@
+// compile-flags: -Z const-eval-profile
+// normalize-stderr-test "executed \d+ times" -> "executed N times"
+// normalize-stdout-test "\d+\.\d+ms" -> "Nms"
+#![feature(const_eval_limit)]
+#![const_eval_limit = "500"]
+
+const X: u32 = {
+    let mut x = 0;
+    while x != 1000 {
+        //~^ ERROR any use of this value will cause an error
+        x += 1;
+    }
+
+    x
+};
+
+fn main() {
+    assert_eq!(X, 1000);
+}
@@ -0,0 +1,15 @@
+// Checks that `-Z group-diagnostics` reports an error in a macro once for all its expansions.
+
+// compile-flags: -Z group-diagnostics
+
+macro_rules! fake_method_stmt {
+    () => {
+        1.fake() //~ ERROR no method
+    };
+}
+
+fn main() {
+    fake_method_stmt!();
+    fake_method_stmt!();
+    fake_method_stmt!();
+}
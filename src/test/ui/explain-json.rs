@@ -0,0 +1,2 @@
+// compile-flags: --explain E0591 --error-format=json
+// check-pass
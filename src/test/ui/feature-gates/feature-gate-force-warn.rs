@@ -0,0 +1,3 @@
+// compile-flags: --force-warn dead_code
+
+fn main() {}
@@ -0,0 +1,39 @@
+// check-pass
+
+#![feature(lint_reasons)]
+#![deny(dead_code, unfulfilled_lint_expectations)]
+
+// The items only used by an item with `#[expect(dead_code)]` are not reported,
+// as with `#[allow(dead_code)]`, but the expected lint is still emitted for the
+// item itself.
+#[expect(dead_code)]
+fn unused() {
+    used_by_unused();
+}
+
+fn used_by_unused() {}
+
+#[expect(dead_code)]
+struct Unused {
+    field: UsedByUnused,
+}
+
+struct UsedByUnused;
+
+struct Fields {
+    used: u8,
+    #[expect(dead_code)]
+    unused: u8,
+}
+
+enum Variants {
+    Used,
+    #[expect(dead_code)]
+    Unused,
+}
+
+fn main() {
+    let fields = Fields { used: 0, unused: 0 };
+    drop(fields.used);
+    drop(Variants::Used);
+}
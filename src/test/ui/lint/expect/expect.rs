@@ -0,0 +1,45 @@
+// check-pass
+
+#![feature(lint_reasons)]
+
+#[expect(unused_variables)]
+fn fulfilled() {
+    let x = 1;
+}
+
+#[expect(unused_variables, reason = "`x` used to be unused")]
+//~^ WARNING this lint expectation is unfulfilled
+fn unfulfilled() {
+    let x = 1;
+    drop(x);
+}
+
+#[expect(unused)]
+fn fulfilled_by_group_member() {
+    let x = 1;
+}
+
+#[expect(warnings)]
+fn fulfilled_by_any_warning() {
+    let x = 1;
+}
+
+#[expect(dead_code, unused_mut)]
+//~^ WARNING this lint expectation is unfulfilled
+fn partially_fulfilled() {
+    let mut x = 1;
+    drop(x);
+}
+
+#[allow(unfulfilled_lint_expectations)]
+#[expect(unused_variables)]
+fn unfulfilled_but_allowed() {}
+
+fn main() {
+    fulfilled();
+    unfulfilled();
+    fulfilled_by_group_member();
+    fulfilled_by_any_warning();
+    partially_fulfilled();
+    unfulfilled_but_allowed();
+}
@@ -0,0 +1,11 @@
+// Checks that `--force-warn` overrides an `#[allow]` attribute.
+
+// compile-flags: --force-warn dead_code -Zunstable-options
+// check-pass
+
+#![allow(dead_code)]
+
+fn dead_function() {}
+//~^ WARN function is never used
+
+fn main() {}
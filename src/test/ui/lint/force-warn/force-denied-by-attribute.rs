@@ -0,0 +1,12 @@
+// Checks that `--force-warn` never turns a lint into an error, neither through
+// a `#[deny]` attribute nor through `-D warnings`.
+
+// compile-flags: --force-warn dead_code -D warnings -Zunstable-options
+// check-pass
+
+#![deny(dead_code)]
+
+fn dead_function() {}
+//~^ WARN function is never used
+
+fn main() {}
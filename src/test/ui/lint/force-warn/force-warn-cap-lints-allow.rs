@@ -0,0 +1,12 @@
+// Checks that `--force-warn` overrides `--cap-lints allow` and `-A warnings`,
+// which otherwise disable all warnings.
+
+// compile-flags: --force-warn dead_code --cap-lints allow -A warnings -Zunstable-options
+// check-pass
+
+fn dead_function() {}
+//~^ WARN function is never used
+
+fn main() {
+    let unused = 0;
+}
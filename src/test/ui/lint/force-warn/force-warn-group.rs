@@ -0,0 +1,11 @@
+// Checks that `--force-warn` with a lint group overrides the attributes on
+// the lints of that group.
+
+// compile-flags: --force-warn unused -Zunstable-options
+// check-pass
+
+#[allow(unused_variables)]
+fn main() {
+    let x = 0;
+    //~^ WARN unused variable
+}
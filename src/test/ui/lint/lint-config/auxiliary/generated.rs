@@ -0,0 +1,3 @@
+fn never_used() {
+    let x = 1;
+}
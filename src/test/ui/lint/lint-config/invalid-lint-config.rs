@@ -0,0 +1,5 @@
+// Checks that errors in a lint configuration file are reported.
+
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config/invalid-lints.toml
+
+fn main() {}
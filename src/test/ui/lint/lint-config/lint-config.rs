@@ -0,0 +1,29 @@
+// Checks the precedence of the tables of a lint configuration file: `[lints]`, then
+// `[crate]`, then `[directory]` and `[module]`, and then lint attributes.
+
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config/lints.toml
+
+#[path = "auxiliary/generated.rs"]
+mod generated;
+
+mod allowed {
+    pub fn f() {
+        let x = 1;
+    }
+}
+
+mod attributes {
+    #![warn(unused_variables)]
+
+    pub fn f() {
+        let x = 1; //~ WARN unused variable: `x`
+    }
+}
+
+fn unused() {} //~ WARN function is never used: `unused`
+
+fn main() {
+    let y = 1; //~ ERROR unused variable: `y`
+    allowed::f();
+    attributes::f();
+}
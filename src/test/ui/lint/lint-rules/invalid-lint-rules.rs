@@ -0,0 +1,6 @@
+// Checks that the lints and the lint groups of a lint rules file must have
+// different names.
+
+// compile-flags: --lint-rules={{src-base}}/lint/lint-rules/conflicting-rules.toml -Zunstable-options
+
+fn main() {}
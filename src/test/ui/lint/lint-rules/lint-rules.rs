@@ -0,0 +1,27 @@
+// Checks the lints defined with `--lint-rules`.
+
+// compile-flags: --lint-rules={{src-base}}/lint/lint-rules/rules.toml -Zunstable-options
+// check-pass
+
+#![warn(rules::rc)]
+// Lints of the namespace that are not defined are ignored.
+#![deny(rules::undefined)]
+
+use std::rc::Rc;
+
+fn f(x: Option<u8>) -> u8 {
+    x.unwrap() //~ WARN called `Option::unwrap`
+}
+
+#[allow(rules::acme)]
+fn g(x: Option<u8>) -> Rc<u8> {
+    Rc::new(x.unwrap())
+}
+
+fn main() {
+    let _: Rc<u8> = Rc::new(f(Some(1)));
+    //~^ WARN used `Rc`
+    //~| WARN used `Rc`
+    g(None);
+    std::process::exit(0); //~ WARN called a function of `std::process`
+}
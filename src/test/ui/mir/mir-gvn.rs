@@ -0,0 +1,45 @@
+// Checks that global value numbering keeps the results of the computations
+// that it removes, and the checks that can still fail.
+
+// run-pass
+// ignore-wasm32-bare compiled with panic=abort by default
+// compile-flags: -Z mir-opt-level=2
+
+fn sum_twice(s: &[u32], i: usize) -> u32 {
+    // The second bounds check is the same as the first.
+    s[i] + s[i]
+}
+
+fn checked(a: u8, b: u8) -> Option<u8> {
+    if a > 100 {
+        return None;
+    }
+    let x = a + b;
+    let y = a + b;
+    Some(x.wrapping_mul(y))
+}
+
+fn branches(c: bool, a: i32, b: i32) -> i32 {
+    // Only the computations that dominate another one are reused.
+    let x = if c { a * b } else { b * a + 1 };
+    x + a * b
+}
+
+fn fields(p: (u32, u32), mut q: (u32, u32)) -> u32 {
+    let x = p.0 + q.1;
+    q.1 = 7;
+    let y = p.0 + q.1;
+    x * 10 + y
+}
+
+fn main() {
+    let s = [1, 2, 3];
+    assert_eq!(sum_twice(&s, 1), 4);
+    assert!(std::panic::catch_unwind(|| sum_twice(&s, 3)).is_err());
+    assert_eq!(checked(3, 4), Some(49));
+    assert_eq!(checked(101, 4), None);
+    assert!(std::panic::catch_unwind(|| checked(100, 200)).is_err());
+    assert_eq!(branches(true, 3, 4), 24);
+    assert_eq!(branches(false, 3, 4), 25);
+    assert_eq!(fields((1, 2), (3, 4)), 58);
+}
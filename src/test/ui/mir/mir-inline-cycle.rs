@@ -0,0 +1,49 @@
+// Checks that the MIR inliner does not inline functions into the functions
+// that they (transitively) call, which would be a query cycle, and that it
+// does not inline recursive functions into themselves forever.
+
+// run-pass
+// compile-flags: -Z inline-mir -Z inline-mir-threshold=1000 -Z inline-mir-hint-threshold=1000
+
+#[inline]
+fn even(n: u32) -> bool {
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+#[inline]
+fn odd(n: u32) -> bool {
+    if n == 0 { false } else { even(n - 1) }
+}
+
+#[inline]
+fn factorial(n: u64) -> u64 {
+    if n == 0 { 1 } else { n * factorial(n - 1) }
+}
+
+trait Depth {
+    fn depth(&self) -> usize;
+}
+
+impl Depth for () {
+    fn depth(&self) -> usize {
+        0
+    }
+}
+
+impl<T: Depth> Depth for Option<T> {
+    #[inline]
+    fn depth(&self) -> usize {
+        match self {
+            Some(inner) => 1 + inner.depth(),
+            None => 1,
+        }
+    }
+}
+
+fn main() {
+    assert!(even(10));
+    assert!(odd(7));
+    assert_eq!(factorial(5), 120);
+    assert_eq!(Some(Some(())).depth(), 3);
+    assert_eq!(vec![1, 2, 3].iter().map(|x| x * 2).sum::<i32>(), 12);
+}
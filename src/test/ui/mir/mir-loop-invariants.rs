@@ -0,0 +1,98 @@
+// Checks that loop-invariant code motion and the removal of redundant bounds
+// checks keep the results of the loops, and the checks that can still fail.
+
+// run-pass
+// ignore-wasm32-bare compiled with panic=abort by default
+// compile-flags: -Z mir-opt-level=1
+
+fn sum(s: &[u32]) -> u32 {
+    // The bounds check is implied by the loop condition.
+    let mut total = 0;
+    let mut i = 0;
+    while i < s.len() {
+        total += s[i];
+        i += 1;
+    }
+    total
+}
+
+fn sum_skipping(s: &[u32]) -> u32 {
+    // The index changes between the loop condition and the bounds check.
+    let mut total = 0;
+    let mut i = 0;
+    while i < s.len() {
+        i += 1;
+        total += s[i];
+    }
+    total
+}
+
+fn reversed(s: &[u32]) -> u32 {
+    let mut total = 0;
+    let mut i = s.len();
+    while 0 < i {
+        i -= 1;
+        if s.len() > i {
+            total = total * 10 + s[i];
+        }
+    }
+    total
+}
+
+fn count_some(options: &[Option<u8>], kind: Option<u8>) -> usize {
+    // The discriminant of `kind` is the same in every iteration.
+    let mut count = 0;
+    for option in options {
+        if kind.is_some() && option.is_some() {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn nested(a: &[u32], b: &[u32]) -> u32 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < a.len() {
+        let mut j = 0;
+        while j < b.len() {
+            total += a[i] * b[j];
+            j += 1;
+        }
+        i += 1;
+    }
+    total
+}
+
+fn not_entered(s: &[u32], n: usize) -> u32 {
+    // The loop is never entered, so its division cannot fail.
+    let mut total = 0;
+    for _ in 0..n {
+        total += s[0] / s.len() as u32;
+    }
+    total
+}
+
+fn continue_to_start(v: &[u8], i: usize) -> u8 {
+    // The loop starts the function, so the condition on the edge back to its
+    // start does not hold when the function is entered.
+    loop {
+        if i < v.len() {
+            continue;
+        }
+        return v[i];
+    }
+}
+
+fn main() {
+    let s = [1, 2, 3];
+    assert_eq!(sum(&s), 6);
+    assert_eq!(sum(&[]), 0);
+    assert!(std::panic::catch_unwind(|| sum_skipping(&s)).is_err());
+    assert_eq!(reversed(&s), 321);
+    assert_eq!(count_some(&[Some(1), None, Some(2)], Some(0)), 2);
+    assert_eq!(count_some(&[Some(1), None, Some(2)], None), 0);
+    assert_eq!(nested(&s, &[10, 100]), 660);
+    assert_eq!(not_entered(&[], 0), 0);
+    assert!(std::panic::catch_unwind(|| continue_to_start(&[1, 2], 2)).is_err());
+}
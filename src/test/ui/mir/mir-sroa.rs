@@ -0,0 +1,54 @@
+// Checks that splitting the locals of aggregates into their fields keeps the
+// values of the fields.
+
+// run-pass
+// compile-flags: -Z mir-opt-level=1
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Line {
+    start: Point,
+    end: Point,
+}
+
+struct Named {
+    name: String,
+    len: usize,
+}
+
+fn length(line: Line) -> i32 {
+    let temp = Line { start: line.start, end: Point { x: line.end.x, y: 0 } };
+    (temp.end.x - temp.start.x).abs() + (line.end.y - temp.start.y).abs()
+}
+
+fn swap(a: u8, b: u8) -> (u8, u8) {
+    let mut pair = (a, b);
+    let first = pair.0;
+    pair.0 = pair.1;
+    pair.1 = first;
+    (pair.0, pair.1)
+}
+
+fn borrowed_field(a: i32) -> i32 {
+    let mut p = Point { x: a, y: 1 };
+    let r = &mut p.y;
+    *r += 10;
+    p.x + p.y
+}
+
+fn with_drop(s: &str) -> (String, usize) {
+    let named = Named { name: s.to_string(), len: s.len() };
+    (named.name, named.len)
+}
+
+fn main() {
+    let line = Line { start: Point { x: 1, y: 2 }, end: Point { x: 4, y: 6 } };
+    assert_eq!(length(line), 7);
+    assert_eq!(swap(1, 2), (2, 1));
+    assert_eq!(borrowed_field(5), 16);
+    assert_eq!(with_drop("abc"), ("abc".to_string(), 3));
+}
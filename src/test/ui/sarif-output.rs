@@ -0,0 +1,6 @@
+// check-pass
+// compile-flags: --error-format=sarif -Z unstable-options -W unused-variables
+
+fn main() {
+    let x = 1;
+}
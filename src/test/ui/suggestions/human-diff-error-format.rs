@@ -0,0 +1,7 @@
+// compile-flags: --error-format=human-diff -Z unstable-options
+
+fn main() {
+    let s = String::new();
+    let x: &str = s; //~ ERROR mismatched types
+    println!("{}", x);
+}
@@ -0,0 +1,6 @@
+// Checks that a locale without a catalog in the sysroot is rejected.
+
+// compile-flags: -Z translation-locale=xx-unknown
+// normalize-stderr-test "in `.*`" -> "in `SYSROOT/share/locale/xx-unknown`"
+
+fn main() {}